@@ -0,0 +1,808 @@
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use jobtracker_core::local_timezone;
+use jobtracker_core::normalize_tag;
+use jobtracker_core::{
+    Config, Contact, Interview, InterviewOutcome, Job, JobSource, JobStatus, JobStore, Location,
+    Offer, PayPeriod, SalaryRange, Source, WorkArrangement,
+};
+use std::fs::File;
+use std::path::PathBuf;
+
+mod output;
+
+/// Command-line access to the job application tracker.
+#[derive(Parser)]
+#[command(name = "jobtracker-cli")]
+struct Cli {
+    /// Data file to use. Overrides the JOBTRACKER_DATA environment variable
+    /// and the config file.
+    #[arg(long, global = true)]
+    data_file: Option<PathBuf>,
+    /// How to print results.
+    #[arg(long, global = true, value_enum, default_value_t = Format::Table)]
+    format: Format,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Log a new application.
+    Add {
+        company: String,
+        role: String,
+        #[arg(long)]
+        location: Option<String>,
+        /// Where the posting was found, e.g. LinkedIn or Indeed. Has to be
+        /// one of the sources listed by `source list`, or one of their
+        /// aliases.
+        #[arg(long)]
+        source: Option<String>,
+        /// ID of the contact who referred you, for a referral source.
+        #[arg(long)]
+        referrer: Option<u32>,
+    },
+    /// Read an application from a saved job posting page (.html). Shows what
+    /// was found; pass --add to add it.
+    ImportPosting {
+        path: PathBuf,
+        /// Add the application rather than only showing it.
+        #[arg(long)]
+        add: bool,
+        /// Use instead of the company found in the page.
+        #[arg(long)]
+        company: Option<String>,
+        /// Use instead of the role found in the page.
+        #[arg(long)]
+        role: Option<String>,
+        /// Use instead of the location found in the page.
+        #[arg(long)]
+        location: Option<String>,
+    },
+    /// List applications, optionally only those in one status, with
+    /// certain tags or in one place.
+    List {
+        #[arg(long)]
+        status: Option<JobStatus>,
+        /// Only applications with this tag. Can be given more than once.
+        #[arg(long)]
+        tag: Vec<String>,
+        /// Only applications worked this way: on-site, hybrid or remote.
+        #[arg(long)]
+        arrangement: Option<WorkArrangement>,
+        /// Only applications in this region or country, e.g. TX or Germany.
+        #[arg(long)]
+        region: Option<String>,
+        /// List the trash instead.
+        #[arg(long)]
+        trash: bool,
+    },
+    /// Move an application to a new status.
+    UpdateStatus {
+        id: u32,
+        status: JobStatus,
+        /// Note to record with the status change.
+        #[arg(long)]
+        note: Option<String>,
+        /// Allow a move the pipeline's rules don't, to correct a mistake.
+        #[arg(long)]
+        correction: bool,
+    },
+    /// Change where an application was found. Leave out the source to
+    /// clear it.
+    UpdateSource {
+        id: u32,
+        source: Option<JobSource>,
+        /// ID of the contact who referred you, for a referral source.
+        #[arg(long)]
+        referrer: Option<u32>,
+    },
+    /// Move an application to the trash.
+    Delete {
+        id: u32,
+        /// Delete it for good instead of moving it to the trash.
+        #[arg(long)]
+        permanent: bool,
+    },
+    /// Add a note to an application, or with no text, show its notes.
+    Note { id: u32, text: Option<String> },
+    /// Change where an application is, or with nothing given, show how its
+    /// location was read.
+    Location {
+        id: u32,
+        /// Where it is as free text, e.g. "Austin, TX (hybrid)". Parsed
+        /// into the fields below.
+        text: Option<String>,
+        /// Correct the parsed city. Empty to clear it.
+        #[arg(long)]
+        city: Option<String>,
+        /// Correct the parsed state or province. Empty to clear it.
+        #[arg(long)]
+        region: Option<String>,
+        /// Correct the parsed country. Empty to clear it.
+        #[arg(long)]
+        country: Option<String>,
+        /// Correct the work arrangement: on-site, hybrid or remote.
+        #[arg(long)]
+        arrangement: Option<WorkArrangement>,
+    },
+    /// Set the link to an application's posting or archive its description.
+    /// With neither given, show both.
+    Posting {
+        id: u32,
+        /// Empty to clear it.
+        #[arg(long)]
+        url: Option<String>,
+        /// Description text. Empty to clear it.
+        #[arg(long, conflicts_with = "description_file")]
+        description: Option<String>,
+        /// Read the description from this file.
+        #[arg(long)]
+        description_file: Option<PathBuf>,
+    },
+    /// Set the pay range from an application's posting. Leave out the
+    /// amounts to clear it.
+    SalaryRange {
+        id: u32,
+        min: Option<f64>,
+        max: Option<f64>,
+        #[arg(long, default_value = "USD")]
+        currency: String,
+        /// Hour, month or year.
+        #[arg(long, default_value = "year")]
+        period: PayPeriod,
+    },
+    /// Label applications with free-form tags.
+    #[command(subcommand)]
+    Tag(TagCommand),
+    /// Record offers and compare them.
+    #[command(subcommand)]
+    Offer(OfferCommand),
+    /// Take an application back out of the trash.
+    Restore { id: u32 },
+    /// Print summary counts over all applications, and broken down by tag,
+    /// work arrangement, region and source.
+    Stats,
+    /// Show how many applications got to each stage, and the path each
+    /// took from its source to where it ended up.
+    Flow,
+    /// List the pipeline stages applications move through.
+    Stages,
+    /// Manage the places applications can come from.
+    #[command(subcommand)]
+    Source(SourceCommand),
+    /// List applications whose company, role, status, location, notes or
+    /// posting contain the given text.
+    Search { text: String },
+    /// Manage recruiters, hiring managers and other contacts.
+    #[command(subcommand)]
+    Contact(ContactCommand),
+    /// Schedule interviews and move them in and out of calendars.
+    #[command(subcommand)]
+    Interview(InterviewCommand),
+    /// Revert the most recent change.
+    Undo,
+    /// Re-apply the most recently undone change.
+    Redo,
+}
+
+#[derive(Subcommand)]
+enum ContactCommand {
+    /// List contacts, optionally only those linked to one application.
+    List {
+        #[arg(long)]
+        job: Option<u32>,
+    },
+    /// Add a contact.
+    Add {
+        name: String,
+        #[command(flatten)]
+        details: ContactDetails,
+        /// Application to link them to. Can be given more than once.
+        #[arg(long)]
+        job: Vec<u32>,
+    },
+    /// Change a contact's details. Anything not given stays as it is.
+    Edit {
+        id: u32,
+        #[arg(long)]
+        name: Option<String>,
+        #[command(flatten)]
+        details: ContactDetails,
+    },
+    /// Delete a contact. The applications they're linked to stay.
+    Delete { id: u32 },
+    /// Link a contact to an application.
+    Link { id: u32, job: u32 },
+    /// Remove the link between a contact and an application.
+    Unlink { id: u32, job: u32 },
+    /// Add the contacts in a vCard (.vcf) file, skipping known ones.
+    Import { path: PathBuf },
+    /// Write every contact to a vCard (.vcf) file.
+    Export { path: PathBuf },
+}
+
+#[derive(Subcommand)]
+enum SourceCommand {
+    /// List every source with its aliases.
+    List,
+    /// Add a source to the end of the list.
+    Add {
+        name: String,
+        /// Another name to recognize it by. Can be given more than once.
+        #[arg(long)]
+        alias: Vec<String>,
+        /// Applications from it were referred by someone.
+        #[arg(long)]
+        referral: bool,
+    },
+    /// Remove a source no application came from.
+    Remove { name: String },
+}
+
+#[derive(Subcommand)]
+enum TagCommand {
+    /// List every tag in use.
+    List,
+    /// Tag an application.
+    Add {
+        id: u32,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// Take tags off an application.
+    Remove {
+        id: u32,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum OfferCommand {
+    /// Record the terms an application's offer came with, replacing any
+    /// recorded before.
+    Set {
+        id: u32,
+        /// Base pay per period.
+        #[arg(long)]
+        base: f64,
+        /// Hour, month or year.
+        #[arg(long, default_value = "year")]
+        period: PayPeriod,
+        /// Expected bonus per year.
+        #[arg(long)]
+        bonus: Option<f64>,
+        /// Value of the whole equity grant.
+        #[arg(long)]
+        equity: Option<f64>,
+        /// Years the equity vests over. Four when not given.
+        #[arg(long)]
+        vesting_years: Option<u32>,
+        #[arg(long, default_value = "USD")]
+        currency: String,
+        #[arg(long)]
+        benefits: Option<String>,
+        /// Date to accept by, e.g. 2024-05-01.
+        #[arg(long)]
+        deadline: Option<NaiveDate>,
+    },
+    /// Forget an application's offer details.
+    Clear { id: u32 },
+    /// Line up every application with an offer, totals converted to a
+    /// yearly amount in the configured base currency.
+    Compare,
+}
+
+#[derive(Subcommand)]
+enum InterviewCommand {
+    /// List upcoming interviews, or every interview of one application.
+    List {
+        #[arg(long)]
+        job: Option<u32>,
+    },
+    /// Schedule an interview for an application.
+    Add {
+        job: u32,
+        /// Which round it is, e.g. "Phone screen".
+        round: String,
+        /// Local start time, e.g. "2024-05-01 14:00".
+        #[arg(long)]
+        start: String,
+        #[arg(long, default_value_t = 60)]
+        minutes: i64,
+        /// IANA time zone the start time is in. Defaults to this
+        /// computer's time zone.
+        #[arg(long)]
+        timezone: Option<Tz>,
+        /// Can be given more than once.
+        #[arg(long)]
+        interviewer: Vec<String>,
+        /// Address or meeting link.
+        #[arg(long)]
+        location: Option<String>,
+    },
+    /// Record how an interview went. Leave out the outcome to mark it
+    /// pending again.
+    Outcome {
+        job: u32,
+        id: u32,
+        outcome: Option<InterviewOutcome>,
+    },
+    /// Delete an interview.
+    Delete { job: u32, id: u32 },
+    /// Add the events in an iCalendar (.ics) file as interviews, matched to
+    /// applications by company name.
+    Import {
+        path: PathBuf,
+        /// Add every event to this application instead.
+        #[arg(long)]
+        job: Option<u32>,
+    },
+    /// Write every interview to an iCalendar (.ics) file.
+    Export { path: PathBuf },
+}
+
+#[derive(Args)]
+struct ContactDetails {
+    #[arg(long)]
+    email: Option<String>,
+    #[arg(long)]
+    phone: Option<String>,
+    /// What they do, e.g. Recruiter.
+    #[arg(long)]
+    role: Option<String>,
+    #[arg(long)]
+    company: Option<String>,
+    #[arg(long)]
+    notes: Option<String>,
+}
+
+impl ContactDetails {
+    /// Overwrites the fields of `contact` that were given.
+    fn apply(self, contact: &mut Contact) {
+        let fields = [
+            (self.email, &mut contact.email),
+            (self.phone, &mut contact.phone),
+            (self.role, &mut contact.role),
+            (self.company, &mut contact.company),
+            (self.notes, &mut contact.notes),
+        ];
+        for (value, field) in fields {
+            if value.is_some() {
+                *field = value;
+            }
+        }
+    }
+}
+
+fn run_contact_command(
+    store: &mut JobStore,
+    command: ContactCommand,
+    format: Format,
+) -> Result<()> {
+    match command {
+        ContactCommand::List { job } => {
+            let contacts: Vec<Contact> = match job {
+                Some(job) => store.contacts_for_job(job).cloned().collect(),
+                None => store.contacts.clone(),
+            };
+            output::print_contacts(&contacts, format)?;
+        }
+        ContactCommand::Add { name, details, job } => {
+            let mut contact = Contact {
+                name,
+                job_ids: job,
+                ..Default::default()
+            };
+            details.apply(&mut contact);
+            let id = store.add_contact(contact)?;
+            output::print_contacts(std::slice::from_ref(store.find_contact(id)?), format)?;
+        }
+        ContactCommand::Edit { id, name, details } => {
+            let mut contact = store.find_contact(id)?.clone();
+            if let Some(name) = name {
+                contact.name = name;
+            }
+            details.apply(&mut contact);
+            store.update_contact(contact)?;
+            output::print_contacts(std::slice::from_ref(store.find_contact(id)?), format)?;
+        }
+        ContactCommand::Delete { id } => {
+            let contact = store.find_contact(id)?.clone();
+            store.delete_contact(id)?;
+            output::print_contacts(&[contact], format)?;
+        }
+        ContactCommand::Link { id, job } => {
+            store.link_contact(id, job)?;
+            output::print_contacts(std::slice::from_ref(store.find_contact(id)?), format)?;
+        }
+        ContactCommand::Unlink { id, job } => {
+            store.unlink_contact(id, job)?;
+            output::print_contacts(std::slice::from_ref(store.find_contact(id)?), format)?;
+        }
+        ContactCommand::Import { path } => {
+            let report = store.import_vcf(File::open(&path)?)?;
+            output::print_contact_import(&report, format)?;
+        }
+        ContactCommand::Export { path } => {
+            store.export_vcf(File::create(&path)?)?;
+            output::print_contacts(&store.contacts, format)?;
+        }
+    }
+    Ok(())
+}
+
+fn run_offer_command(store: &mut JobStore, command: OfferCommand, format: Format) -> Result<()> {
+    match command {
+        OfferCommand::Set {
+            id,
+            base,
+            period,
+            bonus,
+            equity,
+            vesting_years,
+            currency,
+            benefits,
+            deadline,
+        } => {
+            let offer = Offer {
+                base,
+                period,
+                bonus,
+                equity,
+                vesting_years,
+                currency,
+                benefits,
+                deadline,
+            };
+            store.set_offer(id, Some(offer))?;
+        }
+        OfferCommand::Clear { id } => store.set_offer(id, None)?,
+        OfferCommand::Compare => {}
+    }
+    output::print_offers(&store.offer_comparison(), &store.currency.base, format)
+}
+
+fn run_interview_command(
+    store: &mut JobStore,
+    command: InterviewCommand,
+    format: Format,
+) -> Result<()> {
+    match command {
+        InterviewCommand::List { job: Some(id) } => {
+            let job = find_job(store, id)?;
+            let mut interviews: Vec<(&Job, &Interview)> = job
+                .interviews
+                .iter()
+                .map(|interview| (job, interview))
+                .collect();
+            interviews.sort_by_key(|(_, interview)| interview.start);
+            output::print_interviews(&interviews, format)?;
+        }
+        InterviewCommand::List { job: None } => {
+            output::print_interviews(&store.upcoming_interviews(Utc::now()), format)?;
+        }
+        InterviewCommand::Add {
+            job,
+            round,
+            start,
+            minutes,
+            timezone,
+            interviewer,
+            location,
+        } => {
+            let start = NaiveDateTime::parse_from_str(&start, "%Y-%m-%d %H:%M")
+                .map_err(|_| anyhow!("Start must look like 2024-05-01 14:00, got {start}"))?;
+            let timezone = timezone.map_or_else(local_timezone, Ok)?;
+            let interview = Interview {
+                interviewers: interviewer,
+                location,
+                ..Interview::new(round, start, minutes, timezone)?
+            };
+            let id = store.add_interview(job, interview)?;
+            print_interview(store, job, id, format)?;
+        }
+        InterviewCommand::Outcome { job, id, outcome } => {
+            store.set_interview_outcome(job, id, outcome)?;
+            print_interview(store, job, id, format)?;
+        }
+        InterviewCommand::Delete { job, id } => {
+            print_interview(store, job, id, format)?;
+            store.delete_interview(job, id)?;
+        }
+        InterviewCommand::Import { path, job } => {
+            let report = store.import_ics(File::open(&path)?, job)?;
+            output::print_calendar_import(store, &report, format)?;
+        }
+        InterviewCommand::Export { path } => {
+            store.export_ics(File::create(&path)?, Utc::now())?;
+            let count: usize = store.active_jobs().map(|job| job.interviews.len()).sum();
+            if format == Format::Table {
+                println!("Exported {count} interviews to {}", path.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_interview(store: &JobStore, job: u32, id: u32, format: Format) -> Result<()> {
+    let job = find_job(store, job)?;
+    let interview = job
+        .interviews
+        .iter()
+        .find(|interview| interview.id == id)
+        .ok_or_else(|| anyhow!("Job {} has no interview {id}", job.id))?;
+    output::print_interviews(&[(job, interview)], format)
+}
+
+/// Tags of job `id`, each with how many applications have it.
+fn job_tags(store: &JobStore, id: u32) -> Result<Vec<(String, usize)>> {
+    let job = find_job(store, id)?;
+    Ok(store
+        .all_tags()
+        .into_iter()
+        .filter(|(tag, _)| job.tags.contains(tag))
+        .collect())
+}
+
+fn find_job(store: &JobStore, id: u32) -> Result<&Job> {
+    store
+        .jobs
+        .iter()
+        .find(|job| job.id == id)
+        .ok_or_else(|| anyhow!("No job application with ID {id}"))
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let (mut store, warning) = Config::load()?.open_store(cli.data_file)?;
+    if let Some(warning) = warning {
+        eprintln!("{warning}");
+    }
+    store.load()?;
+
+    match cli.command {
+        Command::Add {
+            company,
+            role,
+            location,
+            source,
+            referrer,
+        } => {
+            let job = store.add_draft(Job {
+                company,
+                role,
+                role_location: location.filter(|location| !location.trim().is_empty()),
+                source: source.map(JobSource::new),
+                referrer,
+                ..Job::default()
+            })?;
+            output::print_jobs(&[job], cli.format)?;
+        }
+        Command::ImportPosting {
+            path,
+            add,
+            company,
+            role,
+            location,
+        } => {
+            let mut draft = Job::from_posting_html(File::open(&path)?)?;
+            draft.company = company.unwrap_or(draft.company);
+            draft.role = role.unwrap_or(draft.role);
+            draft.role_location = location.or(draft.role_location);
+            if add {
+                draft = store.add_draft(draft)?;
+            }
+            output::print_posting_draft(&draft, add, cli.format)?;
+        }
+        Command::List {
+            status,
+            tag,
+            arrangement,
+            region,
+            trash,
+        } => {
+            let status = status
+                .map(|status| store.parse_status(status.as_str()))
+                .transpose()?;
+            let tags: Vec<String> = tag.iter().filter_map(|tag| normalize_tag(tag)).collect();
+            let jobs = if trash {
+                store.trashed_jobs().into_iter().cloned().collect()
+            } else {
+                store.list_jobs()?
+            };
+            let jobs: Vec<Job> = jobs
+                .into_iter()
+                .filter(|job| status.as_ref().is_none_or(|s| &job.status == s))
+                .filter(|job| job.has_tags(&tags))
+                .filter(|job| arrangement.is_none_or(|a| job.location.arrangement == Some(a)))
+                .filter(|job| region.as_ref().is_none_or(|region| job.in_area(region)))
+                .collect();
+            output::print_jobs(&jobs, cli.format)?;
+        }
+        Command::UpdateStatus {
+            id,
+            status,
+            note,
+            correction,
+        } => {
+            find_job(&store, id)?;
+            store.update_status_with_note(id, status, note, correction)?;
+            output::print_jobs(std::slice::from_ref(find_job(&store, id)?), cli.format)?;
+        }
+        Command::UpdateSource {
+            id,
+            source,
+            referrer,
+        } => {
+            find_job(&store, id)?;
+            if referrer.is_some() {
+                store.set_source_and_referrer(id, source, referrer)?;
+            } else {
+                store.update_source(id, source)?;
+            }
+            output::print_jobs(std::slice::from_ref(find_job(&store, id)?), cli.format)?;
+        }
+        Command::Delete { id, permanent } => {
+            let job = find_job(&store, id)?.clone();
+            if permanent {
+                store.purge_job(id)?;
+            } else {
+                store.delete_job(id)?;
+            }
+            output::print_jobs(&[job], cli.format)?;
+        }
+        Command::Note { id, text } => {
+            if let Some(text) = text {
+                store.add_note(id, text)?;
+            }
+            output::print_notes(find_job(&store, id)?, cli.format)?;
+        }
+        Command::Location {
+            id,
+            text,
+            city,
+            region,
+            country,
+            arrangement,
+        } => {
+            let corrected =
+                city.is_some() || region.is_some() || country.is_some() || arrangement.is_some();
+            let correct = |location: Location| Location {
+                city: city.or(location.city),
+                region: region.or(location.region),
+                country: country.or(location.country),
+                arrangement: arrangement.or(location.arrangement),
+            };
+            // Text and corrections together are one change, undone as one
+            match text {
+                Some(text) => {
+                    let correction = corrected.then(|| correct(Location::parse(&text)));
+                    store.update_location(id, &text, correction)?;
+                }
+                None if corrected => {
+                    let location = correct(find_job(&store, id)?.location.clone());
+                    store.set_location(id, location)?;
+                }
+                None => {}
+            }
+            output::print_location(find_job(&store, id)?, cli.format)?;
+        }
+        Command::Posting {
+            id,
+            url,
+            description,
+            description_file,
+        } => {
+            find_job(&store, id)?;
+            if let Some(url) = url {
+                store.update_posting_url(id, url)?;
+            }
+            let description = match description_file {
+                Some(path) => Some(
+                    std::fs::read_to_string(&path)
+                        .map_err(|e| anyhow!("Could not read {}: {e}", path.display()))?,
+                ),
+                None => description,
+            };
+            if let Some(description) = description {
+                store.update_description(id, description)?;
+            }
+            output::print_posting(find_job(&store, id)?, cli.format)?;
+        }
+        Command::SalaryRange {
+            id,
+            min,
+            max,
+            currency,
+            period,
+        } => {
+            let range = min.map(|min| SalaryRange {
+                min,
+                max: max.unwrap_or(min),
+                currency,
+                period,
+            });
+            store.set_salary_range(id, range)?;
+            output::print_jobs(std::slice::from_ref(find_job(&store, id)?), cli.format)?;
+        }
+        Command::Offer(command) => run_offer_command(&mut store, command, cli.format)?,
+        Command::Tag(TagCommand::List) => output::print_tags(&store.all_tags(), cli.format)?,
+        Command::Tag(TagCommand::Add { id, tags }) => {
+            store.add_tags(id, tags.iter().flat_map(|tags| tags.split(',')))?;
+            output::print_tags(&job_tags(&store, id)?, cli.format)?;
+        }
+        Command::Tag(TagCommand::Remove { id, tags }) => {
+            store.remove_tags(id, tags.iter().flat_map(|tags| tags.split(',')))?;
+            output::print_tags(&job_tags(&store, id)?, cli.format)?;
+        }
+        Command::Restore { id } => {
+            find_job(&store, id)?;
+            store.restore_job(id)?;
+            output::print_jobs(std::slice::from_ref(find_job(&store, id)?), cli.format)?;
+        }
+        Command::Stats => {
+            store.calculate_summary_stats()?;
+            output::print_stats(&store.summary_stats, cli.format)?;
+        }
+        Command::Flow => output::print_flow(&store.pipeline_flow(), cli.format)?,
+        Command::Stages => output::print_stages(&store.stages, cli.format)?,
+        Command::Source(SourceCommand::List) => output::print_sources(&store.sources, cli.format)?,
+        Command::Source(SourceCommand::Add {
+            name,
+            alias,
+            referral,
+        }) => {
+            store.add_source(Source {
+                name: JobSource::new(name.trim()),
+                aliases: alias,
+                referral,
+            })?;
+            output::print_sources(&store.sources, cli.format)?;
+        }
+        Command::Source(SourceCommand::Remove { name }) => {
+            store.remove_source(&name)?;
+            output::print_sources(&store.sources, cli.format)?;
+        }
+        Command::Search { text } => {
+            let text = text.to_lowercase();
+            let jobs: Vec<Job> = store
+                .list_jobs()?
+                .into_iter()
+                .filter(|job| job.matches_search(&text))
+                .collect();
+            output::print_jobs(&jobs, cli.format)?;
+        }
+        Command::Contact(command) => run_contact_command(&mut store, command, cli.format)?,
+        Command::Interview(command) => run_interview_command(&mut store, command, cli.format)?,
+        Command::Undo => {
+            let label = store.undo()?;
+            output::print_undo(
+                false,
+                label.as_deref(),
+                store.undo_history.path.is_some(),
+                cli.format,
+            )?;
+        }
+        Command::Redo => {
+            let label = store.redo()?;
+            output::print_undo(
+                true,
+                label.as_deref(),
+                store.undo_history.path.is_some(),
+                cli.format,
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,575 @@
+use crate::Format;
+use anyhow::Result;
+use jobtracker_core::{
+    format_amount, AreaCounts, ArrangementCounts, CalendarImport, Contact, ContactImport,
+    Interview, Job, JobStore, OfferComparison, PipelineFlow, Source, SourceCounts, Stage,
+    SummaryCounts, TagCounts,
+};
+use serde_json::json;
+
+const HEADERS: [&str; 8] = [
+    "ID",
+    "Date Applied",
+    "Company",
+    "Role",
+    "Location",
+    "Status",
+    "Source",
+    "Tags",
+];
+
+fn job_row(job: &Job) -> [String; 8] {
+    [
+        job.id.to_string(),
+        job.timestamp.format("%Y-%m-%d %H:%M").to_string(),
+        job.company.clone(),
+        job.role.clone(),
+        job.role_location.clone().unwrap_or("N/A".to_string()),
+        job.status.to_string(),
+        job.source
+            .as_ref()
+            .map_or("Not provided".to_string(), ToString::to_string),
+        job.tags.iter().cloned().collect::<Vec<_>>().join(", "),
+    ]
+}
+
+/// Prints `rows` as left-aligned columns under `headers`.
+pub fn print_table<const N: usize>(headers: [&str; N], rows: &[[String; N]]) {
+    let mut widths = headers.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: &[&str]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+    let separators = widths.map(|width| "-".repeat(width));
+    line(&headers);
+    line(&separators.each_ref().map(String::as_str));
+    for row in rows {
+        line(&row.each_ref().map(String::as_str));
+    }
+}
+
+pub fn print_jobs(jobs: &[Job], format: Format) -> Result<()> {
+    match format {
+        Format::Table => {
+            let rows: Vec<[String; 8]> = jobs.iter().map(job_row).collect();
+            print_table(HEADERS, &rows);
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(jobs)?),
+    }
+    Ok(())
+}
+
+pub fn print_notes(job: &Job, format: Format) -> Result<()> {
+    match format {
+        Format::Table => {
+            for note in &job.notes {
+                println!("{}  #{}", note.at.format("%Y-%m-%d %H:%M"), note.id);
+                println!("{}\n", note.text);
+            }
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(&job.notes)?),
+    }
+    Ok(())
+}
+
+/// A job read from a posting page, `added` or still a draft.
+pub fn print_posting_draft(job: &Job, added: bool, format: Format) -> Result<()> {
+    match format {
+        Format::Table => {
+            let found = |value: &str| {
+                if value.is_empty() {
+                    "(not found)".to_string()
+                } else {
+                    value.to_string()
+                }
+            };
+            if added {
+                println!("Added application {}", job.id);
+            }
+            println!("Company:  {}", found(&job.company));
+            println!("Role:     {}", found(&job.role));
+            println!(
+                "Location: {}",
+                found(job.role_location.as_deref().unwrap_or_default())
+            );
+            println!(
+                "Source:   {}",
+                found(job.source.as_ref().map(|s| s.as_str()).unwrap_or_default())
+            );
+            if let Some(range) = &job.salary_range {
+                println!(
+                    "Salary:   {} {} - {} per {}",
+                    range.currency,
+                    format_amount(range.min),
+                    format_amount(range.max),
+                    range.period.to_string().to_lowercase()
+                );
+            }
+            if let Some(url) = &job.posting_url {
+                println!("URL:      {url}");
+            }
+            if let Some(description) = &job.description {
+                println!("\n{description}");
+            }
+            if !added {
+                println!("\nNot added yet. Run again with --add to add it.");
+            }
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(job)?),
+    }
+    Ok(())
+}
+
+pub fn print_posting(job: &Job, format: Format) -> Result<()> {
+    match format {
+        Format::Table => {
+            println!("{}", job.posting_url.as_deref().unwrap_or("No posting URL"));
+            if let Some(description) = &job.description {
+                println!("\n{description}");
+            }
+        }
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({
+                "id": job.id,
+                "posting_url": job.posting_url,
+                "description": job.description,
+            }))?
+        ),
+    }
+    Ok(())
+}
+
+pub fn print_contacts(contacts: &[Contact], format: Format) -> Result<()> {
+    match format {
+        Format::Table => {
+            let rows: Vec<[String; 7]> = contacts
+                .iter()
+                .map(|contact| {
+                    let jobs: Vec<String> = contact.job_ids.iter().map(u32::to_string).collect();
+                    [
+                        contact.id.to_string(),
+                        contact.name.clone(),
+                        contact.email.clone().unwrap_or_default(),
+                        contact.phone.clone().unwrap_or_default(),
+                        contact.role.clone().unwrap_or_default(),
+                        contact.company.clone().unwrap_or_default(),
+                        jobs.join(","),
+                    ]
+                })
+                .collect();
+            print_table(
+                ["ID", "Name", "Email", "Phone", "Role", "Company", "Jobs"],
+                &rows,
+            );
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(contacts)?),
+    }
+    Ok(())
+}
+
+pub fn print_contact_import(report: &ContactImport, format: Format) -> Result<()> {
+    match format {
+        Format::Table => {
+            print_contacts(&report.imported, format)?;
+            if !report.duplicates.is_empty() {
+                println!(
+                    "\nSkipped {} already known: {}",
+                    report.duplicates.len(),
+                    report.duplicates.join(", ")
+                );
+            }
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(report)?),
+    }
+    Ok(())
+}
+
+/// Interviews with the application each belongs to. Times are shown in
+/// the interview's own time zone.
+pub fn print_interviews(interviews: &[(&Job, &Interview)], format: Format) -> Result<()> {
+    match format {
+        Format::Table => {
+            let rows: Vec<[String; 8]> = interviews
+                .iter()
+                .map(|(job, interview)| {
+                    let start = interview.start.with_timezone(&interview.timezone);
+                    let end = interview.end.with_timezone(&interview.timezone);
+                    [
+                        job.id.to_string(),
+                        interview.id.to_string(),
+                        format!(
+                            "{} - {}",
+                            start.format("%Y-%m-%d %H:%M"),
+                            end.format("%H:%M %Z")
+                        ),
+                        format!("{} - {}", job.company, job.role),
+                        interview.round.clone(),
+                        interview.interviewers.join(", "),
+                        interview.location.clone().unwrap_or_default(),
+                        interview
+                            .outcome
+                            .map_or("Pending".to_string(), |outcome| outcome.to_string()),
+                    ]
+                })
+                .collect();
+            print_table(
+                [
+                    "Job",
+                    "ID",
+                    "When",
+                    "Application",
+                    "Round",
+                    "Interviewers",
+                    "Location",
+                    "Outcome",
+                ],
+                &rows,
+            );
+        }
+        Format::Json => {
+            let interviews: Vec<_> = interviews
+                .iter()
+                .map(|(job, interview)| json!({ "job_id": job.id, "interview": interview }))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&interviews)?);
+        }
+    }
+    Ok(())
+}
+
+pub fn print_calendar_import(
+    store: &JobStore,
+    report: &CalendarImport,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Table => {
+            let interviews: Vec<(&Job, &Interview)> = report
+                .imported
+                .iter()
+                .filter_map(|(id, interview)| {
+                    Some((store.jobs.iter().find(|job| job.id == *id)?, interview))
+                })
+                .collect();
+            print_interviews(&interviews, format)?;
+            if !report.unmatched.is_empty() {
+                println!(
+                    "\nCouldn't match {} event(s) to an application: {}",
+                    report.unmatched.len(),
+                    report.unmatched.join(", ")
+                );
+            }
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(report)?),
+    }
+    Ok(())
+}
+
+/// Offers side by side, one row per application, amounts per year.
+pub fn print_offers(offers: &[OfferComparison], base_currency: &str, format: Format) -> Result<()> {
+    match format {
+        Format::Table => {
+            let rows: Vec<[String; 8]> = offers
+                .iter()
+                .map(|comparison| {
+                    let Some(offer) = &comparison.offer else {
+                        let mut row: [String; 8] = Default::default();
+                        row[0] = comparison.job_id.to_string();
+                        row[1] = format!("{} - {}", comparison.company, comparison.role);
+                        row[2] = "No offer details".to_string();
+                        return row;
+                    };
+                    let amount =
+                        |amount: Option<f64>| amount.map(format_amount).unwrap_or_default();
+                    [
+                        comparison.job_id.to_string(),
+                        format!("{} - {}", comparison.company, comparison.role),
+                        format!("{} {}", offer.currency, amount(comparison.annual_base)),
+                        amount(offer.bonus),
+                        amount(comparison.annual_equity),
+                        amount(comparison.annual_total),
+                        comparison
+                            .total_in_base
+                            .map_or("No exchange rate".to_string(), format_amount),
+                        offer.deadline.map(|d| d.to_string()).unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            let total_header = format!("Total ({base_currency})");
+            print_table(
+                [
+                    "Job",
+                    "Application",
+                    "Base",
+                    "Bonus",
+                    "Equity",
+                    "Total",
+                    &total_header,
+                    "Deadline",
+                ],
+                &rows,
+            );
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(offers)?),
+    }
+    Ok(())
+}
+
+pub fn print_sources(sources: &[Source], format: Format) -> Result<()> {
+    match format {
+        Format::Table => {
+            let rows: Vec<[String; 3]> = sources
+                .iter()
+                .map(|source| {
+                    [
+                        source.name.to_string(),
+                        source.aliases.join(", "),
+                        if source.referral { "yes" } else { "no" }.to_string(),
+                    ]
+                })
+                .collect();
+            print_table(["Source", "Aliases", "Referral"], &rows);
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(sources)?),
+    }
+    Ok(())
+}
+
+pub fn print_stages(stages: &[Stage], format: Format) -> Result<()> {
+    match format {
+        Format::Table => {
+            let rows: Vec<[String; 4]> = stages
+                .iter()
+                .map(|stage| {
+                    [
+                        stage.name.to_string(),
+                        stage.color.clone(),
+                        if stage.terminal { "yes" } else { "no" }.to_string(),
+                        stage.outcome.map(|o| o.to_string()).unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            print_table(["Stage", "Color", "Final", "Outcome"], &rows);
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(stages)?),
+    }
+    Ok(())
+}
+
+/// The funnel, with each stage as a share of the one before it and of all
+/// applications, then how many applications took each path.
+pub fn print_flow(flow: &PipelineFlow, format: Format) -> Result<()> {
+    match format {
+        Format::Table => {
+            let total = flow.funnel.first().map_or(0, |stage| stage.job_ids.len());
+            let percent = |count: usize, of: usize| {
+                if of == 0 {
+                    String::new()
+                } else {
+                    format!("{:.0}%", count as f32 / of as f32 * 100.0)
+                }
+            };
+            let mut previous = total;
+            let rows: Vec<[String; 4]> = flow
+                .funnel
+                .iter()
+                .map(|stage| {
+                    let count = stage.job_ids.len();
+                    let row = [
+                        stage.stage.to_string(),
+                        count.to_string(),
+                        percent(count, previous),
+                        percent(count, total),
+                    ];
+                    previous = count;
+                    row
+                })
+                .collect();
+            print_table(["Reached", "Applications", "Of previous", "Of all"], &rows);
+
+            let rows: Vec<[String; 4]> = flow
+                .paths
+                .iter()
+                .map(|path| {
+                    [
+                        path.source
+                            .as_ref()
+                            .map_or_else(|| "Not provided".to_string(), |s| s.to_string()),
+                        path.reached.to_string(),
+                        path.outcome
+                            .as_ref()
+                            .map_or_else(|| "Still open".to_string(), |s| s.to_string()),
+                        path.job_ids.len().to_string(),
+                    ]
+                })
+                .collect();
+            if !rows.is_empty() {
+                println!();
+                print_table(["Source", "Reached", "Outcome", "Applications"], &rows);
+            }
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(flow)?),
+    }
+    Ok(())
+}
+
+pub fn print_stats(stats: &SummaryCounts, format: Format) -> Result<()> {
+    match format {
+        Format::Table => {
+            println!("{stats}");
+            let by_tag = stats
+                .by_tag
+                .iter()
+                .map(|TagCounts { tag, counts }| (tag.clone(), counts));
+            print_breakdown("Tag", by_tag);
+            let by_arrangement = stats.by_arrangement.iter().map(
+                |ArrangementCounts {
+                     arrangement,
+                     counts,
+                 }| {
+                    let name = arrangement.map_or_else(|| "Unknown".to_string(), |a| a.to_string());
+                    (name, counts)
+                },
+            );
+            print_breakdown("Arrangement", by_arrangement);
+            let by_area = stats
+                .by_area
+                .iter()
+                .map(|AreaCounts { area, counts }| (area.clone(), counts));
+            print_breakdown("Region", by_area);
+            let by_source = stats
+                .by_source
+                .iter()
+                .map(|SourceCounts { source, counts }| {
+                    let name = source
+                        .as_ref()
+                        .map_or_else(|| "Not provided".to_string(), |s| s.to_string());
+                    (name, counts)
+                });
+            print_breakdown("Source", by_source);
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(stats)?),
+    }
+    Ok(())
+}
+
+/// Rates and median response time for each group of applications in
+/// `groups`, under a blank line.
+/// Prints nothing if there are no groups.
+fn print_breakdown<'a>(name: &str, groups: impl Iterator<Item = (String, &'a SummaryCounts)>) {
+    let percent = |rate: Option<f64>| format!("{:.0}%", rate.unwrap_or_default());
+    let rows: Vec<[String; 6]> = groups
+        .map(|(group, counts)| {
+            [
+                group,
+                counts.total.to_string(),
+                percent(counts.interview_rate),
+                percent(counts.offer_rate),
+                percent(counts.rejection_rate),
+                counts
+                    .median_days_to_response
+                    .map_or_else(String::new, |days| format!("{days:.1} days")),
+            ]
+        })
+        .collect();
+    if !rows.is_empty() {
+        println!();
+        print_table(
+            [
+                name,
+                "Applications",
+                "Interview",
+                "Offer",
+                "Rejection",
+                "Median response",
+            ],
+            &rows,
+        );
+    }
+}
+
+/// How the location of `job` was read.
+pub fn print_location(job: &Job, format: Format) -> Result<()> {
+    let location = &job.location;
+    match format {
+        Format::Table => {
+            let rows = [[
+                job.role_location.clone().unwrap_or_default(),
+                location.city.clone().unwrap_or_default(),
+                location.region.clone().unwrap_or_default(),
+                location.country.clone().unwrap_or_default(),
+                location
+                    .arrangement
+                    .map(|a| a.to_string())
+                    .unwrap_or_default(),
+            ]];
+            print_table(
+                ["Entered", "City", "Region", "Country", "Arrangement"],
+                &rows,
+            );
+        }
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({
+                "id": job.id,
+                "role_location": job.role_location,
+                "location": location,
+            }))?
+        ),
+    }
+    Ok(())
+}
+
+/// Every tag in use, with how many applications have it.
+pub fn print_tags(tags: &[(String, usize)], format: Format) -> Result<()> {
+    match format {
+        Format::Table => {
+            let rows: Vec<[String; 2]> = tags
+                .iter()
+                .map(|(tag, count)| [tag.clone(), count.to_string()])
+                .collect();
+            print_table(["Tag", "Applications"], &rows);
+        }
+        Format::Json => {
+            let tags: Vec<_> = tags
+                .iter()
+                .map(|(tag, count)| json!({ "tag": tag, "applications": count }))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&tags)?);
+        }
+    }
+    Ok(())
+}
+
+/// Reports the command an `undo` or `redo` acted on, `None` if there was
+/// nothing to do. If the undo history isn't `persisted` between commands
+/// there never is, so the user is told how to turn that on.
+pub fn print_undo(redo: bool, label: Option<&str>, persisted: bool, format: Format) -> Result<()> {
+    let (verb, done) = if redo {
+        ("redo", "Redid")
+    } else {
+        ("undo", "Undid")
+    };
+    match format {
+        Format::Table => match label {
+            Some(label) => println!("{done}: {label}"),
+            None if persisted => println!("Nothing to {verb}"),
+            None => println!(
+                "Nothing to {verb}. To {verb} changes made by earlier commands, set `persist = true` under [undo] in the config file"
+            ),
+        },
+        Format::Json => println!("{}", serde_json::json!({ verb: label })),
+    }
+    Ok(())
+}
@@ -0,0 +1,326 @@
+use crate::CurrencyConfig;
+use crate::JobStore;
+use crate::Offer;
+use crate::OfferComparison;
+use crate::PayPeriod;
+use crate::SalaryRange;
+use crate::StageOutcome;
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Working hours in a year, for turning hourly pay into yearly pay.
+const HOURS_PER_YEAR: f64 = 2080.0;
+const DEFAULT_VESTING_YEARS: u32 = 4;
+const DEFAULT_CURRENCY: &str = "USD";
+
+impl Default for CurrencyConfig {
+    fn default() -> Self {
+        Self {
+            base: DEFAULT_CURRENCY.to_string(),
+            rates: BTreeMap::new(),
+        }
+    }
+}
+
+impl CurrencyConfig {
+    /// `amount` of `currency` in the base currency, if there's a rate for it.
+    pub fn to_base(&self, amount: f64, currency: &str) -> Option<f64> {
+        if currency.eq_ignore_ascii_case(&self.base) {
+            return Some(amount);
+        }
+        self.rates
+            .iter()
+            .find(|(code, _)| code.eq_ignore_ascii_case(currency))
+            .map(|(_, rate)| amount * rate)
+    }
+}
+
+impl PayPeriod {
+    /// How many of this period there are in a year.
+    pub fn per_year(self) -> f64 {
+        match self {
+            PayPeriod::Hour => HOURS_PER_YEAR,
+            PayPeriod::Month => 12.0,
+            PayPeriod::Year => 1.0,
+        }
+    }
+}
+
+impl fmt::Display for PayPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PayPeriod::Hour => write!(f, "Hour"),
+            PayPeriod::Month => write!(f, "Month"),
+            PayPeriod::Year => write!(f, "Year"),
+        }
+    }
+}
+
+impl FromStr for PayPeriod {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "hour" | "hourly" => Ok(PayPeriod::Hour),
+            "month" | "monthly" => Ok(PayPeriod::Month),
+            "year" | "yearly" | "annual" => Ok(PayPeriod::Year),
+            _ => Err(format!("Unknown pay period: {s}")),
+        }
+    }
+}
+
+impl SalaryRange {
+    /// `min` and `max` per year.
+    pub fn annual(&self) -> (f64, f64) {
+        let per_year = self.period.per_year();
+        (self.min * per_year, self.max * per_year)
+    }
+}
+
+impl Offer {
+    pub fn annual_base(&self) -> f64 {
+        self.base * self.period.per_year()
+    }
+
+    /// The equity grant spread evenly over its vesting years.
+    pub fn annual_equity(&self) -> Option<f64> {
+        let years = self.vesting_years.unwrap_or(DEFAULT_VESTING_YEARS).max(1);
+        self.equity.map(|equity| equity / f64::from(years))
+    }
+
+    /// Base, bonus and equity for one year.
+    pub fn annual_total(&self) -> f64 {
+        self.annual_base() + self.bonus.unwrap_or(0.0) + self.annual_equity().unwrap_or(0.0)
+    }
+}
+
+/// `amount` with thousands separators, and cents only if it has any, e.g.
+/// `120,000` or `52.50`.
+pub fn format_amount(amount: f64) -> String {
+    let cents = (amount.abs() * 100.0).round() as u64;
+    let mut whole = (cents / 100).to_string();
+    let mut grouped = String::new();
+    while whole.len() > 3 {
+        let rest = whole.split_off(whole.len() - 3);
+        grouped = format!(",{rest}{grouped}");
+    }
+    let sign = if amount < 0.0 { "-" } else { "" };
+    match cents % 100 {
+        0 => format!("{sign}{whole}{grouped}"),
+        fraction => format!("{sign}{whole}{grouped}.{fraction:02}"),
+    }
+}
+
+/// Uppercases the currency code and rejects blank ones.
+fn check_currency(currency: &mut String) -> Result<()> {
+    *currency = currency.trim().to_uppercase();
+    if currency.is_empty() {
+        bail!("A currency is needed, e.g. USD");
+    }
+    Ok(())
+}
+
+fn check_amount(name: &str, amount: f64) -> Result<()> {
+    if !amount.is_finite() {
+        bail!("{name} has to be a number, got {amount}");
+    }
+    if amount < 0.0 {
+        bail!("{name} can't be negative");
+    }
+    Ok(())
+}
+
+impl JobStore {
+    /// Sets or, with `None`, clears the pay range from job `id`'s posting.
+    pub fn set_salary_range(&mut self, id: u32, mut range: Option<SalaryRange>) -> Result<()> {
+        if let Some(range) = &mut range {
+            check_currency(&mut range.currency)?;
+            check_amount("The minimum", range.min)?;
+            check_amount("The maximum", range.max)?;
+            if range.max < range.min {
+                bail!("The maximum can't be below the minimum");
+            }
+        }
+        self.find_job(id)?;
+        self.modify_job(id, "Change salary range", |job| job.salary_range = range)
+    }
+
+    /// Sets or, with `None`, clears the details of job `id`'s offer.
+    pub fn set_offer(&mut self, id: u32, mut offer: Option<Offer>) -> Result<()> {
+        if let Some(offer) = &mut offer {
+            check_currency(&mut offer.currency)?;
+            check_amount("Base pay", offer.base)?;
+            check_amount("The bonus", offer.bonus.unwrap_or(0.0))?;
+            check_amount("Equity", offer.equity.unwrap_or(0.0))?;
+        }
+        self.find_job(id)?;
+        self.modify_job(id, "Change offer", |job| job.offer = offer)
+    }
+
+    /// Every application outside the trash in a stage with a successful
+    /// outcome, best paying first. Ones without offer details, or whose
+    /// currency has no exchange rate, come last.
+    pub fn offer_comparison(&self) -> Vec<OfferComparison> {
+        let mut offers: Vec<OfferComparison> = self
+            .active_jobs()
+            .filter(|job| {
+                self.stage(&job.status)
+                    .is_some_and(|stage| stage.outcome == Some(StageOutcome::Success))
+            })
+            .map(|job| {
+                let offer = job.offer.as_ref();
+                OfferComparison {
+                    job_id: job.id,
+                    company: job.company.clone(),
+                    role: job.role.clone(),
+                    offer: job.offer.clone(),
+                    annual_base: offer.map(Offer::annual_base),
+                    annual_equity: offer.and_then(Offer::annual_equity),
+                    annual_total: offer.map(Offer::annual_total),
+                    total_in_base: offer.and_then(|offer| {
+                        self.currency.to_base(offer.annual_total(), &offer.currency)
+                    }),
+                }
+            })
+            .collect();
+        offers.sort_by(|a, b| {
+            b.total_in_base
+                .partial_cmp(&a.total_in_base)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        offers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::store_with_job;
+    use crate::JobStatus;
+
+    fn offer(base: f64, currency: &str) -> Offer {
+        Offer {
+            base,
+            period: PayPeriod::Year,
+            bonus: None,
+            equity: None,
+            vesting_years: None,
+            currency: currency.to_string(),
+            benefits: None,
+            deadline: None,
+        }
+    }
+
+    fn range(min: f64, max: f64) -> SalaryRange {
+        SalaryRange {
+            min,
+            max,
+            currency: "usd".to_string(),
+            period: PayPeriod::Year,
+        }
+    }
+
+    #[test]
+    fn totals_add_up_a_year_of_pay() {
+        let mut hourly = offer(50.0, "USD");
+        hourly.period = PayPeriod::Hour;
+        hourly.bonus = Some(10_000.0);
+        hourly.equity = Some(80_000.0);
+        assert_eq!(hourly.annual_base(), 104_000.0);
+        assert_eq!(hourly.annual_equity(), Some(20_000.0));
+        assert_eq!(hourly.annual_total(), 134_000.0);
+
+        hourly.vesting_years = Some(0);
+        assert_eq!(hourly.annual_equity(), Some(80_000.0));
+        let mut monthly = offer(10_000.0, "USD");
+        monthly.period = PayPeriod::Month;
+        assert_eq!(monthly.annual_total(), 120_000.0);
+        assert_eq!(range(40.0, 60.0).annual(), (40.0, 60.0));
+    }
+
+    #[test]
+    fn amounts_convert_to_the_base_currency() {
+        let currency = CurrencyConfig {
+            base: "USD".to_string(),
+            rates: BTreeMap::from([("EUR".to_string(), 1.5)]),
+        };
+        assert_eq!(currency.to_base(100.0, "usd"), Some(100.0));
+        assert_eq!(currency.to_base(100.0, "eur"), Some(150.0));
+        assert_eq!(currency.to_base(100.0, "GBP"), None);
+    }
+
+    #[test]
+    fn offers_are_compared_best_first() {
+        let mut store = store_with_job();
+        for company in ["Initech", "Hooli", "Globex", "Initrode"] {
+            store
+                .add_job(company.into(), "Dev".into(), String::new(), String::new())
+                .unwrap();
+        }
+        // Job 4 has no offer details yet and job 5 no offer at all
+        for id in 1..=4 {
+            store.update_status(id, JobStatus::OFFER).unwrap();
+        }
+        store.currency.rates.insert("EUR".to_string(), 2.0);
+        store.set_offer(1, Some(offer(100_000.0, "USD"))).unwrap();
+        store.set_offer(2, Some(offer(60_000.0, "eur"))).unwrap();
+        store.set_offer(3, Some(offer(500_000.0, "GBP"))).unwrap();
+
+        let comparison = store.offer_comparison();
+        let ids: Vec<u32> = comparison.iter().map(|offer| offer.job_id).collect();
+        assert_eq!(ids[..2], [2, 1]);
+        assert_eq!(comparison[0].total_in_base, Some(120_000.0));
+        assert_eq!(comparison[2].annual_total, Some(500_000.0));
+        assert!(comparison[2..]
+            .iter()
+            .all(|offer| offer.total_in_base.is_none()));
+        assert_eq!(comparison.len(), 4);
+    }
+
+    #[test]
+    fn amounts_are_formatted_with_separators() {
+        assert_eq!(format_amount(0.0), "0");
+        assert_eq!(format_amount(999.0), "999");
+        assert_eq!(format_amount(120_000.0), "120,000");
+        assert_eq!(format_amount(1_234_567.891), "1,234,567.89");
+        assert_eq!(format_amount(52.5), "52.50");
+        assert_eq!(format_amount(-1_500.0), "-1,500");
+    }
+
+    #[test]
+    fn bad_amounts_are_rejected() {
+        let mut store = store_with_job();
+        store.set_salary_range(1, Some(range(80.0, 90.0))).unwrap();
+        assert_eq!(store.jobs[0].salary_range.as_ref().unwrap().currency, "USD");
+
+        let error = |result: Result<()>| result.unwrap_err().to_string();
+        assert_eq!(
+            error(store.set_salary_range(1, Some(range(-1.0, 90.0)))),
+            "The minimum can't be negative"
+        );
+        assert_eq!(
+            error(store.set_salary_range(1, Some(range(90.0, 80.0)))),
+            "The maximum can't be below the minimum"
+        );
+        assert_eq!(
+            error(store.set_salary_range(1, Some(range(80.0, f64::NAN)))),
+            "The maximum has to be a number, got NaN"
+        );
+        assert_eq!(
+            error(store.set_offer(1, Some(offer(f64::INFINITY, "USD")))),
+            "Base pay has to be a number, got inf"
+        );
+        assert_eq!(
+            error(store.set_offer(1, Some(offer(1.0, " ")))),
+            "A currency is needed, e.g. USD"
+        );
+        let mut bonus = offer(1.0, "USD");
+        bonus.bonus = Some(-5.0);
+        assert_eq!(
+            error(store.set_offer(1, Some(bonus))),
+            "The bonus can't be negative"
+        );
+        assert_eq!(store.jobs[0].offer, None);
+    }
+}
@@ -0,0 +1,213 @@
+use crate::Config;
+use crate::JobStore;
+use anyhow::{anyhow, Context, Result};
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the data file.
+pub const DATA_FILE_ENV: &str = "JOBTRACKER_DATA";
+
+const APP_DIR: &str = "jobtracker";
+const CONFIG_FILE_NAME: &str = "config.toml";
+const DATA_FILE_NAME: &str = "jobtrack.json";
+
+/// Where the tracker used to keep its data, relative to wherever it was
+/// launched from.
+const LEGACY_DATA_FILE: &str = "jobtrack.json";
+
+/// The data file picked by [`Config::data_file`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataFile {
+    pub path: PathBuf,
+    /// Something the user should know about the choice, e.g. that a data
+    /// file from an older version was left where it used to be.
+    pub warning: Option<String>,
+}
+
+/// `$<var>/jobtracker`, or `$HOME/<fallback>/jobtracker` when the variable
+/// is unset, looking variables up with `var_os`. Per the XDG spec, relative
+/// values are ignored.
+fn xdg_dir(
+    var: &str,
+    fallback: &str,
+    var_os: &impl Fn(&str) -> Option<OsString>,
+) -> Option<PathBuf> {
+    var_os(var)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| var_os("HOME").map(|home| PathBuf::from(home).join(fallback)))
+        .map(|dir| dir.join(APP_DIR))
+}
+
+impl Config {
+    /// Location of the config file, if a home directory can be found.
+    pub fn config_file() -> Option<PathBuf> {
+        xdg_dir("XDG_CONFIG_HOME", ".config", &|var| env::var_os(var))
+            .map(|dir| dir.join(CONFIG_FILE_NAME))
+    }
+
+    /// Reads the config file, falling back to defaults when there isn't one.
+    pub fn load() -> Result<Self> {
+        match Self::config_file() {
+            Some(path) if path.exists() => Self::load_from(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let data = fs::read_to_string(path)?;
+        toml::from_str(&data).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    /// Picks the data file, in order of precedence: `cli_data_file` (the
+    /// `--data-file` flag), the `JOBTRACKER_DATA` environment variable, the
+    /// config file, then `$XDG_DATA_HOME/jobtracker/jobtrack.json`. The
+    /// default directory is created if it doesn't exist.
+    pub fn data_file(&self, cli_data_file: Option<PathBuf>) -> Result<DataFile> {
+        self.data_file_with(
+            cli_data_file,
+            |var| env::var_os(var),
+            Path::new(LEGACY_DATA_FILE),
+        )
+    }
+
+    /// Like [`Self::data_file`], looking environment variables up with
+    /// `var_os` and warning about `legacy_file` if it exists but isn't used.
+    fn data_file_with(
+        &self,
+        cli_data_file: Option<PathBuf>,
+        var_os: impl Fn(&str) -> Option<OsString>,
+        legacy_file: &Path,
+    ) -> Result<DataFile> {
+        let chosen = cli_data_file
+            .or_else(|| {
+                var_os(DATA_FILE_ENV)
+                    .filter(|p| !p.is_empty())
+                    .map(PathBuf::from)
+            })
+            .or_else(|| self.data_file.clone());
+        if let Some(path) = chosen {
+            return Ok(DataFile {
+                path,
+                warning: None,
+            });
+        }
+
+        let dir = xdg_dir("XDG_DATA_HOME", ".local/share", &var_os).ok_or_else(|| {
+            anyhow!("Could not find a data directory. Set HOME, XDG_DATA_HOME or {DATA_FILE_ENV}.")
+        })?;
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(DATA_FILE_NAME);
+        let warning = (!path.exists() && legacy_file.exists()).then(|| {
+            format!(
+                "Found {LEGACY_DATA_FILE} in the current directory, but data now lives in {}. \
+                 Move the file there or pass --data-file {LEGACY_DATA_FILE} to keep using it.",
+                path.display()
+            )
+        });
+        Ok(DataFile { path, warning })
+    }
+
+    /// Opens the store at the resolved data file, returning it along with
+    /// any warning about the choice. See [`Self::data_file`].
+    pub fn open_store(&self, cli_data_file: Option<PathBuf>) -> Result<(JobStore, Option<String>)> {
+        let data_file = self.data_file(cli_data_file)?;
+        Ok((JobStore::open(data_file.path, self)?, data_file.warning))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// A fresh, empty directory under the temp dir.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("jobtracker-test-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn resolve(
+        config: &Config,
+        cli: Option<&str>,
+        vars: &[(&str, &Path)],
+        legacy_file: &Path,
+    ) -> DataFile {
+        let vars: HashMap<&str, OsString> = vars
+            .iter()
+            .map(|(var, value)| (*var, value.as_os_str().to_owned()))
+            .collect();
+        config
+            .data_file_with(
+                cli.map(PathBuf::from),
+                |var| vars.get(var).cloned(),
+                legacy_file,
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn data_file_follows_precedence() {
+        let dir = test_dir("data-file-precedence");
+        let legacy_file = dir.join("missing.json");
+        let mut config = Config::default();
+        let mut vars = vec![("HOME", dir.as_path())];
+        let pick = |config: &Config, cli, vars: &[(&str, &Path)]| {
+            resolve(config, cli, vars, &legacy_file).path
+        };
+        assert_eq!(
+            pick(&config, None, &vars),
+            dir.join(".local/share/jobtracker/jobtrack.json")
+        );
+        let xdg = dir.join("xdg");
+        vars.push(("XDG_DATA_HOME", &xdg));
+        assert_eq!(
+            pick(&config, None, &vars),
+            xdg.join("jobtracker/jobtrack.json")
+        );
+        config.data_file = Some(PathBuf::from("config.json"));
+        assert_eq!(pick(&config, None, &vars), PathBuf::from("config.json"));
+        vars.push((DATA_FILE_ENV, Path::new("env.json")));
+        assert_eq!(pick(&config, None, &vars), PathBuf::from("env.json"));
+        assert_eq!(
+            pick(&config, Some("flag.json"), &vars),
+            PathBuf::from("flag.json")
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn relative_xdg_dirs_are_ignored() {
+        let dir = test_dir("data-file-relative-xdg");
+        let vars = [
+            ("HOME", dir.as_path()),
+            ("XDG_DATA_HOME", Path::new("data")),
+        ];
+        let data_file = resolve(&Config::default(), None, &vars, &dir.join("missing.json"));
+        assert_eq!(
+            data_file.path,
+            dir.join(".local/share/jobtracker/jobtrack.json")
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn legacy_files_left_behind_are_warned_about() {
+        let dir = test_dir("data-file-legacy");
+        let legacy_file = dir.join(LEGACY_DATA_FILE);
+        let vars = [("HOME", dir.as_path())];
+        let config = Config::default();
+        assert_eq!(resolve(&config, None, &vars, &legacy_file).warning, None);
+
+        fs::write(&legacy_file, "[]").unwrap();
+        let warning = resolve(&config, None, &vars, &legacy_file).warning;
+        assert!(warning.unwrap().contains("--data-file jobtrack.json"));
+        let flagged = resolve(&config, Some("jobtrack.json"), &vars, &legacy_file);
+        assert_eq!(flagged.warning, None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,191 @@
+use crate::Contact;
+use crate::ContactChange;
+use crate::Job;
+use crate::JobChange;
+use crate::JobStore;
+use crate::StoreCommand;
+use anyhow::{anyhow, bail, Result};
+
+impl Contact {
+    /// Whether the search box text matches this contact. `search_text` is
+    /// expected in lowercase; empty text matches everything.
+    pub fn matches_search(&self, search_text: &str) -> bool {
+        search_text.is_empty()
+            || [
+                Some(&self.name),
+                self.email.as_ref(),
+                self.phone.as_ref(),
+                self.role.as_ref(),
+                self.company.as_ref(),
+                self.notes.as_ref(),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|field| field.to_lowercase().contains(search_text))
+    }
+}
+
+impl JobStore {
+    /// ID for the next contact added to the store.
+    pub fn next_contact_id(&self) -> u32 {
+        self.contacts.iter().map(|c| c.id).max().unwrap_or(0) + 1
+    }
+
+    pub fn find_contact(&self, id: u32) -> Result<&Contact> {
+        self.contacts
+            .iter()
+            .find(|contact| contact.id == id)
+            .ok_or_else(|| anyhow!("No contact with ID {id}"))
+    }
+
+    /// People linked to job `job_id`.
+    pub fn contacts_for_job(&self, job_id: u32) -> impl Iterator<Item = &Contact> {
+        self.contacts
+            .iter()
+            .filter(move |contact| contact.job_ids.contains(&job_id))
+    }
+
+    /// Adds `contact` under the next free ID, which is returned. Its links
+    /// must point at existing jobs.
+    pub fn add_contact(&mut self, mut contact: Contact) -> Result<u32> {
+        contact.id = self.next_contact_id();
+        self.check_contact(&contact)?;
+        let id = contact.id;
+        self.contacts.push(contact.clone());
+        self.record_contact_change(
+            "Add contact",
+            ContactChange {
+                before: None,
+                after: Some(contact),
+            },
+        )?;
+        Ok(id)
+    }
+
+    /// Overwrites the contact with the same ID as `contact`.
+    pub fn update_contact(&mut self, contact: Contact) -> Result<()> {
+        self.check_contact(&contact)?;
+        self.modify_contact(contact.id, "Edit contact", |existing| *existing = contact)
+    }
+
+    /// Removes contact `id` along with its links. The jobs stay, but no
+    /// longer name the contact as their referrer.
+    pub fn delete_contact(&mut self, id: u32) -> Result<()> {
+        let index = self
+            .contacts
+            .iter()
+            .position(|contact| contact.id == id)
+            .ok_or_else(|| anyhow!("No contact with ID {id}"))?;
+        let contact = self.contacts.remove(index);
+        let mut changes = Vec::new();
+        for job in self.jobs.iter_mut().filter(|job| job.referrer == Some(id)) {
+            let before = job.clone();
+            job.referrer = None;
+            changes.push(JobChange {
+                before: Some(before),
+                after: Some(job.clone()),
+            });
+        }
+        self.record_command(StoreCommand {
+            label: "Delete contact".to_string(),
+            changes,
+            contact_changes: vec![ContactChange {
+                before: Some(contact),
+                after: None,
+            }],
+            ..Default::default()
+        })
+    }
+
+    /// The contact who referred the user for `job`, if any.
+    pub fn referrer(&self, job: &Job) -> Option<&Contact> {
+        let id = job.referrer?;
+        self.contacts.iter().find(|contact| contact.id == id)
+    }
+
+    /// Links contact `id` to job `job_id`. Linking twice is a no-op.
+    pub fn link_contact(&mut self, id: u32, job_id: u32) -> Result<()> {
+        if !self.jobs.iter().any(|job| job.id == job_id) {
+            bail!("No job application with ID {job_id}");
+        }
+        self.modify_contact(id, "Link contact", |contact| {
+            if !contact.job_ids.contains(&job_id) {
+                contact.job_ids.push(job_id);
+                contact.job_ids.sort();
+            }
+        })
+    }
+
+    pub fn unlink_contact(&mut self, id: u32, job_id: u32) -> Result<()> {
+        self.modify_contact(id, "Unlink contact", |contact| {
+            contact.job_ids.retain(|linked| *linked != job_id)
+        })
+    }
+
+    /// Drops every link to job `job_id`, returning the changes made for the
+    /// caller to record alongside its own.
+    pub(crate) fn unlink_job_from_contacts(&mut self, job_id: u32) -> Vec<ContactChange> {
+        let mut changes = Vec::new();
+        for contact in &mut self.contacts {
+            if contact.job_ids.contains(&job_id) {
+                let before = contact.clone();
+                contact.job_ids.retain(|linked| *linked != job_id);
+                changes.push(ContactChange {
+                    before: Some(before),
+                    after: Some(contact.clone()),
+                });
+            }
+        }
+        changes
+    }
+
+    fn check_contact(&self, contact: &Contact) -> Result<()> {
+        if contact.name.trim().is_empty() {
+            bail!("A contact needs a name");
+        }
+        if let Some(job_id) = contact
+            .job_ids
+            .iter()
+            .find(|id| !self.jobs.iter().any(|job| job.id == **id))
+        {
+            bail!("No job application with ID {job_id}");
+        }
+        Ok(())
+    }
+
+    /// Applies `change` to contact `id` and records it as an undoable
+    /// command named `label`, unless it leaves the contact as it was.
+    fn modify_contact(
+        &mut self,
+        id: u32,
+        label: &str,
+        change: impl FnOnce(&mut Contact),
+    ) -> Result<()> {
+        let contact = self
+            .contacts
+            .iter_mut()
+            .find(|contact| contact.id == id)
+            .ok_or_else(|| anyhow!("No contact with ID {id}"))?;
+        let before = contact.clone();
+        change(contact);
+        if *contact == before {
+            return Ok(());
+        }
+        let after = contact.clone();
+        self.record_contact_change(
+            label,
+            ContactChange {
+                before: Some(before),
+                after: Some(after),
+            },
+        )
+    }
+
+    fn record_contact_change(&mut self, label: &str, change: ContactChange) -> Result<()> {
+        self.record_command(StoreCommand {
+            label: label.to_string(),
+            contact_changes: vec![change],
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,146 @@
+/// Longest vCard or iCalendar content line allowed before it has to be
+/// folded, in bytes.
+const MAX_LINE: usize = 75;
+
+/// Backslash-escapes the characters text values can't hold as-is.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ',' | ';' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub(crate) fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+/// Splits a structured value such as `N` or `ORG` on the semicolons that
+/// aren't escaped.
+pub(crate) fn components(value: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let last = parts.last_mut().unwrap();
+                last.push(c);
+                if let Some(next) = chars.next() {
+                    last.push(next);
+                }
+            }
+            ';' => parts.push(String::new()),
+            _ => parts.last_mut().unwrap().push(c),
+        }
+    }
+    parts.iter().map(|part| unescape(part)).collect()
+}
+
+/// Writes `line` with CRLF, folding it onto continuation lines that start
+/// with a space once it's too long.
+pub(crate) fn write_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// Joins folded lines back together.
+pub(crate) fn unfold(text: &str) -> String {
+    text.replace("\r\n ", "")
+        .replace("\r\n\t", "")
+        .replace("\n ", "")
+        .replace("\n\t", "")
+}
+
+/// Parameters of a content line, e.g. `TZID=Europe/Berlin`, with the
+/// names uppercased.
+pub(crate) type Params = Vec<(String, String)>;
+
+/// Splits a content line into its property name, parameters and value.
+/// The name is uppercased and stripped of any group prefix
+/// (`item1.EMAIL`). `None` for lines without a value.
+pub(crate) fn parse_line(line: &str) -> Option<(String, Params, &str)> {
+    let (head, value) = line.split_once(':')?;
+    let mut parts = head.split(';');
+    let name = parts.next()?.rsplit('.').next()?.to_ascii_uppercase();
+    let params = parts
+        .filter_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            Some((
+                key.to_ascii_uppercase(),
+                value.trim_matches('"').to_string(),
+            ))
+        })
+        .collect();
+    Some((name, params, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_line_splits_name_params_and_value() {
+        let (name, params, value) = parse_line("item1.email;type=WORK:ann@example.com").unwrap();
+        assert_eq!(name, "EMAIL");
+        assert_eq!(params, [("TYPE".to_string(), "WORK".to_string())]);
+        assert_eq!(value, "ann@example.com");
+
+        // Only the first colon ends the name, so URLs survive
+        let (_, _, value) = parse_line("URL:https://example.com:8080/a").unwrap();
+        assert_eq!(value, "https://example.com:8080/a");
+
+        let (_, _, value) = parse_line("NOTE:").unwrap();
+        assert_eq!(value, "");
+        assert!(parse_line("no value here").is_none());
+    }
+
+    #[test]
+    fn escaping_round_trips() {
+        let text = "Coffee; then lunch, maybe\\later\nsecond line";
+        assert_eq!(
+            escape(text),
+            r"Coffee\; then lunch\, maybe\\later\nsecond line"
+        );
+        assert_eq!(unescape(&escape(text)), text);
+        assert_eq!(components(r"Doe;Jane\;Ann;;"), ["Doe", "Jane;Ann", "", ""]);
+    }
+
+    #[test]
+    fn long_lines_fold_and_unfold() {
+        let line = format!("NOTE:{}", "é".repeat(60));
+        let mut out = String::new();
+        write_line(&mut out, &line);
+        assert!(out.lines().count() > 1);
+        assert!(out.lines().all(|l| l.len() <= MAX_LINE));
+        assert_eq!(unfold(&out), format!("{line}\r\n"));
+    }
+}
@@ -0,0 +1,26 @@
+use crate::DataFileError;
+use std::error::Error;
+use std::fmt;
+
+impl fmt::Display for DataFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DataFileError::NewerVersion {
+                path,
+                found,
+                supported,
+            } => write!(
+                f,
+                "{} was written by a newer version of {} (format version {found}, \
+                 this build supports up to {supported}). Update the tracker to open it.",
+                path.display(),
+                crate::APP_NAME
+            ),
+            DataFileError::UnrecognizedFormat { path } => {
+                write!(f, "{} is not a job tracker data file", path.display())
+            }
+        }
+    }
+}
+
+impl Error for DataFileError {}
@@ -0,0 +1,95 @@
+use crate::GhostingRule;
+use crate::Job;
+use crate::JobStatus;
+use crate::Stage;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+
+impl GhostingRule {
+    pub fn default_statuses() -> Vec<JobStatus> {
+        vec![JobStatus::APPLIED]
+    }
+
+    pub fn default_target() -> JobStatus {
+        JobStatus::GHOSTED
+    }
+
+    /// The rule with its stage names spelled the way `stages` spells them.
+    /// Fails if its target or one of its statuses isn't one of `stages`.
+    pub fn resolve(&self, stages: &[Stage]) -> Result<GhostingRule> {
+        let find = |status: &JobStatus| {
+            stages
+                .iter()
+                .find(|stage| {
+                    stage
+                        .name
+                        .as_str()
+                        .eq_ignore_ascii_case(status.as_str().trim())
+                })
+                .map(|stage| stage.name.clone())
+                .ok_or_else(|| {
+                    anyhow!(
+                        "The ghosting rule uses stage \"{status}\", which isn't in the pipeline"
+                    )
+                })
+        };
+        Ok(GhostingRule {
+            after_days: self.after_days,
+            statuses: self.statuses.iter().map(find).collect::<Result<_>>()?,
+            target: find(&self.target)?,
+        })
+    }
+
+    /// Whether `job` has gone long enough without a status change to count
+    /// as ghosted at `now`.
+    pub fn applies_to(&self, job: &Job, now: DateTime<Utc>) -> bool {
+        !job.is_deleted()
+            && self.statuses.contains(&job.status)
+            && now - job.last_status_change() >= Duration::days(self.after_days.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule() -> GhostingRule {
+        GhostingRule {
+            after_days: 30,
+            statuses: GhostingRule::default_statuses(),
+            target: GhostingRule::default_target(),
+        }
+    }
+
+    #[test]
+    fn applies_once_the_quiet_spell_is_over() {
+        let now = Utc::now();
+        let applied = |days_ago: i64, seconds_ago: i64| {
+            let at = now - Duration::days(days_ago) - Duration::seconds(seconds_ago);
+            Job::new(1, "Acme".into(), "Dev".into(), JobStatus::APPLIED, at)
+        };
+        assert!(rule().applies_to(&applied(30, 0), now));
+        assert!(!rule().applies_to(&applied(29, 86_399), now));
+
+        let mut interviewing = applied(40, 0);
+        interviewing.status = JobStatus::INTERVIEW;
+        assert!(!rule().applies_to(&interviewing, now));
+        let mut trashed = applied(40, 0);
+        trashed.deleted_at = Some(now);
+        assert!(!rule().applies_to(&trashed, now));
+    }
+
+    #[test]
+    fn resolves_stage_names_against_the_pipeline() {
+        let mut rule = rule();
+        rule.statuses = vec![JobStatus::new(" applied")];
+        rule.target = JobStatus::new("GHOSTED");
+        let resolved = rule.resolve(&Stage::defaults()).unwrap();
+        assert_eq!(resolved.statuses, [JobStatus::APPLIED]);
+        assert_eq!(resolved.target, JobStatus::GHOSTED);
+
+        rule.target = JobStatus::new("Silent");
+        let error = rule.resolve(&Stage::defaults()).unwrap_err();
+        assert!(error.to_string().contains("\"Silent\""));
+    }
+}
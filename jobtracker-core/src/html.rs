@@ -0,0 +1,310 @@
+/// A start or end tag, with attribute names lowercased.
+#[derive(Debug)]
+pub(crate) struct Tag {
+    pub name: String,
+    pub closing: bool,
+    pub attrs: Vec<(String, String)>,
+    /// Byte offsets in the document of the `<` and just past the `>`.
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Tag {
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Every tag in `html`, in order. Comments, doctypes and the contents of
+/// `script` and `style` elements are skipped over; only their own tags are
+/// returned. Not a full HTML parser, but enough for saved web pages.
+pub(crate) fn tags(html: &str) -> Vec<Tag> {
+    let mut tags = Vec::new();
+    let mut pos = 0;
+    while let Some(offset) = html[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &html[start..];
+        if rest.starts_with("<!--") {
+            pos = rest
+                .find("-->")
+                .map_or(html.len(), |end| start + end + "-->".len());
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            pos = rest.find('>').map_or(html.len(), |end| start + end + 1);
+            continue;
+        }
+        let Some(tag) = parse_tag(html, start) else {
+            pos = start + 1;
+            continue;
+        };
+        pos = tag.end;
+        // Raw text elements can hold anything, including "<", up to their
+        // end tag
+        if !tag.closing && (tag.name == "script" || tag.name == "style") {
+            let close = format!("</{}", tag.name);
+            if let Some(end) = find_ignore_case(&html[pos..], &close) {
+                pos += end;
+            }
+        }
+        tags.push(tag);
+    }
+    tags
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+fn parse_tag(html: &str, start: usize) -> Option<Tag> {
+    let bytes = html.as_bytes();
+    let mut pos = start + 1;
+    let closing = bytes.get(pos) == Some(&b'/');
+    if closing {
+        pos += 1;
+    }
+    let name_start = pos;
+    while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'-') {
+        pos += 1;
+    }
+    if pos == name_start {
+        return None;
+    }
+    let name = html[name_start..pos].to_ascii_lowercase();
+
+    let mut attrs = Vec::new();
+    loop {
+        while pos < bytes.len() && (bytes[pos].is_ascii_whitespace() || bytes[pos] == b'/') {
+            pos += 1;
+        }
+        match bytes.get(pos) {
+            None => return None,
+            Some(b'>') => break,
+            _ => {}
+        }
+        let key_start = pos;
+        while pos < bytes.len()
+            && !bytes[pos].is_ascii_whitespace()
+            && !matches!(bytes[pos], b'=' | b'>' | b'/')
+        {
+            pos += 1;
+        }
+        let key = html[key_start..pos].to_ascii_lowercase();
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        let mut value = String::new();
+        if bytes.get(pos) == Some(&b'=') {
+            pos += 1;
+            while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            match bytes.get(pos) {
+                Some(&quote @ (b'"' | b'\'')) => {
+                    let end = html[pos + 1..].find(quote as char)? + pos + 1;
+                    value = decode_entities(&html[pos + 1..end]);
+                    pos = end + 1;
+                }
+                _ => {
+                    let value_start = pos;
+                    while pos < bytes.len()
+                        && !bytes[pos].is_ascii_whitespace()
+                        && bytes[pos] != b'>'
+                    {
+                        pos += 1;
+                    }
+                    value = decode_entities(&html[value_start..pos]);
+                }
+            }
+        }
+        if !key.is_empty() {
+            attrs.push((key, value));
+        }
+    }
+    Some(Tag {
+        name,
+        closing,
+        attrs,
+        start,
+        end: pos + 1,
+    })
+}
+
+/// Raw contents of every element named `name`, e.g. the JSON in `script`
+/// tags, together with its start tag.
+pub(crate) fn elements<'a>(html: &'a str, tags: &'a [Tag], name: &str) -> Vec<(&'a Tag, &'a str)> {
+    tags.iter()
+        .enumerate()
+        .filter(|(_, tag)| tag.name == name && !tag.closing)
+        .filter_map(|(index, tag)| {
+            let close = tags[index + 1..]
+                .iter()
+                .find(|other| other.name == name && other.closing)?;
+            Some((tag, &html[tag.end..close.start]))
+        })
+        .collect()
+}
+
+/// Replaces character references like `&amp;` and `&#8217;` with the
+/// characters they stand for. Unknown named references are left alone.
+pub(crate) fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semicolon) = rest.find(';').filter(|&i| i <= 10) else {
+            decoded.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..semicolon];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            "ndash" => Some('–'),
+            "mdash" => Some('—'),
+            "rsquo" => Some('’'),
+            "lsquo" => Some('‘'),
+            "rdquo" => Some('”'),
+            "ldquo" => Some('“'),
+            "bull" => Some('•'),
+            "hellip" => Some('…'),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match c {
+            Some(c) => {
+                decoded.push(c);
+                rest = &rest[semicolon + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Plain text of an HTML fragment: tags dropped, paragraphs and line breaks
+/// kept as newlines, list items as "- " lines and runs of whitespace
+/// squeezed.
+pub(crate) fn to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut pos = 0;
+    let mut in_script = false;
+    for tag in tags(html) {
+        if !in_script {
+            push_text(&mut text, &html[pos..tag.start]);
+        }
+        pos = tag.end;
+        if tag.name == "script" || tag.name == "style" {
+            in_script = !tag.closing;
+        }
+        match tag.name.as_str() {
+            "br" => text.push('\n'),
+            "li" if !tag.closing => text.push_str("\n- "),
+            "p" | "div" | "ul" | "ol" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "tr"
+            | "section" => text.push_str("\n\n"),
+            _ => {}
+        }
+    }
+    push_text(&mut text, &html[pos.min(html.len())..]);
+
+    // At most one blank line in a row, no trailing spaces
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if !(line.is_empty() && lines.last().is_none_or(|last| last.is_empty())) {
+            lines.push(line);
+        }
+    }
+    lines.join("\n").trim().to_string()
+}
+
+fn push_text(text: &mut String, html: &str) {
+    let decoded = decode_entities(html);
+    for c in decoded.chars() {
+        if c.is_whitespace() {
+            if !text.ends_with([' ', '\n']) && !text.is_empty() {
+                text.push(' ');
+            }
+        } else {
+            text.push(c);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_reads_attributes_and_skips_comments_and_scripts() {
+        let html = r#"<!DOCTYPE html><!-- <p>not a tag</p> --><META Property="og:title" content='Dev &amp; Ops'>
+<script>if (a < b) { document.write("<div>") }</script><a href=/jobs/1 data-x>x</a>"#;
+        let tags = tags(html);
+        let names: Vec<(&str, bool)> = tags.iter().map(|t| (t.name.as_str(), t.closing)).collect();
+        assert_eq!(
+            names,
+            [
+                ("meta", false),
+                ("script", false),
+                ("script", true),
+                ("a", false),
+                ("a", true)
+            ]
+        );
+        assert_eq!(tags[0].attr("property"), Some("og:title"));
+        assert_eq!(tags[0].attr("content"), Some("Dev & Ops"));
+        assert_eq!(tags[3].attr("href"), Some("/jobs/1"));
+        assert_eq!(tags[3].attr("data-x"), Some(""));
+    }
+
+    #[test]
+    fn elements_returns_raw_contents() {
+        let html = "<title>Dev | Acme</title><script type=\"application/ld+json\">{\"a\": \"<b>\"}</script>";
+        let tags = tags(html);
+        assert_eq!(elements(html, &tags, "title")[0].1, "Dev | Acme");
+        let (script, json) = elements(html, &tags, "script")[0];
+        assert_eq!(script.attr("type"), Some("application/ld+json"));
+        assert_eq!(json, "{\"a\": \"<b>\"}");
+    }
+
+    #[test]
+    fn decode_entities_handles_named_numeric_and_stray_ampersands() {
+        assert_eq!(
+            decode_entities("R&amp;D &#8217;s &#x2014; &nbsp;ok"),
+            "R&D ’s —  ok"
+        );
+        assert_eq!(
+            decode_entities("AT&T; Q&A &bogus; &"),
+            "AT&T; Q&A &bogus; &"
+        );
+        assert_eq!(decode_entities("&#xD800; é&"), "&#xD800; é&");
+    }
+
+    #[test]
+    fn to_text_keeps_paragraphs_and_lists() {
+        let html = "<h2>About</h2><p>We   build\nthings.</p><ul><li>Rust</li><li>SQL</li></ul><style>p{}</style><br>Apply &gt; now";
+        assert_eq!(
+            to_text(html),
+            "About\n\nWe build things.\n\n- Rust\n- SQL\n\nApply > now"
+        );
+    }
+}
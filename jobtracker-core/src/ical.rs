@@ -0,0 +1,501 @@
+use crate::content_line::{escape, parse_line, unescape, unfold, write_line};
+use crate::CalendarImport;
+use crate::Interview;
+use crate::InterviewOutcome;
+use crate::Job;
+use crate::JobChange;
+use crate::JobStore;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::io::{Read, Write};
+
+const DATE_TIME: &str = "%Y%m%dT%H%M%S";
+
+impl Interview {
+    /// `UID` of the interview's calendar event: the one it was imported
+    /// with, or one made up from the job and interview IDs.
+    pub fn uid(&self, job_id: u32) -> String {
+        self.calendar_uid
+            .clone()
+            .unwrap_or_else(|| format!("job{job_id}-interview{}@jobtracker", self.id))
+    }
+
+    /// The interview as an iCalendar `VEVENT`. Times are written in UTC so
+    /// no `VTIMEZONE` definitions are needed.
+    pub fn to_vevent(&self, job: &Job, now: DateTime<Utc>) -> String {
+        let mut event = String::new();
+        write_line(&mut event, "BEGIN:VEVENT");
+        write_line(&mut event, &format!("UID:{}", escape(&self.uid(job.id))));
+        write_line(&mut event, &format!("DTSTAMP:{}Z", now.format(DATE_TIME)));
+        write_line(
+            &mut event,
+            &format!("DTSTART:{}Z", self.start.format(DATE_TIME)),
+        );
+        write_line(
+            &mut event,
+            &format!("DTEND:{}Z", self.end.format(DATE_TIME)),
+        );
+        write_line(
+            &mut event,
+            &format!(
+                "SUMMARY:{}",
+                escape(&format!("{}: {} - {}", self.round, job.company, job.role))
+            ),
+        );
+        if let Some(location) = &self.location {
+            write_line(&mut event, &format!("LOCATION:{}", escape(location)));
+        }
+        if !self.interviewers.is_empty() {
+            write_line(
+                &mut event,
+                &format!(
+                    "DESCRIPTION:{}",
+                    escape(&format!("Interviewers: {}", self.interviewers.join(", ")))
+                ),
+            );
+        }
+        if self.outcome == Some(InterviewOutcome::Cancelled) {
+            write_line(&mut event, "STATUS:CANCELLED");
+        }
+        write_line(&mut event, "END:VEVENT");
+        event
+    }
+}
+
+/// An event read from an `.ics` file, before it's matched to a job.
+#[derive(Default)]
+struct Event {
+    uid: Option<String>,
+    summary: String,
+    description: String,
+    /// `X-WR-TIMEZONE` of the calendar, for times given without a zone.
+    calendar_zone: Tz,
+    /// Start, and the zone it was given in if it had a `TZID`.
+    start: Option<(DateTime<Utc>, Option<Tz>)>,
+    end: Option<DateTime<Utc>>,
+    duration: Option<Duration>,
+    interviewers: Vec<String>,
+    location: Option<String>,
+    cancelled: bool,
+}
+
+/// Reads a `DTSTART` or `DTEND` value: UTC (`...Z`), local time in the
+/// zone named by `TZID`, or a date for all-day events. Times with neither
+/// are taken to be in `default_zone`. Returns the time and the `TZID`
+/// zone, or `None` for values or zones it can't make sense of.
+fn parse_time(
+    params: &[(String, String)],
+    value: &str,
+    default_zone: Tz,
+) -> Option<(DateTime<Utc>, Option<Tz>)> {
+    let param = |key: &str| {
+        params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    };
+    if let Some(utc) = value.strip_suffix('Z') {
+        let time = NaiveDateTime::parse_from_str(utc, DATE_TIME).ok()?;
+        return Some((time.and_utc(), None));
+    }
+    let zone = match param("TZID") {
+        Some(tzid) => Some(tzid.parse::<Tz>().ok()?),
+        None => None,
+    };
+    let local = if param("VALUE") == Some("DATE") || value.len() == 8 {
+        NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()?
+            .and_hms_opt(0, 0, 0)?
+    } else {
+        NaiveDateTime::parse_from_str(value, DATE_TIME).ok()?
+    };
+    let time = zone
+        .unwrap_or(default_zone)
+        .from_local_datetime(&local)
+        .earliest()?;
+    Some((time.with_timezone(&Utc), zone))
+}
+
+/// Reads the `P1DT1H30M` form of a `DURATION` value. Weeks and seconds
+/// are supported too; negative durations aren't.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let mut rest = value.strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut in_time = false;
+    while !rest.is_empty() {
+        if let Some(time) = rest.strip_prefix('T') {
+            in_time = true;
+            rest = time;
+            continue;
+        }
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let amount: i64 = rest[..digits].parse().ok()?;
+        let unit = rest[digits..].chars().next()?;
+        let part = match (unit, in_time) {
+            ('W', false) => Duration::try_weeks(amount),
+            ('D', false) => Duration::try_days(amount),
+            ('H', true) => Duration::try_hours(amount),
+            ('M', true) => Duration::try_minutes(amount),
+            ('S', true) => Duration::try_seconds(amount),
+            _ => None,
+        };
+        total = total.checked_add(&part?)?;
+        rest = &rest[digits + unit.len_utf8()..];
+    }
+    Some(total)
+}
+
+fn parse_events(text: &str) -> Vec<Event> {
+    let mut events = Vec::new();
+    let mut default_zone = Tz::UTC;
+    let mut event: Option<Event> = None;
+    let mut nested = 0;
+    for line in unfold(text).lines() {
+        let Some((property, params, value)) = parse_line(line) else {
+            continue;
+        };
+        // Skip alarms and other components nested in an event
+        if event.is_some() {
+            match property.as_str() {
+                "BEGIN" => nested += 1,
+                "END" if nested > 0 => nested -= 1,
+                _ => {}
+            }
+            if nested > 0 || (property == "END" && !value.eq_ignore_ascii_case("VEVENT")) {
+                continue;
+            }
+        }
+        match (property.as_str(), &mut event) {
+            ("X-WR-TIMEZONE", None) => {
+                default_zone = value.trim().parse().unwrap_or(default_zone);
+            }
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                event = Some(Event {
+                    calendar_zone: default_zone,
+                    ..Default::default()
+                });
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                events.extend(event.take());
+            }
+            ("UID", Some(event)) => event.uid = Some(unescape(value)),
+            ("SUMMARY", Some(event)) => event.summary = unescape(value).trim().to_string(),
+            ("DESCRIPTION", Some(event)) => event.description = unescape(value),
+            ("LOCATION", Some(event)) => {
+                let location = unescape(value).trim().to_string();
+                event.location = (!location.is_empty()).then_some(location);
+            }
+            ("DTSTART", Some(event)) => event.start = parse_time(&params, value, default_zone),
+            ("DTEND", Some(event)) => {
+                event.end = parse_time(&params, value, default_zone).map(|(end, _)| end);
+            }
+            ("DURATION", Some(event)) => event.duration = parse_duration(value),
+            ("ATTENDEE", Some(event)) => {
+                let name = params
+                    .iter()
+                    .find(|(key, _)| key == "CN")
+                    .map(|(_, name)| name.clone())
+                    .unwrap_or_else(|| value.trim_start_matches("mailto:").to_string());
+                if !name.is_empty() {
+                    event.interviewers.push(name);
+                }
+            }
+            ("STATUS", Some(event)) => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            _ => {}
+        }
+    }
+    events
+}
+
+/// The job whose company is named in the event, preferring the longest
+/// name so "Acme Robotics" wins over "Acme". `None` if there's no match or
+/// two different jobs are equally good ones.
+fn match_job<'a>(jobs: impl Iterator<Item = &'a Job>, event: &Event) -> Option<u32> {
+    let text = format!(
+        "{}\n{}\n{}",
+        event.summary,
+        event.description,
+        event.location.as_deref().unwrap_or_default()
+    )
+    .to_lowercase();
+    let mut best: Option<(&Job, bool)> = None;
+    for job in jobs {
+        let company = job.company.trim().to_lowercase();
+        if company.is_empty() || !text.contains(&company) {
+            continue;
+        }
+        best = match best {
+            Some((other, _)) if other.company.len() > job.company.len() => best,
+            Some((other, _)) if other.company.len() == job.company.len() => Some((other, true)),
+            _ => Some((job, false)),
+        };
+    }
+    match best {
+        Some((job, false)) => Some(job.id),
+        _ => None,
+    }
+}
+
+impl JobStore {
+    /// Adds the events in an `.ics` file as interviews. Each event goes to
+    /// `job_id` if given, otherwise to the application whose company its
+    /// summary, description or location names. Events imported before, or
+    /// exported from here, reschedule their interview instead of adding
+    /// another; its round and outcome are kept.
+    /// The whole import is undone as one command.
+    pub fn import_ics<R: Read>(
+        &mut self,
+        mut reader: R,
+        job_id: Option<u32>,
+    ) -> Result<CalendarImport> {
+        if let Some(id) = job_id {
+            self.find_job(id)?;
+        }
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        if !text.to_ascii_uppercase().contains("BEGIN:VCALENDAR") {
+            bail!("Not an iCalendar file: there's no BEGIN:VCALENDAR");
+        }
+
+        let mut report = CalendarImport::default();
+        let mut jobs = self.jobs.clone();
+        for event in parse_events(&text) {
+            let label = if event.summary.is_empty() {
+                "(no title)".to_string()
+            } else {
+                event.summary.clone()
+            };
+            let Some((start, timezone)) = event.start else {
+                report.unmatched.push(label);
+                continue;
+            };
+            let existing = event.uid.as_ref().and_then(|uid| {
+                jobs.iter()
+                    .filter(|job| job.deleted_at.is_none())
+                    .find_map(|job| {
+                        let interview = job.interviews.iter().find(|i| &i.uid(job.id) == uid)?;
+                        Some((job.id, interview.id))
+                    })
+            });
+            let target = existing
+                .map(|(id, _)| id)
+                .or(job_id)
+                .or_else(|| match_job(jobs.iter().filter(|job| job.deleted_at.is_none()), &event));
+            let Some(target) = target else {
+                report.unmatched.push(label);
+                continue;
+            };
+            let job = jobs.iter_mut().find(|job| job.id == target).unwrap();
+
+            let end = event
+                .end
+                .or(event.duration.map(|duration| start + duration))
+                .filter(|end| *end >= start)
+                .unwrap_or(start + Duration::hours(1));
+            let existing =
+                existing.and_then(|(_, id)| job.interviews.iter_mut().find(|i| i.id == id));
+            let interview = match existing {
+                Some(old) => {
+                    old.start = start;
+                    old.end = end;
+                    old.timezone = timezone.unwrap_or(old.timezone);
+                    if !event.interviewers.is_empty() {
+                        old.interviewers = event.interviewers;
+                    }
+                    old.location = event.location.or(old.location.take());
+                    if event.cancelled {
+                        old.outcome = Some(InterviewOutcome::Cancelled);
+                    }
+                    old.clone()
+                }
+                None => {
+                    let interview = Interview {
+                        id: job.interviews.iter().map(|i| i.id).max().unwrap_or(0) + 1,
+                        round: label,
+                        start,
+                        end,
+                        timezone: timezone.unwrap_or(event.calendar_zone),
+                        interviewers: event.interviewers,
+                        location: event.location,
+                        outcome: event.cancelled.then_some(InterviewOutcome::Cancelled),
+                        calendar_uid: event.uid,
+                    };
+                    job.interviews.push(interview.clone());
+                    interview
+                }
+            };
+            report.imported.push((target, interview));
+        }
+
+        let changes: Vec<JobChange> = self
+            .jobs
+            .iter()
+            .zip(&jobs)
+            .filter(|(before, after)| before != after)
+            .map(|(before, after)| JobChange {
+                before: Some(before.clone()),
+                after: Some(after.clone()),
+            })
+            .collect();
+        self.jobs = jobs;
+        self.record("Import interviews", changes)?;
+        Ok(report)
+    }
+
+    /// Writes the interviews of every application outside the trash as an
+    /// iCalendar file.
+    pub fn export_ics<W: Write>(&self, mut writer: W, now: DateTime<Utc>) -> Result<()> {
+        let mut calendar = String::new();
+        write_line(&mut calendar, "BEGIN:VCALENDAR");
+        write_line(&mut calendar, "VERSION:2.0");
+        write_line(&mut calendar, "PRODID:-//jobtracker//Interviews//EN");
+        let mut interviews: Vec<(&Job, &Interview)> = self
+            .active_jobs()
+            .flat_map(|job| job.interviews.iter().map(move |interview| (job, interview)))
+            .collect();
+        interviews.sort_by_key(|(_, interview)| interview.start);
+        for (job, interview) in interviews {
+            calendar.push_str(&interview.to_vevent(job, now));
+        }
+        write_line(&mut calendar, "END:VCALENDAR");
+        writer.write_all(calendar.as_bytes())?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(text, DATE_TIME)
+            .unwrap()
+            .and_utc()
+    }
+
+    fn calendar(events: &str) -> String {
+        format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{events}END:VCALENDAR\r\n")
+    }
+
+    #[test]
+    fn parse_duration_reads_each_unit() {
+        assert_eq!(parse_duration("PT45M"), Some(Duration::minutes(45)));
+        assert_eq!(
+            parse_duration("P1DT1H30M"),
+            Some(Duration::days(1) + Duration::minutes(90))
+        );
+        assert_eq!(parse_duration("P2W"), Some(Duration::weeks(2)));
+        assert_eq!(parse_duration("PT90S"), Some(Duration::seconds(90)));
+        assert_eq!(parse_duration("P"), Some(Duration::zero()));
+    }
+
+    #[test]
+    fn parse_duration_rejects_bad_values() {
+        for value in [
+            "",
+            "1H",
+            "-PT1H",
+            "PT1",
+            "P1H",
+            "PT1D",
+            "P99999999999999999999W",
+            "P9223372036854775807W",
+            "PT1é",
+            "P1日",
+        ] {
+            assert_eq!(parse_duration(value), None, "{value}");
+        }
+    }
+
+    #[test]
+    fn parse_time_reads_utc_zoned_floating_and_all_day() {
+        let none = params(&[]);
+        assert_eq!(
+            parse_time(&none, "20260110T150000Z", Tz::UTC),
+            Some((utc("20260110T150000"), None))
+        );
+        let berlin = params(&[("TZID", "Europe/Berlin")]);
+        assert_eq!(
+            parse_time(&berlin, "20260110T150000", Tz::UTC),
+            Some((utc("20260110T140000"), Some(chrono_tz::Europe::Berlin)))
+        );
+        assert_eq!(
+            parse_time(&none, "20260710T150000", chrono_tz::America::New_York),
+            Some((utc("20260710T190000"), None))
+        );
+        let date = params(&[("VALUE", "DATE")]);
+        assert_eq!(
+            parse_time(&date, "20260110", Tz::UTC),
+            Some((utc("20260110T000000"), None))
+        );
+        assert_eq!(parse_time(&none, "tomorrow", Tz::UTC), None);
+        let unknown = params(&[("TZID", "Mars/Olympus")]);
+        assert_eq!(parse_time(&unknown, "20260110T090000", Tz::UTC), None);
+    }
+
+    #[test]
+    fn import_matches_and_reschedules() {
+        let mut store = JobStore::default();
+        store
+            .add_job("Acme".into(), "Dev".into(), String::new(), String::new())
+            .unwrap();
+        store
+            .add_job(
+                "Acme Robotics".into(),
+                "Dev".into(),
+                String::new(),
+                String::new(),
+            )
+            .unwrap();
+        let events = "BEGIN:VEVENT\r\nUID:one\r\nSUMMARY:Screen with Acme Robotics\r\nDTSTART:20260110T150000Z\r\nDURATION:PT30M\r\n\
+BEGIN:VALARM\r\nTRIGGER:-PT15M\r\nEND:VALARM\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nUID:two\r\nSUMMARY:Dentist\r\nDTSTART:20260111T150000Z\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nUID:three\r\nSUMMARY:Acme onsite\r\nDTSTART;TZID=Mars/Olympus:20260112T090000\r\nEND:VEVENT\r\n";
+        let report = store.import_ics(calendar(events).as_bytes(), None).unwrap();
+        assert_eq!(report.imported.len(), 1);
+        let (job_id, interview) = &report.imported[0];
+        assert_eq!(*job_id, 2);
+        assert_eq!(interview.end - interview.start, Duration::minutes(30));
+        assert_eq!(report.unmatched, ["Dentist", "Acme onsite"]);
+
+        let moved = "BEGIN:VEVENT\r\nUID:one\r\nSUMMARY:Screen with Acme Robotics\r\nDTSTART:20260113T150000Z\r\nSTATUS:CANCELLED\r\nEND:VEVENT\r\n";
+        store.import_ics(calendar(moved).as_bytes(), None).unwrap();
+        let interviews = &store.jobs[1].interviews;
+        assert_eq!(interviews.len(), 1);
+        assert_eq!(interviews[0].start, utc("20260113T150000"));
+        assert_eq!(interviews[0].outcome, Some(InterviewOutcome::Cancelled));
+    }
+
+    #[test]
+    fn exported_interviews_import_as_the_same_ones() {
+        let mut store = JobStore::default();
+        store
+            .add_job("Acme".into(), "Dev".into(), String::new(), String::new())
+            .unwrap();
+        let events = "BEGIN:VEVENT\r\nSUMMARY:Acme, phone screen\r\nDTSTART:20260110T150000Z\r\nEND:VEVENT\r\n";
+        store
+            .import_ics(calendar(events).as_bytes(), Some(1))
+            .unwrap();
+        let mut file = Vec::new();
+        store.export_ics(&mut file, utc("20260101T000000")).unwrap();
+        let report = store.import_ics(&file[..], None).unwrap();
+        assert_eq!(report.imported.len(), 1);
+        assert_eq!(store.jobs[0].interviews.len(), 1);
+        assert_eq!(store.jobs[0].interviews[0].round, "Acme, phone screen");
+    }
+
+    #[test]
+    fn import_needs_a_calendar() {
+        let mut store = JobStore::default();
+        assert!(store.import_ics(&b"BEGIN:VCARD\r\n"[..], None).is_err());
+    }
+}
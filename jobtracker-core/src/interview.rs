@@ -0,0 +1,122 @@
+use crate::Interview;
+use crate::InterviewOutcome;
+use crate::Job;
+use crate::JobStore;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// The time zone this computer is set to, for interviews entered without
+/// one. Fails if the system doesn't say or names a zone chrono-tz doesn't
+/// know, so a time is never quietly read in the wrong zone.
+pub fn local_timezone() -> Result<Tz> {
+    let name = iana_time_zone::get_timezone()
+        .context("Couldn't tell this computer's time zone, give one such as Europe/Berlin")?;
+    name.parse().map_err(|_| {
+        anyhow!("This computer's time zone {name} isn't known, give one such as Europe/Berlin")
+    })
+}
+
+impl Interview {
+    /// An interview starting at `start` local time in `timezone` and
+    /// lasting `minutes`. Fails if that local time doesn't exist, e.g. in a
+    /// daylight saving gap.
+    pub fn new(round: String, start: NaiveDateTime, minutes: i64, timezone: Tz) -> Result<Self> {
+        let start = timezone
+            .from_local_datetime(&start)
+            .earliest()
+            .ok_or_else(|| anyhow!("{start} doesn't exist in {timezone}"))?
+            .with_timezone(&Utc);
+        Ok(Self {
+            id: 0,
+            round,
+            start,
+            end: start + Duration::minutes(minutes),
+            timezone,
+            interviewers: Vec::new(),
+            location: None,
+            outcome: None,
+            calendar_uid: None,
+        })
+    }
+
+    /// Still to come at `now`, and not cancelled.
+    pub fn is_upcoming(&self, now: DateTime<Utc>) -> bool {
+        self.end >= now && self.outcome != Some(InterviewOutcome::Cancelled)
+    }
+}
+
+impl JobStore {
+    /// Interviews of applications outside the trash that are still to come
+    /// at `now`, soonest first.
+    pub fn upcoming_interviews(&self, now: DateTime<Utc>) -> Vec<(&Job, &Interview)> {
+        let mut upcoming: Vec<(&Job, &Interview)> = self
+            .active_jobs()
+            .flat_map(|job| job.interviews.iter().map(move |interview| (job, interview)))
+            .filter(|(_, interview)| interview.is_upcoming(now))
+            .collect();
+        upcoming.sort_by_key(|(_, interview)| interview.start);
+        upcoming
+    }
+
+    /// Schedules `interview` for job `id` under the next free interview ID,
+    /// which is returned.
+    pub fn add_interview(&mut self, id: u32, mut interview: Interview) -> Result<u32> {
+        check_interview(&interview)?;
+        let job = self.find_job(id)?;
+        interview.id = job.interviews.iter().map(|i| i.id).max().unwrap_or(0) + 1;
+        let interview_id = interview.id;
+        self.modify_job(id, "Schedule interview", |job| {
+            job.interviews.push(interview)
+        })?;
+        Ok(interview_id)
+    }
+
+    /// Overwrites the interview of job `id` with the same ID as `interview`.
+    pub fn update_interview(&mut self, id: u32, interview: Interview) -> Result<()> {
+        check_interview(&interview)?;
+        self.find_interview(id, interview.id)?;
+        self.modify_job(id, "Edit interview", |job| {
+            if let Some(existing) = job.interviews.iter_mut().find(|i| i.id == interview.id) {
+                *existing = interview;
+            }
+        })
+    }
+
+    /// Records how interview `interview_id` of job `id` went.
+    pub fn set_interview_outcome(
+        &mut self,
+        id: u32,
+        interview_id: u32,
+        outcome: Option<InterviewOutcome>,
+    ) -> Result<()> {
+        let mut interview = self.find_interview(id, interview_id)?.clone();
+        interview.outcome = outcome;
+        self.update_interview(id, interview)
+    }
+
+    pub fn delete_interview(&mut self, id: u32, interview_id: u32) -> Result<()> {
+        self.find_interview(id, interview_id)?;
+        self.modify_job(id, "Delete interview", |job| {
+            job.interviews.retain(|i| i.id != interview_id)
+        })
+    }
+
+    fn find_interview(&self, id: u32, interview_id: u32) -> Result<&Interview> {
+        self.find_job(id)?
+            .interviews
+            .iter()
+            .find(|interview| interview.id == interview_id)
+            .ok_or_else(|| anyhow!("Job {id} has no interview {interview_id}"))
+    }
+}
+
+fn check_interview(interview: &Interview) -> Result<()> {
+    if interview.round.trim().is_empty() {
+        bail!("An interview needs a round name, e.g. \"Phone screen\"");
+    }
+    if interview.end < interview.start {
+        bail!("An interview can't end before it starts");
+    }
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use crate::InterviewOutcome;
+use std::fmt;
+use std::str::FromStr;
+
+impl fmt::Display for InterviewOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InterviewOutcome::Passed => write!(f, "Passed"),
+            InterviewOutcome::Failed => write!(f, "Failed"),
+            InterviewOutcome::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+impl FromStr for InterviewOutcome {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "passed" => Ok(InterviewOutcome::Passed),
+            "failed" => Ok(InterviewOutcome::Failed),
+            "cancelled" | "canceled" => Ok(InterviewOutcome::Cancelled),
+            _ => Err(format!("Unknown interview outcome: {s}")),
+        }
+    }
+}
@@ -1,14 +1,125 @@
-use crate::{Job, JobStatus, CYAN, GRAY, GREEN, NAVY_BLUE, RED};
-use eframe::egui::Color32;
+use crate::{Job, JobStatus, StatusChange};
+use chrono::{DateTime, Utc};
 
 impl Job {
-    pub fn get_status_color_mapping(&self) -> Color32 {
-        match self.status {
-            JobStatus::Applied => NAVY_BLUE,
-            JobStatus::Interview => CYAN,
-            JobStatus::Offer => GREEN,
-            JobStatus::Rejected => RED,
-            JobStatus::Ghosted => GRAY,
+    /// An application that entered the tracker in `status` at `timestamp`,
+    /// with the matching first history entry.
+    pub fn new(
+        id: u32,
+        company: String,
+        role: String,
+        status: JobStatus,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            company,
+            role,
+            status: status.clone(),
+            timestamp,
+            history: vec![StatusChange {
+                from: None,
+                to: status,
+                at: timestamp,
+                note: None,
+                automatic: false,
+                correction: false,
+            }],
+            ..Default::default()
         }
     }
+
+    /// Moves the application to `new_status`, recording the transition in
+    /// its history. Returns the new history entry, or `None` if the
+    /// application was already in that status.
+    pub fn transition(
+        &mut self,
+        new_status: JobStatus,
+        at: DateTime<Utc>,
+        note: Option<String>,
+    ) -> Option<&mut StatusChange> {
+        if self.status == new_status {
+            return None;
+        }
+        self.history.push(StatusChange {
+            from: Some(self.status.clone()),
+            to: new_status.clone(),
+            at,
+            note,
+            automatic: false,
+            correction: false,
+        });
+        self.status = new_status;
+        self.history.last_mut()
+    }
+
+    /// Reverts the latest status change if the tracker made it on its own.
+    /// The revert is recorded as a change of its own so the clock that
+    /// triggered the automatic change starts over. Returns whether anything
+    /// was reverted.
+    pub fn undo_automatic_transition(&mut self, at: DateTime<Utc>) -> bool {
+        let Some(StatusChange {
+            from: Some(from),
+            automatic: true,
+            ..
+        }) = self.history.last()
+        else {
+            return false;
+        };
+        let from = from.clone();
+        self.transition(from, at, Some("Reverted automatic change".to_string()));
+        true
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// Whether the search box text matches this application. `search_text`
+    /// is expected in lowercase; empty text matches everything.
+    pub fn matches_search(&self, search_text: &str) -> bool {
+        search_text.is_empty()
+            || self.company.to_lowercase().contains(search_text)
+            || self.role.to_lowercase().contains(search_text)
+            || self.status.to_string().to_lowercase().contains(search_text)
+            || self
+                .role_location
+                .clone()
+                .unwrap_or_default()
+                .to_lowercase()
+                .contains(search_text)
+            || self
+                .notes
+                .iter()
+                .any(|note| note.text.to_lowercase().contains(search_text))
+            || [&self.posting_url, &self.description]
+                .into_iter()
+                .flatten()
+                .any(|text| text.to_lowercase().contains(search_text))
+            || self.tags.iter().any(|tag| tag.contains(search_text))
+            || self
+                .location
+                .to_string()
+                .to_lowercase()
+                .contains(search_text)
+    }
+
+    /// When the application first heard back: the first status change
+    /// after it was added that the user made, rather than the tracker on
+    /// its own. `None` if it's still waiting.
+    pub fn first_response(&self) -> Option<DateTime<Utc>> {
+        self.history
+            .iter()
+            .find(|change| change.from.is_some() && !change.automatic)
+            .map(|change| change.at)
+    }
+
+    /// When the application last changed status, or was added if it never
+    /// has.
+    pub fn last_status_change(&self) -> DateTime<Utc> {
+        self.history
+            .last()
+            .map(|change| change.at)
+            .unwrap_or(self.timestamp)
+    }
 }
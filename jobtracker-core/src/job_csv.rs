@@ -0,0 +1,369 @@
+use crate::parse_tags;
+use crate::CsvColumns;
+use crate::ImportReport;
+use crate::Job;
+use crate::JobChange;
+use crate::JobStore;
+use crate::Location;
+use crate::SkippedRow;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use std::collections::HashSet;
+use std::io::{Read, Write};
+
+/// Note on the first history entry of jobs imported in a status other than
+/// Applied, since when they got there is unknown.
+const IMPORTED_NOTE: &str = "Imported from CSV";
+
+impl Default for CsvColumns {
+    fn default() -> Self {
+        Self {
+            company: "Company".to_string(),
+            role: "Role".to_string(),
+            location: "Location".to_string(),
+            status: "Status".to_string(),
+            source: "Source".to_string(),
+            date_applied: "Date Applied".to_string(),
+            notes: "Notes".to_string(),
+            tags: "Tags".to_string(),
+        }
+    }
+}
+
+impl CsvColumns {
+    fn headers(&self) -> [&str; 8] {
+        [
+            &self.company,
+            &self.role,
+            &self.location,
+            &self.status,
+            &self.source,
+            &self.date_applied,
+            &self.notes,
+            &self.tags,
+        ]
+    }
+}
+
+/// Accepts RFC 3339 (what export writes) as well as the date formats
+/// spreadsheets commonly produce. Dates without a time are taken as midnight
+/// UTC.
+fn parse_date_applied(text: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Some(date.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%m/%d/%Y %H:%M"] {
+        if let Ok(date) = NaiveDateTime::parse_from_str(text, format) {
+            return Some(date.and_utc());
+        }
+    }
+    for format in ["%Y-%m-%d", "%m/%d/%Y", "%m/%d/%y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(text, format) {
+            return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+        }
+    }
+    None
+}
+
+/// All of a job's notes in one cell, each starting with its timestamp in
+/// brackets on a line of its own so [`parse_notes`] can split them again.
+fn format_notes(job: &Job) -> String {
+    let notes: Vec<String> = job
+        .notes
+        .iter()
+        .map(|note| format!("[{}]\n{}", note.at.to_rfc3339(), note.text))
+        .collect();
+    notes.join("\n\n")
+}
+
+/// Reverses [`format_notes`]. Text before the first timestamp line, or a
+/// cell without any, becomes a single note written at `fallback`.
+fn parse_notes(cell: &str, fallback: DateTime<Utc>) -> Vec<(DateTime<Utc>, String)> {
+    let mut notes: Vec<(DateTime<Utc>, String)> = Vec::new();
+    for line in cell.lines() {
+        let stamp = line
+            .trim()
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+            .and_then(|stamp| DateTime::parse_from_rfc3339(stamp).ok());
+        match (stamp, notes.last_mut()) {
+            (Some(at), _) => notes.push((at.with_timezone(&Utc), String::new())),
+            (None, Some((_, text))) => {
+                text.push_str(line);
+                text.push('\n');
+            }
+            (None, None) => notes.push((fallback, format!("{line}\n"))),
+        }
+    }
+    notes
+        .into_iter()
+        .map(|(at, text)| (at, text.trim().to_string()))
+        .filter(|(_, text)| !text.is_empty())
+        .collect()
+}
+
+/// Two applications count as the same if they're for the same role at the
+/// same company on the same day.
+fn duplicate_key(job: &Job) -> (String, String, NaiveDate) {
+    (
+        job.company.trim().to_lowercase(),
+        job.role.trim().to_lowercase(),
+        job.timestamp.date_naive(),
+    )
+}
+
+impl JobStore {
+    /// Reads jobs from CSV with a header row, using `columns` to find each
+    /// field. Rows missing a company, role or parseable date are skipped, as
+    /// are duplicates. With `dry_run` nothing is added and the report shows
+    /// what an import would do.
+    pub fn import_csv<R: Read>(
+        &mut self,
+        reader: R,
+        columns: &CsvColumns,
+        dry_run: bool,
+    ) -> Result<ImportReport> {
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+        let headers = reader.headers()?.clone();
+        let find = |name: &str| {
+            headers
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(name.trim()))
+        };
+        let [company, role, location, status, source, date_applied, notes, tags] =
+            columns.headers().map(find);
+        let company = company.ok_or_else(|| anyhow!("No \"{}\" column", columns.company))?;
+        let role = role.ok_or_else(|| anyhow!("No \"{}\" column", columns.role))?;
+        let date_applied =
+            date_applied.ok_or_else(|| anyhow!("No \"{}\" column", columns.date_applied))?;
+
+        let mut report = ImportReport {
+            dry_run,
+            ..Default::default()
+        };
+        let mut seen: HashSet<_> = self.active_jobs().map(duplicate_key).collect();
+        let mut next_id = self.next_id();
+        let initial_status = self.initial_status();
+        for record in reader.records() {
+            let record = record?;
+            let line = record.position().map_or(0, |p| p.line());
+            let field = |idx: Option<usize>| {
+                idx.and_then(|i| record.get(i))
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+            };
+            let skip = |reason: String| SkippedRow { line, reason };
+
+            let (Some(company), Some(role)) = (field(Some(company)), field(Some(role))) else {
+                report
+                    .skipped
+                    .push(skip("Missing company or role".to_string()));
+                continue;
+            };
+            let Some(timestamp) = field(Some(date_applied)).and_then(parse_date_applied) else {
+                report
+                    .skipped
+                    .push(skip("Missing or unreadable date applied".to_string()));
+                continue;
+            };
+            let status = match field(status).map(|status| self.parse_status(status)) {
+                None => initial_status.clone(),
+                Some(Ok(status)) => status,
+                Some(Err(e)) => {
+                    report.skipped.push(skip(e.to_string()));
+                    continue;
+                }
+            };
+            // Exports from before sources were user-defined wrote "Not
+            // provided" for no source
+            let source =
+                field(source).filter(|source| !source.eq_ignore_ascii_case("not provided"));
+            let source = match source.map(|source| self.parse_source(source)) {
+                None => None,
+                Some(Ok(source)) => Some(source),
+                Some(Err(e)) => {
+                    report.skipped.push(skip(e.to_string()));
+                    continue;
+                }
+            };
+
+            let mut job = Job {
+                role_location: field(location).map(str::to_string),
+                location: Location::parse(field(location).unwrap_or_default()),
+                source,
+                ..Job::new(
+                    next_id,
+                    company.to_string(),
+                    role.to_string(),
+                    status,
+                    timestamp,
+                )
+            };
+            for (at, text) in field(notes).map_or_else(Vec::new, |n| parse_notes(n, timestamp)) {
+                job.add_note(text, at);
+            }
+            job.tags = field(tags).map_or_else(Default::default, parse_tags);
+            if job.status != initial_status {
+                job.history[0].note = Some(IMPORTED_NOTE.to_string());
+            }
+            if !seen.insert(duplicate_key(&job)) {
+                report.duplicates.push(skip(format!(
+                    "{} - {} on {} is already tracked or earlier in the file",
+                    job.company,
+                    job.role,
+                    job.timestamp.date_naive()
+                )));
+                continue;
+            }
+            next_id += 1;
+            report.imported.push(job);
+        }
+
+        if !dry_run {
+            self.jobs.extend(report.imported.iter().cloned());
+            let changes = report
+                .imported
+                .iter()
+                .map(|job| JobChange {
+                    before: None,
+                    after: Some(job.clone()),
+                })
+                .collect();
+            self.record("Import CSV", changes)?;
+        }
+        Ok(report)
+    }
+
+    /// Writes every job outside the trash as CSV with a header row named after `columns`.
+    pub fn export_csv<W: Write>(&self, writer: W, columns: &CsvColumns) -> Result<()> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(columns.headers())?;
+        for job in self.active_jobs() {
+            writer.write_record([
+                job.company.clone(),
+                job.role.clone(),
+                job.role_location.clone().unwrap_or_default(),
+                job.status.to_string(),
+                job.source
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+                job.timestamp.to_rfc3339(),
+                format_notes(job),
+                job.tags.iter().cloned().collect::<Vec<_>>().join(", "),
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JobSource, JobStatus};
+
+    const ROWS: &str = "Company,Role,Location,Status,Source,Date Applied,Tags
+Acme,Dev,\"Austin, TX\",Interview,LinkedIn,2026-01-02,\"Rust, remote,rust\"
+acme , dev,,,,01/02/2026,
+Beta,Ops,,Hired,,2026-01-03,
+Gamma,QA,,,Newspaper,2026-01-04,
+Delta,PM,,,,someday,
+,Dev,,,,2026-01-05,
+Epsilon,SRE,,applied,Not provided,2026-01-06 09:30,
+";
+
+    fn companies(jobs: &[Job]) -> Vec<(u32, &str)> {
+        jobs.iter()
+            .map(|job| (job.id, job.company.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn dry_run_reports_without_importing() {
+        let mut store = JobStore::default();
+        let report = store
+            .import_csv(ROWS.as_bytes(), &CsvColumns::default(), true)
+            .unwrap();
+        assert!(report.dry_run);
+        assert!(store.jobs.is_empty());
+        assert!(!store.undo_history.can_undo());
+
+        assert_eq!(companies(&report.imported), [(1, "Acme"), (2, "Epsilon")]);
+        let skipped: Vec<u64> = report.skipped.iter().map(|row| row.line).collect();
+        assert_eq!(skipped, [4, 5, 6, 7]);
+        assert_eq!(report.duplicates.len(), 1);
+        assert_eq!(report.duplicates[0].line, 3);
+
+        let acme = &report.imported[0];
+        assert_eq!(acme.status, JobStatus::INTERVIEW);
+        assert_eq!(acme.source, Some(JobSource::LINKEDIN));
+        assert_eq!(acme.history[0].note.as_deref(), Some(IMPORTED_NOTE));
+        assert_eq!(acme.role_location.as_deref(), Some("Austin, TX"));
+        assert_eq!(acme.location.city.as_deref(), Some("Austin"));
+        let tags: Vec<&str> = acme.tags.iter().map(String::as_str).collect();
+        assert_eq!(tags, ["remote", "rust"]);
+        let epsilon = &report.imported[1];
+        assert_eq!(epsilon.status, JobStatus::APPLIED);
+        assert_eq!(epsilon.history[0].note, None);
+        assert_eq!(epsilon.source, None);
+        assert_eq!(epsilon.timestamp.to_rfc3339(), "2026-01-06T09:30:00+00:00");
+    }
+
+    #[test]
+    fn real_run_imports_what_the_dry_run_reported() {
+        let mut store = JobStore::default();
+        let dry_run = store
+            .import_csv(ROWS.as_bytes(), &CsvColumns::default(), true)
+            .unwrap();
+        let report = store
+            .import_csv(ROWS.as_bytes(), &CsvColumns::default(), false)
+            .unwrap();
+        assert!(!report.dry_run);
+        assert_eq!(companies(&report.imported), companies(&dry_run.imported));
+        assert_eq!(companies(&store.jobs), companies(&report.imported));
+
+        let again = store
+            .import_csv(ROWS.as_bytes(), &CsvColumns::default(), false)
+            .unwrap();
+        assert!(again.imported.is_empty());
+        assert_eq!(again.duplicates.len(), 3);
+        assert_eq!(store.jobs.len(), 2);
+
+        assert_eq!(store.undo().unwrap().as_deref(), Some("Import CSV"));
+        assert!(store.jobs.is_empty());
+    }
+
+    #[test]
+    fn exports_read_back_in() {
+        let mut store = JobStore::default();
+        store
+            .import_csv(ROWS.as_bytes(), &CsvColumns::default(), false)
+            .unwrap();
+        let mut data = Vec::new();
+        store.export_csv(&mut data, &CsvColumns::default()).unwrap();
+
+        let report = JobStore::default()
+            .import_csv(&data[..], &CsvColumns::default(), true)
+            .unwrap();
+        assert_eq!(companies(&report.imported), companies(&store.jobs));
+        for (read, job) in report.imported.iter().zip(&store.jobs) {
+            assert_eq!(read.timestamp, job.timestamp);
+            assert_eq!(read.status, job.status);
+            assert_eq!(read.role_location, job.role_location);
+            assert_eq!(read.tags, job.tags);
+        }
+    }
+
+    #[test]
+    fn missing_required_columns_fail() {
+        let error = JobStore::default()
+            .import_csv(
+                &b"Company,Title,Date Applied\n"[..],
+                &CsvColumns::default(),
+                true,
+            )
+            .unwrap_err();
+        assert_eq!(error.to_string(), "No \"Role\" column");
+    }
+}
@@ -0,0 +1,223 @@
+use crate::DataFileError;
+use crate::Job;
+use crate::JobDocument;
+use crate::Location;
+use crate::Source;
+use crate::Stage;
+use anyhow::{bail, Result};
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// Upgrades from each older format version, indexed by the version they
+/// upgrade from: `UPGRADES[n]` turns a version `n` document into version
+/// `n + 1`. Append a step whenever the shape of `JobDocument` or `Job`
+/// changes in a way old files can't deserialize into.
+const UPGRADES: &[fn(Value) -> Value] = &[
+    wrap_bare_array,
+    seed_status_history,
+    seed_stages,
+    parse_locations,
+    name_sources,
+];
+
+/// Note on the history entry given to jobs that predate status history.
+pub(crate) const IMPORTED_HISTORY_NOTE: &str = "Status when history tracking began";
+
+/// Format version written by this build.
+pub const JSON_FORMAT_VERSION: u32 = UPGRADES.len() as u32;
+
+/// Version 0 files are a bare array of jobs.
+fn wrap_bare_array(jobs: Value) -> Value {
+    json!({ "version": 1, "jobs": jobs })
+}
+
+/// Version 1 jobs have no status history. Start each one with its current
+/// status as of when it was added, since when it got there is unknown.
+fn seed_status_history(mut document: Value) -> Value {
+    if let Some(jobs) = document["jobs"].as_array_mut() {
+        for job in jobs {
+            job["history"] = json!([{
+                "from": null,
+                "to": job["status"],
+                "at": job["timestamp"],
+                "note": IMPORTED_HISTORY_NOTE,
+            }]);
+        }
+    }
+    document["version"] = json!(2);
+    document
+}
+
+/// Version 2 files predate configurable stages and always used the five
+/// built-in ones.
+fn seed_stages(mut document: Value) -> Value {
+    document["stages"] = json!(Stage::defaults());
+    document["version"] = json!(3);
+    document
+}
+
+/// Version 3 jobs only have their location as free text. Parse it into
+/// `location`.
+fn parse_locations(mut document: Value) -> Value {
+    if let Some(jobs) = document["jobs"].as_array_mut() {
+        for job in jobs {
+            let text = job["role_location"].as_str().unwrap_or_default();
+            job["location"] = json!(Location::parse(text));
+        }
+    }
+    document["version"] = json!(4);
+    document
+}
+
+/// Version 4 jobs name their source by what used to be a fixed list of
+/// variants, with `NotProvided` for none, and there is no list of sources.
+fn name_sources(mut document: Value) -> Value {
+    if let Some(jobs) = document["jobs"].as_array_mut() {
+        for job in jobs {
+            job["source"] = match job["source"].as_str() {
+                Some("NotProvided") | None => Value::Null,
+                Some("Talent") => json!("Talent.com"),
+                Some(source) => json!(source),
+            };
+        }
+    }
+    document["sources"] = json!(Source::defaults());
+    document["version"] = json!(5);
+    document
+}
+
+impl Default for JobDocument {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl JobDocument {
+    pub fn new(jobs: Vec<Job>) -> Self {
+        Self {
+            version: JSON_FORMAT_VERSION,
+            jobs,
+            stages: Stage::defaults(),
+            contacts: Vec::new(),
+            sources: Source::defaults(),
+        }
+    }
+
+    /// Parses a data file of any known format version, upgrading it to the
+    /// current one. `path` is only used for error messages.
+    pub fn from_json(data: &str, path: &Path) -> Result<Self> {
+        let mut value: Value = serde_json::from_str(data)?;
+        let mut version = match &value {
+            Value::Array(_) => 0,
+            Value::Object(map) if map.contains_key("version") => match map["version"].as_u64() {
+                // Too big for a u32 is still newer than any version so far
+                Some(version) => {
+                    u32::try_from(version).map_err(|_| DataFileError::NewerVersion {
+                        path: path.to_path_buf(),
+                        found: version,
+                        supported: JSON_FORMAT_VERSION,
+                    })?
+                }
+                None => bail!(DataFileError::UnrecognizedFormat {
+                    path: path.to_path_buf(),
+                }),
+            },
+            _ => bail!(DataFileError::UnrecognizedFormat {
+                path: path.to_path_buf(),
+            }),
+        };
+        if version > JSON_FORMAT_VERSION {
+            bail!(DataFileError::NewerVersion {
+                path: path.to_path_buf(),
+                found: version.into(),
+                supported: JSON_FORMAT_VERSION,
+            });
+        }
+        while version < JSON_FORMAT_VERSION {
+            value = UPGRADES[version as usize](value);
+            version += 1;
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JobSource, JobStatus};
+
+    const PATH: &str = "jobs.json";
+
+    #[test]
+    fn upgrades_a_bare_job_list() {
+        let data = r#"[
+            {"id": 1, "company": "Acme", "role": "Dev", "role_location": "Remote - US",
+             "status": "Ghosted", "timestamp": "2024-01-02T03:04:05Z", "source": "NotProvided"},
+            {"id": 2, "company": "Initech", "role": "QA", "role_location": null,
+             "status": "Applied", "timestamp": "2024-02-03T04:05:06Z", "source": "Talent"}
+        ]"#;
+        let document = JobDocument::from_json(data, Path::new(PATH)).unwrap();
+        assert_eq!(document.version, JSON_FORMAT_VERSION);
+        assert_eq!(document.stages, Stage::defaults());
+        assert_eq!(document.sources, Source::defaults());
+
+        let [acme, initech] = &document.jobs[..] else {
+            panic!("expected two jobs, got {:?}", document.jobs);
+        };
+        assert_eq!(acme.status, JobStatus::GHOSTED);
+        assert_eq!(acme.history.len(), 1);
+        assert_eq!(acme.history[0].from, None);
+        assert_eq!(acme.history[0].to, JobStatus::GHOSTED);
+        assert_eq!(acme.history[0].at, acme.timestamp);
+        assert_eq!(acme.history[0].note.as_deref(), Some(IMPORTED_HISTORY_NOTE));
+        assert_eq!(acme.source, None);
+        assert_eq!(acme.location, Location::parse("Remote - US"));
+        assert_eq!(initech.role_location, None);
+        assert_eq!(initech.location, Location::default());
+        assert_eq!(initech.source, Some(JobSource::new("Talent.com")));
+    }
+
+    #[test]
+    fn round_trips_the_current_version() {
+        let document = JobDocument::default();
+        let read = JobDocument::from_json(&document.to_json().unwrap(), Path::new(PATH)).unwrap();
+        assert_eq!(read.version, JSON_FORMAT_VERSION);
+        assert!(read.jobs.is_empty());
+        assert_eq!(read.stages, document.stages);
+        assert_eq!(read.sources, document.sources);
+    }
+
+    #[test]
+    fn rejects_newer_and_unknown_files() {
+        let newer = json!({"version": JSON_FORMAT_VERSION + 1, "jobs": []}).to_string();
+        let error = JobDocument::from_json(&newer, Path::new(PATH)).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<DataFileError>(),
+            Some(DataFileError::NewerVersion { found, .. })
+                if *found == u64::from(JSON_FORMAT_VERSION + 1)
+        ));
+        // Would be version 1 if cut down to a u32
+        let huge = json!({"version": 4_294_967_297_u64, "jobs": []}).to_string();
+        let error = JobDocument::from_json(&huge, Path::new(PATH)).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<DataFileError>(),
+            Some(DataFileError::NewerVersion {
+                found: 4_294_967_297,
+                ..
+            })
+        ));
+
+        for data in [r#"{"jobs": []}"#, r#"{"version": "2"}"#, "42"] {
+            let error = JobDocument::from_json(data, Path::new(PATH)).unwrap_err();
+            assert!(matches!(
+                error.downcast_ref::<DataFileError>(),
+                Some(DataFileError::UnrecognizedFormat { .. })
+            ));
+        }
+        assert!(JobDocument::from_json("not json", Path::new(PATH)).is_err());
+    }
+}
@@ -1,36 +1,59 @@
 use crate::JobSource;
-use anyhow::Result;
+use std::borrow::Cow;
 use std::fmt;
 use std::str::FromStr;
 
+impl JobSource {
+    pub const RECRUITER: Self = Self(Cow::Borrowed("Recruiter"));
+    pub const LINKEDIN: Self = Self(Cow::Borrowed("LinkedIn"));
+    pub const MONSTER: Self = Self(Cow::Borrowed("Monster"));
+    pub const INDEED: Self = Self(Cow::Borrowed("Indeed"));
+    pub const TALENT: Self = Self(Cow::Borrowed("Talent.com"));
+    pub const GLASSDOOR: Self = Self(Cow::Borrowed("Glassdoor"));
+    pub const ZIPRECRUITER: Self = Self(Cow::Borrowed("ZipRecruiter"));
+    pub const REFERRAL: Self = Self(Cow::Borrowed("Referral"));
+
+    /// The sources every new tracker starts with.
+    pub const BUILT_IN: [Self; 8] = [
+        Self::RECRUITER,
+        Self::LINKEDIN,
+        Self::MONSTER,
+        Self::INDEED,
+        Self::TALENT,
+        Self::GLASSDOOR,
+        Self::ZIPRECRUITER,
+        Self::REFERRAL,
+    ];
+
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(Cow::Owned(name.into()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
 impl fmt::Display for JobSource {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            JobSource::LinkedIn => write!(f, "LinkedIn"),
-            JobSource::Monster => write!(f, "Monster"),
-            JobSource::Indeed => write!(f, "Indeed"),
-            JobSource::Recruiter => write!(f, "Recruiter"),
-            JobSource::NotProvided => write!(f, "Not provided"),
-            JobSource::Talent => write!(f, "Talent.com"),
-            JobSource::Glassdoor => write!(f, "Glassdoor"),
-            JobSource::ZipRecruiter => write!(f, "ZipRecruiter"),
-        }
+        write!(f, "{}", self.0)
     }
 }
 
+/// Accepts any source name. The built-in names are matched
+/// case-insensitively; [`crate::JobStore::parse_source`] matches names and
+/// aliases against the store's own sources and rejects names it doesn't
+/// define.
 impl FromStr for JobSource {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.to_lowercase();
-        match s.as_str() {
-            "linkedin" => Ok(JobSource::LinkedIn),
-            "monster" => Ok(JobSource::Monster),
-            "indeed" => Ok(JobSource::Indeed),
-            "recruiter" => Ok(JobSource::Recruiter),
-            "talent.com" => Ok(JobSource::Talent),
-            "glassdoor" => Ok(JobSource::Glassdoor),
-            "ziprecruiter" => Ok(JobSource::ZipRecruiter),
-            _ => Ok(JobSource::NotProvided),
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("Job source can't be empty".to_string());
         }
+        Ok(Self::BUILT_IN
+            .into_iter()
+            .find(|source| source.as_str().eq_ignore_ascii_case(s))
+            .unwrap_or_else(|| Self::new(s)))
     }
 }
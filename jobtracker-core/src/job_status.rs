@@ -1,24 +1,58 @@
 use crate::JobStatus;
+use std::borrow::Cow;
 use std::fmt;
+use std::str::FromStr;
+
+impl JobStatus {
+    pub const APPLIED: Self = Self(Cow::Borrowed("Applied"));
+    pub const INTERVIEW: Self = Self(Cow::Borrowed("Interview"));
+    pub const OFFER: Self = Self(Cow::Borrowed("Offer"));
+    pub const REJECTED: Self = Self(Cow::Borrowed("Rejected"));
+    pub const GHOSTED: Self = Self(Cow::Borrowed("Ghosted"));
+
+    /// The stages every new tracker starts with.
+    pub const BUILT_IN: [Self; 5] = [
+        Self::APPLIED,
+        Self::INTERVIEW,
+        Self::OFFER,
+        Self::REJECTED,
+        Self::GHOSTED,
+    ];
+
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(Cow::Owned(name.into()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for JobStatus {
+    fn default() -> Self {
+        Self::APPLIED
+    }
+}
 
 impl fmt::Display for JobStatus {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Self::Applied => {
-                write!(f, "Applied")
-            }
-            JobStatus::Interview => {
-                write!(f, "Interview")
-            }
-            JobStatus::Offer => {
-                write!(f, "Offer")
-            }
-            JobStatus::Rejected => {
-                write!(f, "Rejected")
-            }
-            JobStatus::Ghosted => {
-                write!(f, "Ghosted")
-            }
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Accepts any stage name. The built-in names are matched case-insensitively;
+/// [`crate::JobStore::parse_status`] does the same against the store's own
+/// stages and rejects names it doesn't define.
+impl FromStr for JobStatus {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("Job status can't be empty".to_string());
         }
+        Ok(Self::BUILT_IN
+            .into_iter()
+            .find(|status| status.as_str().eq_ignore_ascii_case(s))
+            .unwrap_or_else(|| Self::new(s)))
     }
 }
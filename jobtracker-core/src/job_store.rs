@@ -1,59 +1,231 @@
+use crate::summary_counts::median;
+use crate::AreaCounts;
+use crate::ArrangementCounts;
+use crate::Backup;
+use crate::Config;
+use crate::CurrencyConfig;
+use crate::GhostingRule;
 use crate::Job;
+use crate::JobChange;
 use crate::JobSource;
 use crate::JobStatus;
 use crate::JobStore;
+use crate::JsonFileStorage;
+use crate::MemoryStorage;
+use crate::Source;
+use crate::SourceCounts;
+use crate::SqliteStorage;
+use crate::Stage;
+use crate::StageCount;
+use crate::StageOutcome;
+use crate::Storage;
+use crate::StoreCommand;
 use crate::SummaryCounts;
+use crate::TagCounts;
+use crate::UndoConfig;
+use crate::UndoHistory;
+use crate::WorkArrangement;
 use anyhow::Error;
 use anyhow::Result;
+use anyhow::{anyhow, bail};
 use chrono::{DateTime, Utc};
-use std::fs;
-use std::fs::OpenOptions;
-use std::io::Read;
+use std::ffi::OsString;
+use std::path::Path;
+use strum::IntoEnumIterator;
 
-const FILE: &str = "jobtrack.json";
+/// An empty store that only lives in memory. Use [`JobStore::open`] or
+/// [`Config::open_store`] for one backed by a data file.
+impl Default for JobStore {
+    fn default() -> Self {
+        Self::new(Box::new(MemoryStorage::default()))
+    }
+}
 
 impl JobStore {
-    pub fn save_to_file(&self) -> Result<()> {
-        let data = serde_json::to_string_pretty(&self.jobs)?;
-        fs::write(FILE, data)?;
-        Ok(())
+    pub fn new(storage: Box<dyn Storage>) -> Self {
+        Self {
+            jobs: Vec::new(),
+            summary_stats: SummaryCounts::default(),
+            stages: Stage::defaults(),
+            contacts: Vec::new(),
+            sources: Source::defaults(),
+            storage,
+            ghosting: None,
+            last_ghosted: Vec::new(),
+            undo_history: UndoHistory::new(UndoConfig::default().limit, None),
+            currency: CurrencyConfig::default(),
+        }
     }
 
-    pub fn load_from_file(&mut self) -> Result<(), Error> {
-        if let Ok(mut file) = OpenOptions::new().read(true).open(FILE) {
-            let mut data = String::new();
-            file.read_to_string(&mut data)?;
-            if data.trim().is_empty() {
-                println!("Got empty data from file");
-                Ok(())
-            } else {
-                println!("Got data, deserializing");
-                self.jobs = serde_json::from_str(&data)?;
-                Ok(())
+    /// Opens the data file at `path`, picking the backend from its extension:
+    /// `.db`, `.sqlite` and `.sqlite3` are SQLite databases, anything else is
+    /// treated as a JSON file.
+    pub fn open(path: impl AsRef<Path>, config: &Config) -> Result<Self> {
+        let path = path.as_ref();
+        let storage: Box<dyn Storage> = match path.extension().and_then(|e| e.to_str()) {
+            Some("db" | "sqlite" | "sqlite3") => Box::new(SqliteStorage::open(path)?),
+            _ => {
+                let mut storage = JsonFileStorage::new(path);
+                if let Some(backups) = config.backups {
+                    storage = storage.with_backups(backups);
+                }
+                Box::new(storage)
             }
-        } else {
-            println!("Error opening data file");
-            Ok(())
-        }
+        };
+        let mut store = Self::new(storage);
+        // Checked against the stages once they're loaded
+        store.ghosting = config.ghosting.clone();
+        store.currency = config.currency.clone();
+        // Saved as `<data file>.undo.json`
+        let undo_path = config.undo.persist.then(|| {
+            let mut name = OsString::from(path.as_os_str());
+            name.push(".undo.json");
+            name.into()
+        });
+        store.undo_history = UndoHistory::new(config.undo.limit, undo_path);
+        Ok(store)
+    }
+
+    /// Writes every job in memory to the storage backend.
+    pub fn save(&mut self) -> Result<()> {
+        self.storage.save(&self.jobs)
+    }
+
+    /// Replaces the jobs in memory with whatever the storage backend holds,
+    /// then applies the ghosting rule.
+    pub fn load(&mut self) -> Result<(), Error> {
+        self.jobs = self.storage.load()?;
+        self.stages = self.storage.load_stages()?;
+        self.contacts = self.storage.load_contacts()?;
+        self.sources = self.storage.load_sources()?;
+        self.undo_history.load();
+        self.set_ghosting(self.ghosting.clone())?;
+        self.apply_ghosting(Utc::now())?;
+        Ok(())
+    }
+
+    /// Lists the backups kept by the storage backend, newest first.
+    pub fn backups(&self) -> Result<Vec<Backup>> {
+        self.storage.backups()
+    }
+
+    /// Replaces the current data with backup `index` from [`Self::backups`].
+    /// Undo history no longer applies to the restored data and is cleared.
+    pub fn restore_backup(&mut self, index: usize) -> Result<Vec<Job>, Error> {
+        self.jobs = self.storage.restore_backup(index)?;
+        self.stages = self.storage.load_stages()?;
+        self.contacts = self.storage.load_contacts()?;
+        self.sources = self.storage.load_sources()?;
+        self.undo_history.clear();
+        self.undo_history.save()?;
+        Ok(self.jobs.clone())
     }
 
     pub fn calculate_summary_stats(&mut self) -> Result<(), Error> {
         // TODO: Add a periodic check for this? dont need to iterate every frame.
         // Reset counts to account for the egui update() tick
-        self.summary_stats = SummaryCounts::default();
-        for job in &self.jobs {
-            self.summary_stats.total += 1;
-            match job.status {
-                JobStatus::Rejected => self.summary_stats.rejected += 1,
-                JobStatus::Ghosted => self.summary_stats.ghosted += 1,
-                JobStatus::Applied => self.summary_stats.applied += 1,
-                JobStatus::Interview => self.summary_stats.interviews += 1,
-                JobStatus::Offer => self.summary_stats.offers += 1,
+        let mut stats = self.summarize(self.active_jobs());
+        stats.by_tag = self
+            .all_tags()
+            .into_iter()
+            .map(|(tag, _)| TagCounts {
+                counts: self.summarize(self.active_jobs().filter(|job| job.tags.contains(&tag))),
+                tag,
+            })
+            .collect();
+        stats.by_arrangement = WorkArrangement::iter()
+            .map(Some)
+            .chain([None])
+            .map(|arrangement| ArrangementCounts {
+                counts: self.summarize(
+                    self.active_jobs()
+                        .filter(|job| job.location.arrangement == arrangement),
+                ),
+                arrangement,
+            })
+            .filter(|breakdown| breakdown.counts.total > 0)
+            .collect();
+        stats.by_area = self
+            .areas()
+            .into_iter()
+            .map(|(area, _)| AreaCounts {
+                counts: self.summarize(
+                    self.active_jobs()
+                        .filter(|job| job.location.area().as_ref() == Some(&area)),
+                ),
+                area,
+            })
+            .collect();
+        stats.by_source = self
+            .sources
+            .iter()
+            .map(|source| Some(source.name.clone()))
+            .chain([None])
+            .map(|source| SourceCounts {
+                counts: self.summarize(self.active_jobs().filter(|job| job.source == source)),
+                source,
+            })
+            .filter(|breakdown| breakdown.counts.total > 0)
+            .collect();
+        self.summary_stats = stats;
+        Ok(())
+    }
+
+    /// Stage and outcome counts over `jobs`.
+    fn summarize<'a>(&self, jobs: impl Iterator<Item = &'a Job>) -> SummaryCounts {
+        let mut stats = SummaryCounts {
+            by_stage: self
+                .stages
+                .iter()
+                .map(|stage| StageCount {
+                    stage: stage.name.clone(),
+                    count: 0,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let first_stage = self.stages.first().map(|stage| &stage.name);
+        let mut days_to_response = Vec::new();
+        for job in jobs {
+            stats.total += 1;
+            match stats.by_stage.iter_mut().find(|c| c.stage == job.status) {
+                Some(count) => count.count += 1,
+                None => stats.by_stage.push(StageCount {
+                    stage: job.status.clone(),
+                    count: 1,
+                }),
+            }
+            let outcome = self.stage(&job.status).and_then(|stage| stage.outcome);
+            match outcome {
+                Some(StageOutcome::Success) => stats.succeeded += 1,
+                Some(StageOutcome::Failure) => stats.failed += 1,
+                None => {}
+            }
+            if Some(&job.status) != first_stage && outcome != Some(StageOutcome::Failure) {
+                stats.advanced += 1;
+            }
+            if let Some(response) = job.first_response() {
+                let waited = (response - job.timestamp).num_seconds().max(0);
+                days_to_response.push(waited as f64 / (24.0 * 60.0 * 60.0));
             }
         }
-        Ok(())
+        let rate =
+            |count: usize| (stats.total > 0).then(|| count as f64 / stats.total as f64 * 100.0);
+        stats.interview_rate = rate(stats.advanced);
+        stats.offer_rate = rate(stats.succeeded);
+        stats.rejection_rate = rate(stats.failed);
+        stats.median_days_to_response = median(&mut days_to_response);
+        stats
     }
 
+    /// ID for the next job added to the store.
+    pub fn next_id(&self) -> u32 {
+        self.jobs.iter().map(|a| a.id).max().unwrap_or(0) + 1
+    }
+
+    /// Adds an application in the first stage, like [`Self::add_draft`].
+    /// `new_source` has to name one of the store's sources, see
+    /// [`Self::parse_source`], or be blank for none.
     pub fn add_job(
         &mut self,
         company: String,
@@ -61,54 +233,224 @@ impl JobStore {
         new_role_location: String,
         new_source: String,
     ) -> Result<Vec<Job>, Error> {
-        let new_job_id = self.jobs.iter().map(|a| a.id).max().unwrap_or(0) + 1;
-        self.jobs.push(Job {
-            id: new_job_id,
+        let source = Some(new_source.trim())
+            .filter(|source| !source.is_empty())
+            .map(JobSource::new);
+        self.add_draft(Job {
             company,
             role,
             role_location: Some(new_role_location),
-            status: JobStatus::Applied,
-            timestamp: Utc::now(),
-            source: Some(new_source.parse().unwrap()),
-        });
-        self.save_to_file()?;
+            source,
+            ..Job::default()
+        })?;
         Ok(self.jobs.clone())
     }
 
+    /// Every job outside the trash, after applying the ghosting rule.
     pub fn list_jobs(&mut self) -> Result<Vec<Job>, Error> {
-        // TODO? Handle ghosted apps here? (cfg'able duration value)
+        self.apply_ghosting(Utc::now())?;
+        Ok(self.active_jobs().cloned().collect())
+    }
+
+    /// Jobs that haven't been moved to the trash.
+    pub fn active_jobs(&self) -> impl Iterator<Item = &Job> {
+        self.jobs.iter().filter(|job| !job.is_deleted())
+    }
+
+    /// Jobs in the trash, most recently deleted first.
+    pub fn trashed_jobs(&self) -> Vec<&Job> {
+        let mut trashed: Vec<&Job> = self.jobs.iter().filter(|job| job.is_deleted()).collect();
+        trashed.sort_by_key(|job| std::cmp::Reverse(job.deleted_at));
+        trashed
+    }
+
+    /// Sets the ghosting rule, `None` turning it off. Fails if the rule
+    /// names stages that aren't in the pipeline.
+    pub fn set_ghosting(&mut self, rule: Option<GhostingRule>) -> Result<()> {
+        self.ghosting = rule.map(|rule| rule.resolve(&self.stages)).transpose()?;
+        Ok(())
+    }
+
+    /// IDs of the jobs the ghosting rule would move to its target at `now`.
+    pub fn preview_ghosting(&self, now: DateTime<Utc>) -> Result<Vec<u32>> {
+        let Some(rule) = &self.ghosting else {
+            return Ok(Vec::new());
+        };
+        let rule = rule.resolve(&self.stages)?;
+        Ok(self.ghosting_candidates(&rule, now))
+    }
+
+    /// IDs of the jobs `rule` applies to at `now` whose move to its target
+    /// the pipeline's rules allow.
+    fn ghosting_candidates(&self, rule: &GhostingRule, now: DateTime<Utc>) -> Vec<u32> {
+        self.jobs
+            .iter()
+            .filter(|job| rule.applies_to(job, now))
+            .filter(|job| {
+                self.check_transition(job.id, &job.status, &rule.target)
+                    .is_ok()
+            })
+            .map(|job| job.id)
+            .collect()
+    }
+
+    /// Moves every job the ghosting rule applies to at `now` to its target,
+    /// marking the transitions as automatic. Returns the affected IDs, which
+    /// are also kept in `last_ghosted` for [`Self::undo_ghosting`]. Fails
+    /// if the rule names stages that aren't in the pipeline.
+    pub fn apply_ghosting(&mut self, now: DateTime<Utc>) -> Result<Vec<u32>, Error> {
+        let Some(rule) = &self.ghosting else {
+            return Ok(Vec::new());
+        };
+        let rule = rule.resolve(&self.stages)?;
+        let ids = self.ghosting_candidates(&rule, now);
+        let note = format!("No response in {} days", rule.after_days);
+        let mut changes = Vec::new();
+        for job in self.jobs.iter_mut().filter(|job| ids.contains(&job.id)) {
+            let before = job.clone();
+            if let Some(change) = job.transition(rule.target.clone(), now, Some(note.clone())) {
+                change.automatic = true;
+            }
+            changes.push(JobChange {
+                before: Some(before),
+                after: Some(job.clone()),
+            });
+        }
+        // Keep the previous run undoable when this one found nothing
+        if !ids.is_empty() {
+            self.record("Mark silent applications as ghosted", changes)?;
+            self.last_ghosted = ids.clone();
+        }
+        Ok(ids)
+    }
+
+    /// Reverts automatic ghosting of the given jobs. Jobs whose latest status
+    /// change wasn't automatic are left alone.
+    pub fn undo_ghosting(&mut self, ids: &[u32]) -> Result<Vec<Job>, Error> {
+        let now = Utc::now();
+        let mut changes = Vec::new();
+        for job in self.jobs.iter_mut().filter(|job| ids.contains(&job.id)) {
+            let before = job.clone();
+            if job.undo_automatic_transition(now) {
+                changes.push(JobChange {
+                    before: Some(before),
+                    after: Some(job.clone()),
+                });
+            }
+        }
+        self.record("Revert automatic ghosting", changes)?;
+        self.last_ghosted.retain(|id| !ids.contains(id));
         Ok(self.jobs.clone())
     }
 
-    pub fn delete_job(&mut self, index: usize) -> Result<Vec<Job>, Error> {
-        if index < self.jobs.len() {
-            self.jobs.remove(index);
-            self.save_to_file()?;
+    /// Moves the job with the given ID to the trash. It stays in the data
+    /// file until purged, so it can be brought back with
+    /// [`Self::restore_job`].
+    pub fn delete_job(&mut self, id: u32) -> Result<Vec<Job>, Error> {
+        self.modify_job(id, "Delete application", |job| {
+            job.deleted_at = Some(Utc::now())
+        })?;
+        Ok(self.jobs.clone())
+    }
+
+    /// Takes the job with the given ID back out of the trash.
+    pub fn restore_job(&mut self, id: u32) -> Result<Vec<Job>, Error> {
+        self.modify_job(id, "Restore application", |job| job.deleted_at = None)?;
+        Ok(self.jobs.clone())
+    }
+
+    /// Permanently removes the job with the given ID.
+    pub fn purge_job(&mut self, id: u32) -> Result<Vec<Job>, Error> {
+        if let Some(index) = self.jobs.iter().position(|j| j.id == id) {
+            let job = self.jobs.remove(index);
+            // The ID may be handed out again, so nobody should stay linked to it
+            let contact_changes = self.unlink_job_from_contacts(id);
+            self.record_command(StoreCommand {
+                label: "Delete application forever".to_string(),
+                changes: vec![JobChange {
+                    before: Some(job),
+                    after: None,
+                }],
+                contact_changes,
+                ..Default::default()
+            })?;
         }
         Ok(self.jobs.clone())
     }
 
+    /// Moves job `id` to `new_status`. Fails with a [`crate::TransitionError`] if
+    /// the pipeline's rules don't allow the move.
     pub fn update_status(&mut self, id: u32, new_status: JobStatus) -> Result<Vec<Job>, Error> {
-        if let Some(job) = self.jobs.iter_mut().find(|j| j.id == id) {
-            job.status = new_status;
-            self.save_to_file()?;
+        self.update_status_with_note(id, new_status, None, false)
+    }
+
+    /// Like [`Self::update_status`], attaching `note` to the recorded
+    /// transition. With `correction` set the pipeline's rules are skipped
+    /// and the change is marked as a correction. Fails if `new_status`
+    /// isn't one of the store's stages.
+    pub fn update_status_with_note(
+        &mut self,
+        id: u32,
+        new_status: JobStatus,
+        note: Option<String>,
+        correction: bool,
+    ) -> Result<Vec<Job>, Error> {
+        let new_status = self.parse_status(new_status.as_str())?;
+        if let Some(job) = self.jobs.iter().find(|job| job.id == id) {
+            if !correction {
+                self.check_transition(id, &job.status, &new_status)?;
+            }
         }
+        self.modify_job(id, "Change status", |job| {
+            if let Some(change) = job.transition(new_status, Utc::now(), note) {
+                change.correction = correction;
+            }
+        })?;
         Ok(self.jobs.clone())
     }
 
-    pub fn update_source(&mut self, id: u32, new_source: JobSource) -> Result<Vec<Job>, Error> {
-        if let Some(job) = self.jobs.iter_mut().find(|j| j.id == id) {
-            job.source = Some(new_source);
-            self.save_to_file()?;
-        }
+    /// Changes where job `id` came from, `None` meaning the user didn't
+    /// say. The referrer is dropped if the new source isn't a referral.
+    pub fn update_source(
+        &mut self,
+        id: u32,
+        new_source: Option<JobSource>,
+    ) -> Result<Vec<Job>, Error> {
+        let new_source = new_source
+            .map(|source| self.parse_source(source.as_str()))
+            .transpose()?;
+        let keep_referrer = self.is_referral(new_source.as_ref());
+        self.modify_job(id, "Change source", |job| {
+            job.source = new_source;
+            if !keep_referrer {
+                job.referrer = None;
+            }
+        })?;
         Ok(self.jobs.clone())
     }
 
     pub fn update_company(&mut self, id: u32, new_company: String) -> Result<Vec<Job>, Error> {
-        if let Some(job) = self.jobs.iter_mut().find(|j| j.id == id) {
-            job.company = new_company;
-            self.save_to_file()?;
-        }
+        self.modify_job(id, "Change company", |job| job.company = new_company)?;
+        Ok(self.jobs.clone())
+    }
+
+    /// Sets the link to the job posting. Blank clears it.
+    pub fn update_posting_url(&mut self, id: u32, new_url: String) -> Result<Vec<Job>, Error> {
+        let new_url = parse_posting_url(&new_url)?;
+        self.modify_job(id, "Change posting URL", |job| job.posting_url = new_url)?;
+        Ok(self.jobs.clone())
+    }
+
+    /// Archives `new_description` as the posting's text. Blank clears it.
+    pub fn update_description(
+        &mut self,
+        id: u32,
+        new_description: String,
+    ) -> Result<Vec<Job>, Error> {
+        let new_description = Some(new_description.trim().to_string()).filter(|d| !d.is_empty());
+        self.modify_job(id, "Change description", |job| {
+            job.description = new_description
+        })?;
         Ok(self.jobs.clone())
     }
 
@@ -117,10 +459,675 @@ impl JobStore {
         id: u32,
         new_timestamp: DateTime<Utc>,
     ) -> Result<Vec<Job>, Error> {
-        if let Some(job) = self.jobs.iter_mut().find(|j| j.id == id) {
-            job.timestamp = new_timestamp;
-            self.save_to_file()?;
-        }
+        self.modify_job(id, "Change date applied", |job| {
+            job.timestamp = new_timestamp
+        })?;
         Ok(self.jobs.clone())
     }
+
+    /// Applies `change` to the job with the given ID and records it as an
+    /// undoable command named `label`. Does nothing if there's no such job
+    /// or `change` leaves it as it was.
+    pub(crate) fn modify_job(
+        &mut self,
+        id: u32,
+        label: &str,
+        change: impl FnOnce(&mut Job),
+    ) -> Result<()> {
+        let Some(job) = self.jobs.iter_mut().find(|j| j.id == id) else {
+            return Ok(());
+        };
+        let before = job.clone();
+        change(job);
+        if *job == before {
+            return Ok(());
+        }
+        let after = job.clone();
+        self.record(
+            label,
+            vec![JobChange {
+                before: Some(before),
+                after: Some(after),
+            }],
+        )
+    }
+
+    /// Persists `changes`, which have already been made to `self.jobs`, and
+    /// pushes them onto the undo stack as one command.
+    pub(crate) fn record(&mut self, label: &str, changes: Vec<JobChange>) -> Result<()> {
+        self.record_command(StoreCommand {
+            label: label.to_string(),
+            changes,
+            ..Default::default()
+        })
+    }
+
+    /// Like [`Self::record`] for commands that may also touch contacts,
+    /// stages or sources.
+    pub(crate) fn record_command(&mut self, command: StoreCommand) -> Result<()> {
+        if command.changes.is_empty()
+            && command.contact_changes.is_empty()
+            && command.stage_change.is_none()
+            && command.source_change.is_none()
+        {
+            return Ok(());
+        }
+        self.write_or_revert(&command)?;
+        self.undo_history.push(command);
+        self.undo_history.save()
+    }
+
+    /// Writes `command`, whose changes have already been made in memory, to
+    /// the storage backend. If that fails the changes are taken back out of
+    /// memory, so nothing is kept that isn't saved.
+    fn write_or_revert(&mut self, command: &StoreCommand) -> Result<()> {
+        let written = self.write_command(command);
+        if written.is_err() {
+            let inverse = command.inverse();
+            self.apply_in_memory(&inverse);
+            // Best effort to put back on disk whatever part did get
+            // written; the first error is the one worth reporting
+            let _ = self.write_command(&inverse);
+        }
+        written
+    }
+
+    /// Writes the jobs, contacts, stages and sources `command` touches to
+    /// the storage backend, in that order.
+    fn write_command(&mut self, command: &StoreCommand) -> Result<()> {
+        self.storage.apply_changes(&command.changes)?;
+        if !command.contact_changes.is_empty() {
+            self.storage.save_contacts(&self.contacts)?;
+        }
+        if let Some(change) = &command.stage_change {
+            self.storage.save_stages(&change.after)?;
+        }
+        if let Some(change) = &command.source_change {
+            self.storage.save_sources(&change.after)?;
+        }
+        Ok(())
+    }
+
+    /// Moves everything in `command` from its `before` to its `after` state
+    /// in memory only.
+    fn apply_in_memory(&mut self, command: &StoreCommand) {
+        for change in &command.changes {
+            apply_change(&mut self.jobs, &change.before, &change.after, |job| job.id);
+        }
+        for change in &command.contact_changes {
+            apply_change(&mut self.contacts, &change.before, &change.after, |c| c.id);
+        }
+        if let Some(change) = &command.stage_change {
+            self.stages = change.after.clone();
+        }
+        if let Some(change) = &command.source_change {
+            self.sources = change.after.clone();
+        }
+    }
+
+    /// Reverts the most recent command. Returns its label, or `None` if
+    /// there was nothing to undo. If the change can't be saved the command
+    /// stays on the undo stack.
+    pub fn undo(&mut self) -> Result<Option<String>> {
+        let Some(command) = self.undo_history.undo.pop_back() else {
+            return Ok(None);
+        };
+        let inverse = command.inverse();
+        self.check_command(&inverse)?;
+        if let Err(error) = self.apply_command(&inverse) {
+            self.undo_history.undo.push_back(command);
+            return Err(error);
+        }
+        self.undo_history.redo.push(command);
+        self.undo_history.save()?;
+        Ok(Some(inverse.label))
+    }
+
+    /// Re-applies the most recently undone command. Returns its label, or
+    /// `None` if there was nothing to redo. If the change can't be saved
+    /// the command stays on the redo stack.
+    pub fn redo(&mut self) -> Result<Option<String>> {
+        let Some(command) = self.undo_history.redo.pop() else {
+            return Ok(None);
+        };
+        self.check_command(&command)?;
+        if let Err(error) = self.apply_command(&command) {
+            self.undo_history.redo.push(command);
+            return Err(error);
+        }
+        let label = command.label.clone();
+        self.undo_history.undo.push_back(command);
+        self.undo_history.save()?;
+        Ok(Some(label))
+    }
+
+    /// Fails if a job, contact, or the stage or source list in `command` no
+    /// longer matches its `before` state, e.g. because another process edited it since the
+    /// command was recorded. The caller has already taken the command off
+    /// its stack, so it's dropped in that case.
+    fn check_command(&mut self, command: &StoreCommand) -> Result<()> {
+        let label = &command.label;
+        for change in &command.changes {
+            let id = change_id(&change.before, &change.after, |job| job.id, label)?;
+            if self.jobs.iter().find(|job| job.id == id) != change.before.as_ref() {
+                self.undo_history.save()?;
+                bail!("Can't undo or redo \"{label}\": job {id} has changed since");
+            }
+        }
+        for change in &command.contact_changes {
+            let id = change_id(&change.before, &change.after, |c| c.id, label)?;
+            if self.contacts.iter().find(|c| c.id == id) != change.before.as_ref() {
+                self.undo_history.save()?;
+                bail!("Can't undo or redo \"{label}\": contact {id} has changed since");
+            }
+        }
+        let stages_changed = command
+            .stage_change
+            .as_ref()
+            .is_some_and(|change| change.before != self.stages);
+        let sources_changed = command
+            .source_change
+            .as_ref()
+            .is_some_and(|change| change.before != self.sources);
+        if stages_changed || sources_changed {
+            self.undo_history.save()?;
+            let list = if stages_changed { "stages" } else { "sources" };
+            bail!("Can't undo or redo \"{label}\": the {list} have changed since");
+        }
+        Ok(())
+    }
+
+    /// Moves everything in `command` from its `before` to its `after` state
+    /// and saves it. Nothing is changed if saving fails.
+    fn apply_command(&mut self, command: &StoreCommand) -> Result<()> {
+        self.apply_in_memory(command);
+        self.write_or_revert(command)
+    }
+}
+
+/// `url` trimmed, or `None` if blank. Fails unless it's a web address.
+pub(crate) fn parse_posting_url(url: &str) -> Result<Option<String>> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Ok(None);
+    }
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        bail!("Posting URL should start with http:// or https://, got {url}");
+    }
+    Ok(Some(url.to_string()))
+}
+
+/// ID of the job or contact a change is about.
+fn change_id<T>(
+    before: &Option<T>,
+    after: &Option<T>,
+    id: impl Fn(&T) -> u32,
+    label: &str,
+) -> Result<u32> {
+    before
+        .as_ref()
+        .or(after.as_ref())
+        .map(id)
+        .ok_or_else(|| anyhow!("Empty change in \"{label}\""))
+}
+
+/// Puts `after` in place of the item with the same ID in `items`, keeping
+/// them sorted by ID, or removes `before` if there's no `after`.
+pub(crate) fn apply_change<T: Clone>(
+    items: &mut Vec<T>,
+    before: &Option<T>,
+    after: &Option<T>,
+    id: impl Fn(&T) -> u32,
+) {
+    match (before, after) {
+        (_, Some(after)) => match items.iter_mut().find(|item| id(item) == id(after)) {
+            Some(item) => *item = after.clone(),
+            None => {
+                items.push(after.clone());
+                items.sort_by_key(&id);
+            }
+        },
+        (Some(before), None) => items.retain(|item| id(item) != id(before)),
+        (None, None) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{store_with_job, FailingStorage};
+    use crate::Contact;
+    use crate::CsvColumns;
+
+    #[test]
+    fn memory_storage_keeps_what_the_store_writes() {
+        let mut store = JobStore::new(Box::new(MemoryStorage::default()));
+        for company in ["Acme", "Initech", "Hooli"] {
+            store
+                .add_job(company.into(), "Dev".into(), String::new(), String::new())
+                .unwrap();
+        }
+        store.update_company(1, "Globex".to_string()).unwrap();
+        store.update_status(1, JobStatus::INTERVIEW).unwrap();
+        store.delete_job(2).unwrap();
+        store.purge_job(3).unwrap();
+        let in_memory = store.jobs.clone();
+
+        store.jobs.clear();
+        store.load().unwrap();
+        assert_eq!(store.jobs, in_memory);
+        assert_eq!(store.jobs.len(), 2);
+        assert_eq!(store.jobs[0].company, "Globex");
+        assert_eq!(store.jobs[0].status, JobStatus::INTERVIEW);
+        assert!(store.jobs[1].is_deleted());
+    }
+
+    #[test]
+    fn stores_start_from_what_memory_storage_holds() {
+        let job = Job::new(
+            7,
+            "Acme".to_string(),
+            "Dev".to_string(),
+            JobStatus::APPLIED,
+            Utc::now(),
+        );
+        let mut store = JobStore::new(Box::new(MemoryStorage::new(vec![job.clone()])));
+        store.load().unwrap();
+        assert_eq!(store.jobs, [job]);
+        assert_eq!(store.stages, Stage::defaults());
+        assert_eq!(store.sources, Source::defaults());
+        assert_eq!(store.next_id(), 8);
+    }
+
+    #[test]
+    fn trashed_jobs_are_hidden_until_restored() {
+        let mut store = store_with_job();
+        store
+            .add_job("Initech".into(), "QA".into(), String::new(), String::new())
+            .unwrap();
+        store.delete_job(1).unwrap();
+        store.delete_job(2).unwrap();
+        store.restore_job(2).unwrap();
+        store.delete_job(2).unwrap();
+
+        assert!(store.list_jobs().unwrap().is_empty());
+        let trashed: Vec<u32> = store.trashed_jobs().iter().map(|job| job.id).collect();
+        assert_eq!(trashed, [2, 1]);
+        store.calculate_summary_stats().unwrap();
+        assert_eq!(store.summary_stats.total, 0);
+
+        let restored = store.restore_job(1).unwrap();
+        assert!(!restored[0].is_deleted());
+        let listed: Vec<u32> = store
+            .list_jobs()
+            .unwrap()
+            .iter()
+            .map(|job| job.id)
+            .collect();
+        assert_eq!(listed, [1]);
+        assert_eq!(store.trashed_jobs().len(), 1);
+    }
+
+    #[test]
+    fn purged_jobs_are_gone_with_their_links() {
+        let mut store = store_with_job();
+        let contact = Contact {
+            name: "Sam".to_string(),
+            job_ids: vec![1],
+            ..Contact::default()
+        };
+        store.add_contact(contact).unwrap();
+        store.delete_job(1).unwrap();
+        store.purge_job(1).unwrap();
+
+        assert!(store.jobs.is_empty());
+        assert!(store.trashed_jobs().is_empty());
+        assert!(store.contacts[0].job_ids.is_empty());
+        store.load().unwrap();
+        assert!(store.jobs.is_empty());
+
+        assert_eq!(
+            store.undo().unwrap().as_deref(),
+            Some("Delete application forever")
+        );
+        assert!(store.jobs[0].is_deleted());
+        assert_eq!(store.contacts[0].job_ids, [1]);
+    }
+
+    #[test]
+    fn undo_and_redo_replay_changes() {
+        let mut store = store_with_job();
+        store.update_company(1, "Initech".to_string()).unwrap();
+
+        assert_eq!(store.undo().unwrap().as_deref(), Some("Change company"));
+        assert_eq!(store.jobs[0].company, "Acme");
+        assert_eq!(store.undo().unwrap().as_deref(), Some("Add application"));
+        assert!(store.jobs.is_empty());
+        assert_eq!(store.undo().unwrap(), None);
+
+        assert_eq!(store.redo().unwrap().as_deref(), Some("Add application"));
+        assert_eq!(store.redo().unwrap().as_deref(), Some("Change company"));
+        assert_eq!(store.jobs[0].company, "Initech");
+        assert_eq!(store.redo().unwrap(), None);
+
+        store.undo().unwrap();
+        store.update_status(1, JobStatus::INTERVIEW).unwrap();
+        assert!(!store.undo_history.can_redo());
+    }
+
+    #[test]
+    fn unchanged_edits_are_not_recorded() {
+        let mut store = store_with_job();
+        store.update_company(1, "Acme".to_string()).unwrap();
+        store.update_company(2, "Initech".to_string()).unwrap();
+        assert_eq!(store.undo_history.undo.len(), 1);
+    }
+
+    #[test]
+    fn undo_refuses_after_an_outside_change() {
+        let mut store = store_with_job();
+        store.update_company(1, "Initech".to_string()).unwrap();
+        store.jobs[0].role = "Lead".to_string();
+
+        let error = store.undo().unwrap_err();
+        assert!(error.to_string().contains("job 1 has changed since"));
+        assert_eq!(store.jobs[0].company, "Initech");
+        assert_eq!(store.undo_history.undo.len(), 1);
+        assert!(!store.undo_history.can_redo());
+    }
+
+    /// Whether `result` is the error [`FailingStorage`] fails writes with.
+    fn disk_full<T: std::fmt::Debug>(result: Result<T>) -> bool {
+        result.unwrap_err().to_string() == "No space left on device"
+    }
+
+    #[test]
+    fn failed_writes_are_not_kept_in_memory() {
+        let (mut store, failing) = FailingStorage::store();
+        store
+            .add_job("Acme".into(), "Dev".into(), String::new(), String::new())
+            .unwrap();
+        failing.set(true);
+
+        assert!(disk_full(store.update_company(1, "Initech".to_string())));
+        assert!(disk_full(store.add_job(
+            "Hooli".into(),
+            "QA".into(),
+            String::new(),
+            String::new()
+        )));
+        assert!(disk_full(store.add_draft(Job {
+            company: "Globex".to_string(),
+            role: "Dev".to_string(),
+            ..Job::default()
+        })));
+        let csv = "Company,Role,Date Applied\nInitrode,Dev,2024-01-02\n";
+        assert!(disk_full(store.import_csv(
+            csv.as_bytes(),
+            &CsvColumns::default(),
+            false
+        )));
+        let contact = Contact {
+            name: "Sam".to_string(),
+            job_ids: vec![1],
+            ..Contact::default()
+        };
+        assert!(disk_full(store.add_contact(contact)));
+
+        let companies: Vec<&str> = store.jobs.iter().map(|job| job.company.as_str()).collect();
+        assert_eq!(companies, ["Acme"]);
+        assert!(store.contacts.is_empty());
+        assert_eq!(store.undo_history.undo.len(), 1);
+        failing.set(false);
+        let in_memory = store.jobs.clone();
+        store.load().unwrap();
+        assert_eq!(store.jobs, in_memory);
+    }
+
+    #[test]
+    fn failed_undo_and_redo_keep_their_command() {
+        let (mut store, failing) = FailingStorage::store();
+        store
+            .add_job("Acme".into(), "Dev".into(), String::new(), String::new())
+            .unwrap();
+        store.update_company(1, "Initech".to_string()).unwrap();
+
+        failing.set(true);
+        assert!(disk_full(store.undo()));
+        assert_eq!(store.jobs[0].company, "Initech");
+        assert_eq!(store.undo_history.undo.len(), 2);
+        failing.set(false);
+        assert_eq!(store.undo().unwrap().as_deref(), Some("Change company"));
+
+        failing.set(true);
+        assert!(disk_full(store.redo()));
+        assert_eq!(store.jobs[0].company, "Acme");
+        assert_eq!(store.undo_history.redo.len(), 1);
+        failing.set(false);
+        assert_eq!(store.redo().unwrap().as_deref(), Some("Change company"));
+        assert_eq!(store.jobs[0].company, "Initech");
+        store.load().unwrap();
+        assert_eq!(store.jobs[0].company, "Initech");
+    }
+
+    #[test]
+    fn stage_edits_are_undone_like_other_changes() {
+        let (mut store, failing) = FailingStorage::store();
+        store
+            .add_job("Acme".into(), "Dev".into(), String::new(), String::new())
+            .unwrap();
+        store.update_status(1, JobStatus::INTERVIEW).unwrap();
+        let screen = JobStatus::new("Phone screen");
+        let mut stages = Stage::defaults();
+        stages[1].name = screen.clone();
+        let renamed = [(JobStatus::INTERVIEW, screen.clone())];
+
+        failing.set(true);
+        assert!(disk_full(store.set_stages(stages.clone(), &renamed)));
+        assert_eq!(store.stages, Stage::defaults());
+        assert_eq!(store.jobs[0].status, JobStatus::INTERVIEW);
+        failing.set(false);
+
+        store.set_stages(stages.clone(), &renamed).unwrap();
+        assert_eq!(store.jobs[0].status, screen);
+        assert_eq!(store.jobs[0].history.last().unwrap().to, screen);
+        store.load().unwrap();
+        assert_eq!(store.stages, stages);
+        assert_eq!(store.jobs[0].status, screen);
+
+        // The status change recorded before the rename still undoes once
+        // the rename is taken back
+        assert_eq!(store.undo().unwrap().as_deref(), Some("Change stages"));
+        assert_eq!(store.stages, Stage::defaults());
+        assert_eq!(store.jobs[0].status, JobStatus::INTERVIEW);
+        assert_eq!(store.undo().unwrap().as_deref(), Some("Change status"));
+        assert_eq!(store.jobs[0].status, JobStatus::APPLIED);
+        store.redo().unwrap();
+        store.redo().unwrap();
+        assert_eq!(store.stages, stages);
+        assert_eq!(store.jobs[0].status, screen);
+        store.load().unwrap();
+        assert_eq!(store.stages, stages);
+    }
+
+    #[test]
+    fn ghosting_does_what_it_previews_and_can_be_undone() {
+        let now = Utc::now();
+        let mut store = JobStore::default();
+        for (id, status, days_ago) in [
+            (1, JobStatus::APPLIED, 40),
+            (2, JobStatus::APPLIED, 10),
+            (3, JobStatus::INTERVIEW, 40),
+            (4, JobStatus::APPLIED, 30),
+        ] {
+            let at = now - chrono::Duration::days(days_ago);
+            store
+                .jobs
+                .push(Job::new(id, "Acme".into(), "Dev".into(), status, at));
+        }
+        store
+            .set_ghosting(Some(GhostingRule {
+                after_days: 30,
+                statuses: GhostingRule::default_statuses(),
+                target: GhostingRule::default_target(),
+            }))
+            .unwrap();
+
+        let preview = store.preview_ghosting(now).unwrap();
+        assert_eq!(preview, [1, 4]);
+        assert_eq!(store.apply_ghosting(now).unwrap(), preview);
+        assert_eq!(store.last_ghosted, preview);
+        let statuses: Vec<&str> = store.jobs.iter().map(|job| job.status.as_str()).collect();
+        assert_eq!(statuses, ["Ghosted", "Applied", "Interview", "Ghosted"]);
+        assert!(store.jobs[0].history.last().unwrap().automatic);
+        assert_eq!(store.preview_ghosting(now).unwrap(), Vec::<u32>::new());
+
+        // A later run that finds nothing keeps the last one undoable
+        assert_eq!(store.apply_ghosting(now).unwrap(), Vec::<u32>::new());
+        store
+            .update_status_with_note(4, JobStatus::APPLIED, None, true)
+            .unwrap();
+        store.undo_ghosting(&[1, 4]).unwrap();
+        assert_eq!(store.jobs[0].status, JobStatus::APPLIED);
+        // Job 4 was moved by hand since, so it's left alone
+        assert_eq!(store.jobs[3].history.len(), 3);
+        assert!(store.last_ghosted.is_empty());
+    }
+
+    #[test]
+    fn ghosting_only_moves_jobs_into_defined_stages() {
+        let now = Utc::now();
+        let mut store = JobStore::default();
+        store.jobs.push(Job::new(
+            1,
+            "Acme".into(),
+            "Dev".into(),
+            JobStatus::APPLIED,
+            now - chrono::Duration::days(40),
+        ));
+        let rule = GhostingRule {
+            after_days: 30,
+            statuses: GhostingRule::default_statuses(),
+            target: JobStatus::new("Silent"),
+        };
+        assert!(store.set_ghosting(Some(rule.clone())).is_err());
+        assert!(store.ghosting.is_none());
+
+        // Set behind the store's back, e.g. before the stages were loaded
+        store.ghosting = Some(rule);
+        assert!(store.preview_ghosting(now).is_err());
+        assert!(store.apply_ghosting(now).is_err());
+        assert_eq!(store.jobs[0].status, JobStatus::APPLIED);
+
+        store
+            .set_ghosting(Some(GhostingRule {
+                after_days: 30,
+                statuses: GhostingRule::default_statuses(),
+                target: GhostingRule::default_target(),
+            }))
+            .unwrap();
+        let mut stages = Stage::defaults();
+        stages.retain(|stage| stage.name != JobStatus::GHOSTED);
+        let error = store.set_stages(stages, &[]).unwrap_err();
+        assert!(error.to_string().contains("ghosting rule"));
+        let renamed = [(JobStatus::GHOSTED, JobStatus::new("Silent"))];
+        let mut stages = Stage::defaults();
+        stages[4].name = JobStatus::new("Silent");
+        assert!(store.set_stages(stages, &renamed).is_err());
+
+        // Ghosting follows the pipeline's rules, like any other move
+        let mut stages = Stage::defaults();
+        stages[0].next = Some(vec![JobStatus::INTERVIEW]);
+        store.set_stages(stages, &[]).unwrap();
+        assert_eq!(store.preview_ghosting(now).unwrap(), Vec::<u32>::new());
+        assert_eq!(store.apply_ghosting(now).unwrap(), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn counts_follow_stage_outcomes() {
+        let mut store = JobStore::default();
+        store.calculate_summary_stats().unwrap();
+        assert_eq!(store.summary_stats.interview_rate, None);
+        for status in [
+            JobStatus::APPLIED,
+            JobStatus::INTERVIEW,
+            JobStatus::OFFER,
+            JobStatus::REJECTED,
+            JobStatus::GHOSTED,
+        ] {
+            store
+                .add_job("Acme".into(), "Dev".into(), String::new(), String::new())
+                .unwrap();
+            store.update_status(store.next_id() - 1, status).unwrap();
+        }
+        store.delete_job(1).unwrap();
+        store
+            .add_job("Acme".into(), "Dev".into(), String::new(), String::new())
+            .unwrap();
+        store.calculate_summary_stats().unwrap();
+
+        let stats = &store.summary_stats;
+        assert_eq!(stats.total, 5);
+        assert_eq!(stats.succeeded, 1);
+        assert_eq!(stats.failed, 2);
+        assert_eq!(stats.advanced, 2);
+        let counts: Vec<usize> = stats.by_stage.iter().map(|count| count.count).collect();
+        assert_eq!(counts, [1, 1, 1, 1, 1]);
+        assert_eq!(stats.offer_rate, Some(20.0));
+        assert_eq!(stats.interview_rate, Some(40.0));
+        assert_eq!(stats.rejection_rate, Some(40.0));
+        assert!(stats.median_days_to_response.unwrap() < 1.0);
+        let by_source: Vec<(Option<JobSource>, usize)> = stats
+            .by_source
+            .iter()
+            .map(|breakdown| (breakdown.source.clone(), breakdown.counts.total))
+            .collect();
+        assert_eq!(by_source, [(None, 5)]);
+    }
+
+    #[test]
+    fn posting_details_are_kept_and_searched() {
+        let mut store = store_with_job();
+        store
+            .update_posting_url(1, " https://example.com/jobs/1 ".to_string())
+            .unwrap();
+        store
+            .update_description(1, "\n Build the billing pipeline \n".to_string())
+            .unwrap();
+        let job = &store.jobs[0];
+        assert_eq!(
+            job.posting_url.as_deref(),
+            Some("https://example.com/jobs/1")
+        );
+        assert_eq!(
+            job.description.as_deref(),
+            Some("Build the billing pipeline")
+        );
+        assert!(job.matches_search("billing"));
+        assert!(job.matches_search("example.com"));
+
+        assert!(store
+            .update_posting_url(1, "ftp://example.com".to_string())
+            .is_err());
+        assert!(store.jobs[0].posting_url.is_some());
+        store.update_posting_url(1, " ".to_string()).unwrap();
+        store.update_description(1, String::new()).unwrap();
+        let job = &store.jobs[0];
+        assert_eq!((&job.posting_url, &job.description), (&None, &None));
+        assert!(!job.matches_search("billing"));
+        assert_eq!(store.undo().unwrap().as_deref(), Some("Change description"));
+    }
+
+    #[test]
+    fn posting_urls_must_be_web_addresses() {
+        assert_eq!(parse_posting_url("  ").unwrap(), None);
+        assert_eq!(
+            parse_posting_url(" https://example.com/jobs/1 ")
+                .unwrap()
+                .as_deref(),
+            Some("https://example.com/jobs/1")
+        );
+        assert!(parse_posting_url("example.com/jobs/1").is_err());
+        assert!(parse_posting_url("javascript:alert(1)").is_err());
+    }
 }
@@ -0,0 +1,396 @@
+use crate::job_store::apply_change;
+use crate::Backup;
+use crate::Contact;
+use crate::Job;
+use crate::JobChange;
+use crate::JobDocument;
+use crate::JsonFileStorage;
+use crate::Source;
+use crate::Stage;
+use crate::Storage;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+use std::ffi::OsString;
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+
+const DEFAULT_BACKUPS: usize = 5;
+/// Backups are taken at most this often, so a burst of writes (a CSV
+/// import, a ghosting pass) doesn't push every older backup out.
+const DEFAULT_BACKUP_INTERVAL: TimeDelta = TimeDelta::hours(1);
+/// `strftime` format of the time in backup file names. Down to the
+/// microsecond, so a restore right after a write doesn't back up over the
+/// write's backup.
+const BACKUP_TIME_FORMAT: &str = "%Y%m%d-%H%M%S%.6f";
+/// Reads the times [`BACKUP_TIME_FORMAT`] writes, and the whole seconds
+/// older versions wrote.
+const BACKUP_TIME_PARSE_FORMAT: &str = "%Y%m%d-%H%M%S%.f";
+
+impl JsonFileStorage {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            backups: DEFAULT_BACKUPS,
+            backup_interval: DEFAULT_BACKUP_INTERVAL,
+            document: None,
+        }
+    }
+
+    /// Sets how many backups to keep. Zero disables backups.
+    pub fn with_backups(mut self, backups: usize) -> Self {
+        self.backups = backups;
+        self
+    }
+
+    /// Sets how long to wait after a backup before taking the next one.
+    pub fn with_backup_interval(mut self, interval: TimeDelta) -> Self {
+        self.backup_interval = interval;
+        self
+    }
+
+    /// `<path><suffix>`, e.g. `jobtrack.json.tmp`.
+    fn sibling(&self, suffix: &str) -> PathBuf {
+        let mut name = OsString::from(self.path.as_os_str());
+        name.push(suffix);
+        PathBuf::from(name)
+    }
+
+    /// When the backup at `path` was taken, if it's one of this file's.
+    /// Backups are named `<path>.<time>.bak`.
+    fn backup_time(&self, path: &Path) -> Option<DateTime<Utc>> {
+        let name = path.file_name()?.to_str()?;
+        let own_name = self.path.file_name()?.to_str()?;
+        let time = name
+            .strip_prefix(own_name)?
+            .strip_prefix('.')?
+            .strip_suffix(".bak")?;
+        let time = NaiveDateTime::parse_from_str(time, BACKUP_TIME_PARSE_FORMAT).ok()?;
+        Some(time.and_utc())
+    }
+
+    /// Copies the current file to a new backup and deletes the oldest ones
+    /// beyond `backups`.
+    fn take_backup(&self) -> Result<()> {
+        if self.backups == 0 || !self.path.exists() {
+            return Ok(());
+        }
+        let time = Utc::now().format(BACKUP_TIME_FORMAT);
+        fs::copy(&self.path, self.sibling(&format!(".{time}.bak")))?;
+        for backup in self.backups()?.into_iter().skip(self.backups) {
+            fs::remove_file(&backup.path)?;
+        }
+        Ok(())
+    }
+
+    /// Whether the newest backup is older than `backup_interval`, or there
+    /// is none.
+    fn backup_due(&self) -> Result<bool> {
+        let newest = self.backups()?.first().map(|backup| backup.taken);
+        Ok(newest.is_none_or(|taken| Utc::now() - taken >= self.backup_interval))
+    }
+
+    /// Reads the data file. A missing or empty file is an empty tracker.
+    /// Nothing is printed to stdout so command-line output stays parseable.
+    fn read_file(&self) -> Result<JobDocument> {
+        match OpenOptions::new().read(true).open(&self.path) {
+            Ok(mut file) => {
+                let mut data = String::new();
+                file.read_to_string(&mut data)?;
+                if data.trim().is_empty() {
+                    Ok(JobDocument::default())
+                } else {
+                    JobDocument::from_json(&data, &self.path)
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(JobDocument::default()),
+            Err(e) => Err(anyhow!("Could not open {}: {e}", self.path.display())),
+        }
+    }
+
+    /// Writes the document over the data file, backing the old file up
+    /// first if a backup is due.
+    fn write_file(&self, document: &JobDocument) -> Result<()> {
+        if self.backup_due()? {
+            self.take_backup()?;
+        }
+        write_atomically(&self.path, document.to_json()?.as_bytes())
+    }
+
+    /// Applies `change` to the file contents and rewrites the file. The file
+    /// is read first if this storage hasn't touched it yet, so a single-row
+    /// write never clobbers rows that were never loaded. The change is only
+    /// kept in memory once it's on disk.
+    fn modify(&mut self, change: impl FnOnce(&mut JobDocument)) -> Result<()> {
+        let mut document = match &self.document {
+            Some(document) => document.clone(),
+            None => self.read_file()?,
+        };
+        change(&mut document);
+        self.write_file(&document)?;
+        self.document = Some(document);
+        Ok(())
+    }
+}
+
+/// Writes `data` to a temp file next to `path`, flushes it to disk and
+/// renames it over `path`, so a crash leaves either the old or the new file
+/// in place and never a truncated one.
+pub(crate) fn write_atomically(path: &Path, data: &[u8]) -> Result<()> {
+    let mut tmp_name = OsString::from(path.as_os_str());
+    tmp_name.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_name);
+    let mut tmp = File::create(&tmp_path)?;
+    tmp.write_all(data)?;
+    tmp.sync_all()?;
+    drop(tmp);
+
+    fs::rename(&tmp_path, path)?;
+    // Persist the rename itself. Not every platform can open a directory
+    // for syncing, so this is best effort.
+    if let Some(dir) = path.parent() {
+        let dir = if dir.as_os_str().is_empty() {
+            PathBuf::from(".")
+        } else {
+            dir.to_path_buf()
+        };
+        if let Ok(dir) = File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
+
+impl Storage for JsonFileStorage {
+    fn load(&mut self) -> Result<Vec<Job>> {
+        let document = self.read_file()?;
+        let jobs = document.jobs.clone();
+        self.document = Some(document);
+        Ok(jobs)
+    }
+
+    fn save(&mut self, jobs: &[Job]) -> Result<()> {
+        self.modify(|document| document.jobs = jobs.to_vec())
+    }
+
+    fn insert(&mut self, job: &Job) -> Result<()> {
+        self.modify(|document| document.jobs.push(job.clone()))
+    }
+
+    fn update(&mut self, job: &Job) -> Result<()> {
+        self.modify(|document| {
+            if let Some(existing) = document.jobs.iter_mut().find(|j| j.id == job.id) {
+                *existing = job.clone();
+            }
+        })
+    }
+
+    fn delete(&mut self, id: u32) -> Result<()> {
+        self.modify(|document| document.jobs.retain(|j| j.id != id))
+    }
+
+    fn apply_changes(&mut self, changes: &[JobChange]) -> Result<()> {
+        self.modify(|document| {
+            for change in changes {
+                apply_change(&mut document.jobs, &change.before, &change.after, |j| j.id);
+            }
+        })
+    }
+
+    fn load_stages(&mut self) -> Result<Vec<Stage>> {
+        if self.document.is_none() {
+            self.document = Some(self.read_file()?);
+        }
+        Ok(self
+            .document
+            .as_ref()
+            .map_or_else(Stage::defaults, |d| d.stages.clone()))
+    }
+
+    fn save_stages(&mut self, stages: &[Stage]) -> Result<()> {
+        self.modify(|document| document.stages = stages.to_vec())
+    }
+
+    fn load_contacts(&mut self) -> Result<Vec<Contact>> {
+        if self.document.is_none() {
+            self.document = Some(self.read_file()?);
+        }
+        Ok(self
+            .document
+            .as_ref()
+            .map_or_else(Vec::new, |d| d.contacts.clone()))
+    }
+
+    fn save_contacts(&mut self, contacts: &[Contact]) -> Result<()> {
+        self.modify(|document| document.contacts = contacts.to_vec())
+    }
+
+    fn load_sources(&mut self) -> Result<Vec<Source>> {
+        if self.document.is_none() {
+            self.document = Some(self.read_file()?);
+        }
+        Ok(self
+            .document
+            .as_ref()
+            .map_or_else(Source::defaults, |d| d.sources.clone()))
+    }
+
+    fn save_sources(&mut self, sources: &[Source]) -> Result<()> {
+        self.modify(|document| document.sources = sources.to_vec())
+    }
+
+    fn backups(&self) -> Result<Vec<Backup>> {
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(anyhow!("Could not list {}: {e}", dir.display())),
+        };
+        let mut backups = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if let Some(taken) = self.backup_time(&path) {
+                backups.push(Backup {
+                    index: 0,
+                    path,
+                    taken,
+                });
+            }
+        }
+        backups.sort_by_key(|backup| std::cmp::Reverse(backup.taken));
+        for (index, backup) in backups.iter_mut().enumerate() {
+            backup.index = index + 1;
+        }
+        Ok(backups)
+    }
+
+    /// The file being replaced is always backed up first, so a restore can
+    /// itself be restored away.
+    fn restore_backup(&mut self, index: usize) -> Result<Vec<Job>> {
+        let path = self
+            .backups()?
+            .into_iter()
+            .find(|backup| backup.index == index)
+            .map(|backup| backup.path)
+            .ok_or_else(|| anyhow!("Backup {index} not found"))?;
+        let data = fs::read_to_string(&path)
+            .map_err(|e| anyhow!("Could not read backup {}: {e}", path.display()))?;
+        let document = JobDocument::from_json(&data, &path)?;
+        self.take_backup()?;
+        write_atomically(&self.path, document.to_json()?.as_bytes())?;
+        let jobs = document.jobs.clone();
+        self.document = Some(document);
+        Ok(jobs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JSON_FORMAT_VERSION;
+
+    /// An empty directory of its own for test `name`.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("jobtracker-test-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn old_files_are_upgraded_and_backed_up_on_write() {
+        let dir = test_dir("json-upgrade");
+        let path = dir.join("jobs.json");
+        let old = r#"[{"id": 1, "company": "Acme", "role": "Dev", "role_location": null,
+            "status": "Applied", "timestamp": "2024-01-02T03:04:05Z", "source": "LinkedIn"}]"#;
+        fs::write(&path, old).unwrap();
+
+        let mut storage = JsonFileStorage::new(&path).with_backup_interval(TimeDelta::zero());
+        let mut jobs = storage.load().unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(storage.load_stages().unwrap(), Stage::defaults());
+        // Reading alone leaves the file as it was
+        assert_eq!(fs::read_to_string(&path).unwrap(), old);
+
+        jobs[0].company = "Initech".to_string();
+        storage.update(&jobs[0]).unwrap();
+        let written = JobDocument::from_json(&fs::read_to_string(&path).unwrap(), &path).unwrap();
+        assert_eq!(written.version, JSON_FORMAT_VERSION);
+        assert_eq!(written.jobs, jobs);
+
+        let backups = storage.backups().unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read_to_string(&backups[0].path).unwrap(), old);
+        assert!(storage.restore_backup(2).is_err());
+        let restored = storage.restore_backup(1).unwrap();
+        assert_eq!(restored[0].company, "Acme");
+        assert_eq!(storage.load().unwrap(), restored);
+        // The restore backed up the file it replaced without losing the
+        // backup it restored from, even within the same second
+        let backups = storage.backups().unwrap();
+        assert_eq!(backups.len(), 2);
+        assert!(fs::read_to_string(&backups[0].path)
+            .unwrap()
+            .contains("Initech"));
+        assert_eq!(fs::read_to_string(&backups[1].path).unwrap(), old);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn missing_and_empty_files_are_empty_trackers() {
+        let dir = test_dir("json-empty");
+        let path = dir.join("jobs.json");
+        let mut storage = JsonFileStorage::new(&path);
+        assert!(storage.load().unwrap().is_empty());
+        fs::write(&path, " \n").unwrap();
+        assert!(storage.load().unwrap().is_empty());
+        assert_eq!(storage.load_sources().unwrap(), Source::defaults());
+        assert!(!dir.join("jobs.json.tmp").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn only_this_files_backups_are_listed() {
+        let dir = test_dir("json-backups");
+        let path = dir.join("jobs.json");
+        for name in [
+            "jobs.json.20240102-030405.bak",
+            "jobs.json.20240102-030405.123456.bak",
+            "jobs.json.3",
+            "jobs.json.tmp",
+            "jobs.json.notatime.bak",
+            "other.json.20240102-030405.bak",
+        ] {
+            fs::write(dir.join(name), "[]").unwrap();
+        }
+        let storage = JsonFileStorage::new(&path);
+        let mut names: Vec<String> = storage
+            .backups()
+            .unwrap()
+            .into_iter()
+            .map(|backup| {
+                backup
+                    .path
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "jobs.json.20240102-030405.123456.bak",
+                "jobs.json.20240102-030405.bak"
+            ]
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
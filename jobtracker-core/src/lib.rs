@@ -1,70 +1,458 @@
+mod compensation;
+mod config;
+mod contact;
+mod content_line;
+mod data_file_error;
+mod ghosting_rule;
+mod html;
+mod ical;
+mod interview;
+mod interview_outcome;
 mod job;
-mod job_app;
+mod job_csv;
+mod job_document;
 mod job_source;
 mod job_status;
 mod job_store;
+mod json_file_storage;
+mod location;
+mod memory_storage;
+mod note;
+mod pipeline_flow;
+mod posting_import;
+mod source;
+mod sqlite_storage;
+mod stage;
 mod summary_counts;
-use chrono::{DateTime, Utc};
-use eframe::egui::Color32;
+mod tag;
+#[cfg(test)]
+mod test_support;
+mod transition_error;
+mod transition_policy;
+mod undo_history;
+mod vcard;
+mod work_arrangement;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use chrono_tz::Tz;
+pub use compensation::format_amount;
+pub use config::{DataFile, DATA_FILE_ENV};
+pub use interview::local_timezone;
+pub use job_document::JSON_FORMAT_VERSION;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Debug;
+use std::path::PathBuf;
 use strum_macros::EnumIter;
+pub use tag::{normalize_tag, parse_tags};
 
 pub const APP_NAME: &str = "Job Application Tracker";
-pub const WINDOW_WIDTH: f32 = 1200.0;
-pub const WINDOW_HEIGHT: f32 = 800.0;
-
-const NAVY_BLUE: Color32 = Color32::from_rgb(65, 105, 225);
-const CYAN: Color32 = Color32::from_rgb(0, 255, 255);
-const GREEN: Color32 = Color32::from_rgb(0, 255, 0);
-const RED: Color32 = Color32::from_rgb(255, 0, 0);
-const GRAY: Color32 = Color32::from_rgb(128, 128, 128);
-
-/// Representation of the application itself.
-#[derive(Default)]
-pub struct JobApp {
-    /// Internal datastore of all job applications so far.
-    pub store: JobStore,
-    /// Input element in form.
-    pub new_company: String,
-    /// Input element in form.
-    pub new_role: String,
-    /// Input element in form.
-    pub new_role_location: String,
-    /// Input element in form
-    pub new_source: String,
-    /// Input element in form
-    pub search_text: String,
-    /// The set of timestamps the user has edited in the form.
-    pub edit_timestamps: HashMap<u32, String>,
-    /// The set of company names the user has edited in the form.
-    pub edit_companies: HashMap<u32, String>,
-    /// Last time the data file (DB TODO) was successfully read and deserialized.
-    pub last_refresh: DateTime<Utc>,
-    /// Tracks which chart entry the user's currently selected. This is used for
-    /// highlighting and filtering for a specific job application through the stacked
-    /// bar chart.
-    pub selected_company: Option<String>,
+/// User settings read from `config.toml` in the tracker's config directory
+/// (`$XDG_CONFIG_HOME/jobtracker/`).
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Data file to use when neither `--data-file` nor `JOBTRACKER_DATA`
+    /// names one.
+    pub data_file: Option<PathBuf>,
+    /// How many rotating backups to keep next to a JSON data file.
+    pub backups: Option<usize>,
+    /// When to mark silent applications as ghosted. Off unless configured.
+    pub ghosting: Option<GhostingRule>,
+    pub undo: UndoConfig,
+    pub currency: CurrencyConfig,
 }
 
-#[derive(Default, Debug)]
+/// Exchange rates used to put offers in different currencies side by side.
+/// Rates aren't fetched from anywhere, they're whatever the user sets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CurrencyConfig {
+    /// Currency offer totals are compared in, e.g. `USD`.
+    pub base: String,
+    /// Value of one unit of each other currency in `base`, e.g.
+    /// `EUR = 1.08`.
+    pub rates: BTreeMap<String, f64>,
+}
+
+/// How much undo history to keep and whether it outlives the process.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UndoConfig {
+    /// Most commands kept on the undo stack.
+    pub limit: usize,
+    /// Save the undo history next to the data file so it survives restarts
+    /// and can be used from the command line. Off by default.
+    pub persist: bool,
+}
+
+/// Moves job applications to `target` (Ghosted unless set) once they've sat
+/// in one of `statuses` for `after_days` days without a status change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GhostingRule {
+    pub after_days: u32,
+    #[serde(default = "GhostingRule::default_statuses")]
+    pub statuses: Vec<JobStatus>,
+    #[serde(default = "GhostingRule::default_target")]
+    pub target: JobStatus,
+}
+
+#[derive(Debug)]
 pub struct JobStore {
     pub jobs: Vec<Job>,
     pub summary_stats: SummaryCounts,
+    /// Pipeline stages, in order. Every job's status names one of these.
+    pub stages: Vec<Stage>,
+    /// People involved in the applications, each linked to any number of
+    /// jobs.
+    pub contacts: Vec<Contact>,
+    /// Where applications can come from, in the order they're offered.
+    /// Every job's source names one of these.
+    pub sources: Vec<Source>,
+    /// Backend every mutation is persisted through.
+    pub storage: Box<dyn Storage>,
+    /// Applied on every load and refresh, if set.
+    pub ghosting: Option<GhostingRule>,
+    /// IDs of the jobs the most recent ghosting run moved to Ghosted.
+    pub last_ghosted: Vec<u32>,
+    /// Every mutation made through the store, for undo and redo.
+    pub undo_history: UndoHistory,
+    /// Used to compare offers made in different currencies.
+    pub currency: CurrencyConfig,
+}
+
+/// One job's state before and after a mutation. `before` is `None` for an
+/// insert and `after` is `None` for a permanent delete.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobChange {
+    pub before: Option<Job>,
+    pub after: Option<Job>,
+}
+
+/// A store mutation as the user sees it, undone and redone as a unit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StoreCommand {
+    /// Short description, e.g. "Change status".
+    pub label: String,
+    pub changes: Vec<JobChange>,
+    #[serde(default)]
+    pub contact_changes: Vec<ContactChange>,
+    /// Set if the pipeline's stages were edited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage_change: Option<ListChange<Stage>>,
+    /// Set if the list of sources was edited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_change: Option<ListChange<Source>>,
+}
+
+/// A whole list of stages or sources before and after a mutation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListChange<T> {
+    pub before: Vec<T>,
+    pub after: Vec<T>,
+}
+
+/// One contact's state before and after a mutation, like [`JobChange`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactChange {
+    pub before: Option<Contact>,
+    pub after: Option<Contact>,
+}
+
+/// Bounded undo and redo stacks of store commands.
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct UndoHistory {
+    /// Oldest first; the next command to undo is at the back.
+    pub undo: VecDeque<StoreCommand>,
+    /// The next command to redo is at the back.
+    pub redo: Vec<StoreCommand>,
+    /// Most commands kept on the undo stack.
+    #[serde(skip)]
+    pub limit: usize,
+    /// Where the history is saved after every change, if anywhere.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+/// A place job applications are persisted to. `JobStore` keeps the
+/// working copy in memory and forwards every mutation here.
+pub trait Storage: Debug {
+    /// Reads every stored job application.
+    fn load(&mut self) -> Result<Vec<Job>>;
+    /// Replaces everything stored with `jobs`.
+    fn save(&mut self, jobs: &[Job]) -> Result<()>;
+    /// Persists a newly created job application.
+    fn insert(&mut self, job: &Job) -> Result<()>;
+    /// Overwrites the stored job application with the same ID.
+    fn update(&mut self, job: &Job) -> Result<()>;
+    /// Removes the job application with the given ID, if present.
+    fn delete(&mut self, id: u32) -> Result<()>;
+    /// Inserts, updates or deletes each job in `changes`, in order.
+    /// Backends that can write them all in one go, and either keep all of
+    /// them or none, should.
+    fn apply_changes(&mut self, changes: &[JobChange]) -> Result<()> {
+        for change in changes {
+            match (&change.before, &change.after) {
+                (None, Some(after)) => self.insert(after)?,
+                (Some(_), Some(after)) => self.update(after)?,
+                (Some(before), None) => self.delete(before.id)?,
+                (None, None) => {}
+            }
+        }
+        Ok(())
+    }
+    /// Reads the pipeline stage definitions stored with the jobs.
+    fn load_stages(&mut self) -> Result<Vec<Stage>>;
+    /// Replaces the stored pipeline stage definitions.
+    fn save_stages(&mut self, stages: &[Stage]) -> Result<()>;
+    /// Reads every stored contact.
+    fn load_contacts(&mut self) -> Result<Vec<Contact>>;
+    /// Replaces every stored contact with `contacts`.
+    fn save_contacts(&mut self, contacts: &[Contact]) -> Result<()>;
+    /// Reads the job source definitions stored with the jobs.
+    fn load_sources(&mut self) -> Result<Vec<Source>>;
+    /// Replaces the stored job source definitions.
+    fn save_sources(&mut self, sources: &[Source]) -> Result<()>;
+    /// Lists the backups this backend keeps, newest first.
+    fn backups(&self) -> Result<Vec<Backup>> {
+        Ok(Vec::new())
+    }
+    /// Makes backup `index` the live data and returns its jobs.
+    fn restore_backup(&mut self, index: usize) -> Result<Vec<Job>> {
+        anyhow::bail!("Backup {index} not found: this storage backend doesn't keep backups")
+    }
+}
+
+/// A previous copy of the data kept by a storage backend.
+#[derive(Debug, Clone)]
+pub struct Backup {
+    /// Position in the list of backups, 1 being the most recent.
+    pub index: usize,
+    pub path: PathBuf,
+    /// When the backup was taken.
+    pub taken: DateTime<Utc>,
 }
 
+/// Stores all job applications in one pretty-printed JSON file, as a
+/// [`JobDocument`]: `{ "version": N, "jobs": [...] }` plus the stages,
+/// contacts and sources. Every write replaces the file atomically, after
+/// copying the old one to a timestamped backup if one is due. Files from
+/// older versions are upgraded when read.
+#[derive(Debug)]
+pub struct JsonFileStorage {
+    /// Location of the data file.
+    pub path: PathBuf,
+    /// How many previous versions of the file to keep, as
+    /// `<path>.<time>.bak`.
+    pub backups: usize,
+    /// Least time between two backups.
+    pub backup_interval: TimeDelta,
+    /// Contents of the data file, read on first use. Every write rewrites
+    /// the whole file from this copy.
+    document: Option<JobDocument>,
+}
+
+/// On-disk layout of the JSON data file. Files written before `version`
+/// existed hold a bare array of jobs and are treated as version 0.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobDocument {
+    /// Format version the file was written with.
+    pub version: u32,
+    pub jobs: Vec<Job>,
+    #[serde(default = "Stage::defaults")]
+    pub stages: Vec<Stage>,
+    #[serde(default)]
+    pub contacts: Vec<Contact>,
+    #[serde(default = "Source::defaults")]
+    pub sources: Vec<Source>,
+}
+
+/// Problems with the contents of a data file that a retry won't fix.
+#[derive(Debug)]
+pub enum DataFileError {
+    /// The file was written by a newer build of the tracker.
+    NewerVersion {
+        path: PathBuf,
+        /// As found in the file, which may not fit a `u32`.
+        found: u64,
+        supported: u32,
+    },
+    /// The file is neither a job list nor a versioned document.
+    UnrecognizedFormat { path: PathBuf },
+}
+
+/// Status changes the store refuses to make.
+#[derive(Debug)]
+pub enum TransitionError {
+    /// The pipeline's rules don't allow moving from `from` to `to`. Pass the
+    /// override flag to make the change anyway.
+    NotAllowed {
+        id: u32,
+        from: JobStatus,
+        to: JobStatus,
+    },
+}
+
+/// Stores job applications as rows of a `jobs` table in a SQLite database,
+/// so a single edit only touches the affected row.
+#[derive(Debug)]
+pub struct SqliteStorage {
+    /// Location of the database file.
+    pub path: PathBuf,
+    conn: rusqlite::Connection,
+}
+
+/// Keeps job applications in memory only. Nothing survives the process,
+/// which makes this the backend to use in tests.
 #[derive(Default, Debug)]
+pub struct MemoryStorage {
+    pub jobs: Vec<Job>,
+    /// Empty means the default stages.
+    pub stages: Vec<Stage>,
+    pub contacts: Vec<Contact>,
+    /// Empty means the default sources.
+    pub sources: Vec<Source>,
+}
+
+/// Names of the CSV columns holding each job field, for import and export.
+/// Header names are matched case-insensitively on import.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvColumns {
+    pub company: String,
+    pub role: String,
+    pub location: String,
+    pub status: String,
+    pub source: String,
+    pub date_applied: String,
+    /// Optional on import.
+    pub notes: String,
+    /// Optional on import. Tags are separated by commas.
+    pub tags: String,
+}
+
+/// What a CSV import did, or on a dry run, would do.
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct ImportReport {
+    /// Jobs added to the store, or that would have been.
+    pub imported: Vec<Job>,
+    /// Rows that couldn't be turned into a job.
+    pub skipped: Vec<SkippedRow>,
+    /// Rows matching a job already in the store or earlier in the file.
+    pub duplicates: Vec<SkippedRow>,
+    pub dry_run: bool,
+}
+
+/// A CSV row left out of an import.
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedRow {
+    /// Line number in the file, counting the header as line 1.
+    pub line: u64,
+    pub reason: String,
+}
+
+#[derive(Default, Debug, Serialize)]
 pub struct SummaryCounts {
     pub total: usize,
-    pub rejected: usize,
-    pub ghosted: usize,
-    pub applied: usize,
-    pub interviews: usize,
-    pub offers: usize,
+    /// Applications in each stage, in pipeline order. Statuses that are no
+    /// longer defined as stages come last.
+    pub by_stage: Vec<StageCount>,
+    /// Past the first stage and not in a failure stage.
+    pub advanced: usize,
+    /// In a stage with a success outcome.
+    pub succeeded: usize,
+    /// In a stage with a failure outcome.
+    pub failed: usize,
+    /// `advanced`, `succeeded` and `failed` as percentages of `total`.
+    /// `None` when there are no applications.
+    pub interview_rate: Option<f64>,
+    pub offer_rate: Option<f64>,
+    pub rejection_rate: Option<f64>,
+    /// Median days from applying to the first response, see
+    /// [`Job::first_response`], over the applications that have had one.
+    pub median_days_to_response: Option<f64>,
+    /// The same counts over the applications with each tag, by tag name.
+    /// Empty in the per-tag counts themselves.
+    pub by_tag: Vec<TagCounts>,
+    /// The same counts for each work arrangement applications have, and
+    /// for applications whose arrangement isn't known. Empty in the
+    /// breakdowns themselves.
+    pub by_arrangement: Vec<ArrangementCounts>,
+    /// The same counts for each area, see [`Location::area`], by name.
+    /// Empty in the breakdowns themselves.
+    pub by_area: Vec<AreaCounts>,
+    /// The same counts for each source applications came from, in the
+    /// order of the store's sources, then for applications with no source.
+    /// Empty in the breakdowns themselves.
+    pub by_source: Vec<SourceCounts>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagCounts {
+    pub tag: String,
+    pub counts: SummaryCounts,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArrangementCounts {
+    /// `None` for applications with no known arrangement.
+    pub arrangement: Option<WorkArrangement>,
+    pub counts: SummaryCounts,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AreaCounts {
+    pub area: String,
+    pub counts: SummaryCounts,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SourceCounts {
+    /// `None` for applications with no source.
+    pub source: Option<JobSource>,
+    pub counts: SummaryCounts,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StageCount {
+    pub stage: JobStatus,
+    pub count: usize,
+}
+
+/// How applications outside the trash have moved through the pipeline,
+/// for the funnel and flow charts.
+#[derive(Debug, Default, Serialize)]
+pub struct PipelineFlow {
+    /// Every stage without a failure outcome, in pipeline order, with the
+    /// applications that got at least that far.
+    pub funnel: Vec<FunnelStage>,
+    /// Applications grouped by where they came from, the furthest stage
+    /// they got to and where they ended up. Ordered by source, then by
+    /// stage.
+    pub paths: Vec<FlowPath>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FunnelStage {
+    pub stage: JobStatus,
+    pub job_ids: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FlowPath {
+    /// `None` for applications with no source.
+    pub source: Option<JobSource>,
+    /// Furthest stage without a failure outcome the applications have
+    /// been in, see [`JobStore::furthest_stage`].
+    pub reached: JobStatus,
+    /// Final stage the applications are in, `None` while they're still
+    /// open.
+    pub outcome: Option<JobStatus>,
+    pub job_ids: Vec<u32>,
 }
 
 /// Representation of a job application entered by the user.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Job {
     /// Sequential ID number.
     pub id: u32,
@@ -72,7 +460,7 @@ pub struct Job {
     pub company: String,
     /// Name of the position the user applied to.
     pub role: String,
-    /// Location of the position.
+    /// Location of the position, as entered.
     pub role_location: Option<String>,
     /// State of this job application.
     pub status: JobStatus,
@@ -81,31 +469,280 @@ pub struct Job {
     /// value of this is set when the user clicks the button
     /// to add a new application to the tracker.
     pub timestamp: DateTime<Utc>,
-    /// Where this job application was discovered.
+    /// Where this job application was discovered, if the user said.
     pub source: Option<JobSource>,
+    /// ID of the contact who referred the user, for applications from a
+    /// referral source.
+    #[serde(default)]
+    pub referrer: Option<u32>,
+    /// Every status this application has been in, oldest first. The last
+    /// entry matches `status`.
+    #[serde(default)]
+    pub history: Vec<StatusChange>,
+    /// When the application was moved to the trash. Trashed applications
+    /// are hidden everywhere except the trash until restored or purged.
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// What happened along the way, e.g. recruiter calls and interview
+    /// questions. Oldest first.
+    #[serde(default)]
+    pub notes: Vec<Note>,
+    /// Scheduled and past interviews, in no particular order.
+    #[serde(default)]
+    pub interviews: Vec<Interview>,
+    /// Pay advertised in the posting, if it said.
+    #[serde(default)]
+    pub salary_range: Option<SalaryRange>,
+    /// What was offered, once there's an offer.
+    #[serde(default)]
+    pub offer: Option<Offer>,
+    /// Link to the job posting.
+    #[serde(default)]
+    pub posting_url: Option<String>,
+    /// Copy of the posting's description, kept for when the posting is
+    /// taken down.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Free-form labels such as "backend" or "visa sponsorship", lowercase.
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    /// `role_location` broken down into place and work arrangement, so
+    /// applications can be grouped by them. Parsed from `role_location`
+    /// whenever that changes, and correctable by hand.
+    #[serde(default)]
+    pub location: Location,
+}
+
+/// Where a job is and whether it's done from there, as far as is known.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    pub city: Option<String>,
+    /// State, province or similar.
+    pub region: Option<String>,
+    pub country: Option<String>,
+    pub arrangement: Option<WorkArrangement>,
 }
 
-/// The states a job application may be in.
-/// A job application will only be in one state
-/// at any moment.
-#[derive(EnumIter, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-pub enum JobStatus {
-    Applied,
-    Interview,
-    Offer,
-    Rejected,
-    Ghosted,
-}
-
-#[derive(Default, EnumIter, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-pub enum JobSource {
-    Recruiter,
-    LinkedIn,
-    Monster,
-    Indeed,
+/// Where the work is done.
+#[derive(EnumIter, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum WorkArrangement {
+    Onsite,
+    Hybrid,
+    Remote,
+}
+
+/// Pay range advertised in a job posting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SalaryRange {
+    pub min: f64,
+    pub max: f64,
+    /// ISO 4217 code, e.g. `USD`.
+    pub currency: String,
+    pub period: PayPeriod,
+}
+
+/// How often an amount is paid.
+#[derive(EnumIter, Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum PayPeriod {
+    Hour,
+    Month,
     #[default]
-    NotProvided,
-    Talent,
-    Glassdoor,
-    ZipRecruiter,
+    Year,
+}
+
+/// The terms of a job offer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Offer {
+    /// Base pay per `period`.
+    pub base: f64,
+    pub period: PayPeriod,
+    /// Expected bonus per year.
+    pub bonus: Option<f64>,
+    /// Value of the whole equity grant at the time of the offer.
+    pub equity: Option<f64>,
+    /// Years the equity grant vests over. Four when not given.
+    pub vesting_years: Option<u32>,
+    /// ISO 4217 code, e.g. `USD`. Every amount in the offer is in it.
+    pub currency: String,
+    /// Health insurance, retirement matching, time off and the like.
+    pub benefits: Option<String>,
+    /// Date the offer has to be accepted by.
+    pub deadline: Option<NaiveDate>,
+}
+
+/// A job in a successful final stage, with its offer reduced to yearly
+/// amounts for comparison.
+#[derive(Debug, Clone, Serialize)]
+pub struct OfferComparison {
+    pub job_id: u32,
+    pub company: String,
+    pub role: String,
+    /// `None` if no offer details have been entered yet.
+    pub offer: Option<Offer>,
+    /// Base pay per year, in the offer's currency.
+    pub annual_base: Option<f64>,
+    /// Yearly equity, the grant spread evenly over its vesting years.
+    pub annual_equity: Option<f64>,
+    /// Base, bonus and equity per year, in the offer's currency.
+    pub annual_total: Option<f64>,
+    /// `annual_total` in the configured base currency, `None` if there's
+    /// no exchange rate for the offer's currency.
+    pub total_in_base: Option<f64>,
+}
+
+/// An interview for a job application.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interview {
+    /// Unique within the job application.
+    pub id: u32,
+    /// Which round this is, e.g. "Phone screen" or "Onsite".
+    pub round: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Time zone the interview was scheduled in. Times are shown in it.
+    pub timezone: Tz,
+    /// Names of the people on the other side.
+    #[serde(default)]
+    pub interviewers: Vec<String>,
+    /// Address or meeting link.
+    pub location: Option<String>,
+    /// How it went, once known.
+    pub outcome: Option<InterviewOutcome>,
+    /// `UID` of the calendar invite it was imported from, so importing the
+    /// invite again updates it instead of adding a copy.
+    #[serde(default)]
+    pub calendar_uid: Option<String>,
+}
+
+#[derive(EnumIter, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum InterviewOutcome {
+    Passed,
+    Failed,
+    Cancelled,
+}
+
+/// What an iCalendar import did.
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct CalendarImport {
+    /// Interviews added or updated, with the ID of their job application.
+    pub imported: Vec<(u32, Interview)>,
+    /// Summaries of events that couldn't be matched to an application or
+    /// had no usable start time.
+    pub unmatched: Vec<String>,
+}
+
+/// A person involved in job applications, e.g. a recruiter, hiring manager
+/// or referrer.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Contact {
+    /// Sequential ID number.
+    pub id: u32,
+    pub name: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    /// What they do, e.g. "Recruiter" or "Engineering Manager".
+    pub role: Option<String>,
+    pub company: Option<String>,
+    pub notes: Option<String>,
+    /// IDs of the job applications this person is involved in.
+    #[serde(default)]
+    pub job_ids: Vec<u32>,
+}
+
+/// What a vCard import did.
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct ContactImport {
+    /// Contacts added to the store.
+    pub imported: Vec<Contact>,
+    /// Cards left out because a contact with the same email address, or
+    /// the same name if there's no email, already exists.
+    pub duplicates: Vec<String>,
+}
+
+/// A timestamped note on a job application. `text` is Markdown.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Note {
+    /// Unique within the job application.
+    pub id: u32,
+    pub at: DateTime<Utc>,
+    pub text: String,
+    /// When `text` was last changed, if it has been.
+    #[serde(default)]
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+/// A single entry in a job application's status history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusChange {
+    /// Status before the change. `None` for the entry recorded when the
+    /// application was added to the tracker.
+    pub from: Option<JobStatus>,
+    pub to: JobStatus,
+    pub at: DateTime<Utc>,
+    /// Optional free text, e.g. who reached out.
+    pub note: Option<String>,
+    /// Whether the tracker made this change on its own (e.g. auto-ghosting)
+    /// rather than the user.
+    #[serde(default)]
+    pub automatic: bool,
+    /// Whether the user forced this change past the pipeline's transition
+    /// rules to fix a mistake.
+    #[serde(default)]
+    pub correction: bool,
+}
+
+/// The stage a job application is in, by name.
+/// A job application will only be in one stage
+/// at any moment. Which names are valid, and what they
+/// mean, is up to the store's [`Stage`] list.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct JobStatus(Cow<'static, str>);
+
+/// A user-defined step in the application pipeline, e.g. "Phone screen"
+/// or "Take-home".
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Stage {
+    pub name: JobStatus,
+    /// Position in the pipeline. Stages are listed and charted in this order.
+    pub order: u32,
+    /// Chart and legend color as `#rrggbb`.
+    pub color: String,
+    /// No further progress is expected once an application gets here.
+    #[serde(default)]
+    pub terminal: bool,
+    /// Whether ending up here counts as a win or a loss, if either.
+    #[serde(default)]
+    pub outcome: Option<StageOutcome>,
+    /// Stages an application may move to from here. Unset means any later
+    /// stage or any final one, and nowhere at all from a final stage.
+    #[serde(default)]
+    pub next: Option<Vec<JobStatus>>,
+}
+
+#[derive(EnumIter, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum StageOutcome {
+    Success,
+    Failure,
+}
+
+/// Where a job application was found, by name, e.g. "LinkedIn". Which
+/// names are valid is up to the store's [`Source`] list.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct JobSource(Cow<'static, str>);
+
+/// A user-defined place job applications come from, e.g. a job board, a
+/// company's careers page or a referral.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Source {
+    pub name: JobSource,
+    /// Other names the source is recognized by when typed, e.g. "Wellfound"
+    /// for "AngelList". Matched case-insensitively.
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Someone put the user forward. Applications from a referral source
+    /// can name who did, see [`Job::referrer`].
+    #[serde(default)]
+    pub referral: bool,
 }
@@ -0,0 +1,579 @@
+use crate::Job;
+use crate::JobStore;
+use crate::Location;
+use crate::WorkArrangement;
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Known countries, regions and cities, see the file for its format.
+const GAZETTEER: &str = include_str!("gazetteer.txt");
+
+/// Phrases that give away the work arrangement, by arrangement. When a
+/// location mentions more than one, the first listed here wins, so
+/// "Hybrid remote" is hybrid.
+const ARRANGEMENT_PHRASES: &[(WorkArrangement, &[&str])] = &[
+    (WorkArrangement::Hybrid, &["hybrid", "flexible"]),
+    (
+        WorkArrangement::Remote,
+        &[
+            "remote",
+            "work from home",
+            "wfh",
+            "telecommute",
+            "anywhere",
+            "distributed",
+        ],
+    ),
+    (
+        WorkArrangement::Onsite,
+        &["on-site", "onsite", "on site", "in-office", "in office"],
+    ),
+];
+
+/// Words around a place name that aren't part of it, as in "Greater Boston
+/// Area" or "US only".
+const LEADING_FILLER: &[&str] = &["greater", "in", "the", "from", "based", "within"];
+const TRAILING_FILLER: &[&str] = &[
+    "area",
+    "metro",
+    "metropolitan",
+    "only",
+    "based",
+    "first",
+    "friendly",
+    "ok",
+    "preferred",
+    "office",
+    "hq",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PlaceKind {
+    Country,
+    Region,
+    City,
+}
+
+#[derive(Debug)]
+struct Place {
+    kind: PlaceKind,
+    name: &'static str,
+    /// Region a city is in. Unset for regions and countries.
+    region: Option<&'static str>,
+    /// Country a city or region is in. Unset for countries.
+    country: Option<&'static str>,
+}
+
+impl Place {
+    /// The country this place is or is in.
+    fn country(&self) -> &'static str {
+        self.country.unwrap_or(self.name)
+    }
+}
+
+#[derive(Debug)]
+struct Gazetteer {
+    places: Vec<Place>,
+    /// Indexes into `places` of every place with a name or alias, by
+    /// [`key`] of the name, in file order.
+    by_name: HashMap<String, Vec<usize>>,
+}
+
+fn gazetteer() -> &'static Gazetteer {
+    static GAZETTEER_INDEX: OnceLock<Gazetteer> = OnceLock::new();
+    GAZETTEER_INDEX.get_or_init(|| {
+        let mut gazetteer = Gazetteer {
+            places: Vec::new(),
+            by_name: HashMap::new(),
+        };
+        let lines = GAZETTEER
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        for line in lines {
+            let fields: Vec<&'static str> = line.split('|').collect();
+            let [kind, name, aliases, region, country] = fields[..] else {
+                panic!("Malformed gazetteer line: {line}");
+            };
+            let kind = match kind {
+                "country" => PlaceKind::Country,
+                "region" => PlaceKind::Region,
+                "city" => PlaceKind::City,
+                _ => panic!("Unknown place kind in gazetteer line: {line}"),
+            };
+            let index = gazetteer.places.len();
+            gazetteer.places.push(Place {
+                kind,
+                name,
+                region: (!region.is_empty()).then_some(region),
+                country: (!country.is_empty()).then_some(country),
+            });
+            for alias in std::iter::once(name).chain(aliases.split(',')) {
+                if !alias.is_empty() {
+                    gazetteer.by_name.entry(key(alias)).or_default().push(index);
+                }
+            }
+        }
+        gazetteer
+    })
+}
+
+impl Gazetteer {
+    /// Places of `kind` named `text`, most likely first.
+    fn find(&self, text: &str, kind: PlaceKind) -> Vec<&Place> {
+        self.by_name
+            .get(&key(text))
+            .into_iter()
+            .flatten()
+            .map(|&index| &self.places[index])
+            .filter(|place| place.kind == kind)
+            .collect()
+    }
+
+    fn is_known(&self, text: &str) -> bool {
+        self.by_name.contains_key(&key(text))
+    }
+}
+
+/// `text` as looked up in the gazetteer: lowercase, with runs of whitespace
+/// squeezed and no trailing period.
+fn key(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// Cuts every arrangement phrase out of `text`, leaving a comma in its
+/// place, and returns the arrangement they name.
+fn take_arrangement(text: &str) -> (Option<WorkArrangement>, String) {
+    // ASCII lowercasing keeps byte offsets the same as in `text`
+    let lower = text.to_ascii_lowercase();
+    let is_boundary = |c: Option<char>| !c.is_some_and(char::is_alphanumeric);
+    let mut found = None;
+    let mut cuts: Vec<(usize, usize)> = Vec::new();
+    for (arrangement, phrases) in ARRANGEMENT_PHRASES {
+        for phrase in *phrases {
+            for (start, _) in lower.match_indices(phrase) {
+                let end = start + phrase.len();
+                if is_boundary(lower[..start].chars().next_back())
+                    && is_boundary(lower[end..].chars().next())
+                    && !cuts.iter().any(|&(s, e)| start < e && s < end)
+                {
+                    found.get_or_insert(*arrangement);
+                    cuts.push((start, end));
+                }
+            }
+        }
+    }
+    cuts.sort();
+    let mut rest = String::new();
+    let mut from = 0;
+    for (start, end) in cuts {
+        rest.push_str(&text[from..start]);
+        rest.push(',');
+        from = end;
+    }
+    rest.push_str(&text[from..]);
+    (found, rest)
+}
+
+/// The comma-, slash- or parenthesis-separated pieces of `text`, also split
+/// at "or" and "and", without filler words or words with digits in them
+/// like postal codes. A piece that isn't a known place but ends in one, as
+/// in "Austin TX", is split in two.
+fn parts(text: &str) -> Vec<String> {
+    let gazetteer = gazetteer();
+    let text = text.replace(" - ", ",");
+    let pieces = text.split(|c| {
+        matches!(
+            c,
+            ',' | ';' | '/' | '(' | ')' | '[' | ']' | '|' | '·' | '•' | '–' | '—'
+        )
+    });
+    let mut parts = Vec::new();
+    for piece in pieces {
+        let words: Vec<&str> = piece
+            .split_whitespace()
+            .map(|word| word.trim_matches('-'))
+            .filter(|word| !word.is_empty() && !word.chars().any(|c| c.is_ascii_digit()))
+            .collect();
+        for words in words.split(|word| ["or", "and", "&"].contains(&key(word).as_str())) {
+            let mut words = words.to_vec();
+            let is_known = |words: &[&str]| gazetteer.is_known(&words.join(" "));
+            while words.len() > 1
+                && !is_known(&words)
+                && LEADING_FILLER.contains(&key(words[0]).as_str())
+            {
+                words.remove(0);
+            }
+            while words.len() > 1
+                && !is_known(&words)
+                && TRAILING_FILLER.contains(&key(words[words.len() - 1]).as_str())
+            {
+                words.pop();
+            }
+            if words.is_empty() {
+                continue;
+            }
+            let split = (1..words.len()).rev().find(|&at| {
+                let tail = words[at..].join(" ");
+                !gazetteer.find(&tail, PlaceKind::Region).is_empty()
+                    || !gazetteer.find(&tail, PlaceKind::Country).is_empty()
+            });
+            match split {
+                Some(at) if !is_known(&words) => {
+                    parts.push(words[..at].join(" "));
+                    parts.push(words[at..].join(" "));
+                }
+                _ => parts.push(words.join(" ")),
+            }
+        }
+    }
+    parts
+}
+
+/// What one piece of a location was taken to be.
+enum Slot<'a> {
+    /// As written, and the places of that name.
+    Known(String, Vec<&'a Place>),
+    Unknown(String),
+}
+
+impl Location {
+    /// Reads free text like "NYC", "Austin, TX" or "Remote (US)" using the
+    /// built-in gazetteer. Places it doesn't know are kept as written, and
+    /// anything it can't make sense of is left out.
+    pub fn parse(text: &str) -> Location {
+        let gazetteer = gazetteer();
+        let (arrangement, rest) = take_arrangement(text);
+        let mut city: Option<Slot> = None;
+        let mut region: Option<Slot> = None;
+        let mut country: Option<Slot> = None;
+        for part in parts(&rest) {
+            // The first piece is most likely the city, later ones where it is
+            let order = if city.is_none() && region.is_none() && country.is_none() {
+                [PlaceKind::City, PlaceKind::Region, PlaceKind::Country]
+            } else {
+                [PlaceKind::Region, PlaceKind::Country, PlaceKind::City]
+            };
+            let mut matched = false;
+            for kind in order {
+                let slot = match kind {
+                    PlaceKind::City => &mut city,
+                    PlaceKind::Region => &mut region,
+                    PlaceKind::Country => &mut country,
+                };
+                let places = gazetteer.find(&part, kind);
+                if slot.is_none() && !places.is_empty() {
+                    *slot = Some(Slot::Known(part.clone(), places));
+                    matched = true;
+                    break;
+                }
+            }
+            // A second city or the like, as in "Seattle or Bellevue"
+            if matched || gazetteer.is_known(&part) {
+                continue;
+            }
+            // Remote jobs rarely name a city, so an unknown first piece is
+            // more likely something like "Europe" or "EMEA"
+            if city.is_none() && region.is_none() && arrangement != Some(WorkArrangement::Remote) {
+                city = Some(Slot::Unknown(part));
+            } else if city.is_some() && region.is_none() && country.is_none() {
+                region = Some(Slot::Unknown(part));
+            }
+        }
+
+        let mut location = Location {
+            arrangement,
+            ..Default::default()
+        };
+        let city_places: &[&Place] = match &city {
+            Some(Slot::Known(_, places)) => places,
+            _ => &[],
+        };
+        match country {
+            Some(Slot::Known(_, places)) => location.country = Some(places[0].name.to_string()),
+            Some(Slot::Unknown(text)) => location.country = Some(text),
+            None => {}
+        }
+        match region {
+            Some(Slot::Known(text, places)) => {
+                let in_country = |place: &&&Place| {
+                    location
+                        .country
+                        .as_ref()
+                        .is_none_or(|country| place.country() == country)
+                };
+                let with_city = places.iter().filter(in_country).find(|place| {
+                    city_places.iter().any(|city| {
+                        city.region == Some(place.name) && city.country == place.country
+                    })
+                });
+                match with_city.or_else(|| places.iter().find(in_country)) {
+                    Some(place) => {
+                        location.region = Some(place.name.to_string());
+                        location.country = Some(place.country().to_string());
+                    }
+                    None => location.region = Some(text),
+                }
+            }
+            Some(Slot::Unknown(text)) => location.region = Some(text),
+            None => {}
+        }
+        match city {
+            Some(Slot::Known(text, places)) => {
+                let found = places.iter().find(|place| {
+                    location
+                        .region
+                        .as_ref()
+                        .is_none_or(|region| place.region == Some(region.as_str()))
+                        && location
+                            .country
+                            .as_ref()
+                            .is_none_or(|country| place.country() == country)
+                });
+                match found {
+                    Some(place) => {
+                        location.city = Some(place.name.to_string());
+                        location.region = place.region.map(str::to_string);
+                        location.country = Some(place.country().to_string());
+                    }
+                    // Not the city of that name the gazetteer knows, e.g.
+                    // Paris, TX
+                    None => location.city = Some(text),
+                }
+            }
+            Some(Slot::Unknown(text)) => location.city = Some(text),
+            None => {}
+        }
+        location
+    }
+
+    /// Whether nothing at all is known about the location.
+    pub fn is_empty(&self) -> bool {
+        self == &Location::default()
+    }
+
+    /// The region and country, or just the country, e.g. "Texas, United
+    /// States". This is what applications are grouped by in statistics.
+    pub fn area(&self) -> Option<String> {
+        match (&self.region, &self.country) {
+            (Some(region), Some(country)) => Some(format!("{region}, {country}")),
+            (None, Some(country)) => Some(country.clone()),
+            (Some(region), None) => Some(region.clone()),
+            (None, None) => None,
+        }
+    }
+
+    /// Whether the location is in `area`, which can be a region or
+    /// country by name or abbreviation, e.g. "TX" or "Germany", or a value
+    /// returned by [`Self::area`].
+    pub fn in_area(&self, area: &str) -> bool {
+        let area = area.trim();
+        if self
+            .area()
+            .is_some_and(|own| own.eq_ignore_ascii_case(area))
+        {
+            return true;
+        }
+        let gazetteer = gazetteer();
+        let same = |value: &Option<String>, name: &str| {
+            value
+                .as_ref()
+                .is_some_and(|value| value.eq_ignore_ascii_case(name))
+        };
+        same(&self.region, area)
+            || same(&self.country, area)
+            || gazetteer
+                .find(area, PlaceKind::Region)
+                .iter()
+                .any(|place| same(&self.region, place.name) && same(&self.country, place.country()))
+            || gazetteer
+                .find(area, PlaceKind::Country)
+                .iter()
+                .any(|place| same(&self.country, place.name))
+    }
+
+    /// Trims every field and drops the ones left blank.
+    fn trimmed(self) -> Location {
+        let trim = |field: Option<String>| {
+            field
+                .map(|text| text.trim().to_string())
+                .filter(|text| !text.is_empty())
+        };
+        Location {
+            city: trim(self.city),
+            region: trim(self.region),
+            country: trim(self.country),
+            arrangement: self.arrangement,
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // "Berlin, Germany" rather than "Berlin, Berlin, Germany"
+        let region = self
+            .region
+            .as_ref()
+            .filter(|region| Some(*region) != self.city.as_ref());
+        let place = [self.city.as_ref(), region, self.country.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        match (place.is_empty(), self.arrangement) {
+            (true, Some(arrangement)) => write!(f, "{arrangement}"),
+            (false, Some(arrangement)) => write!(f, "{place} ({arrangement})"),
+            (_, None) => write!(f, "{place}"),
+        }
+    }
+}
+
+impl Job {
+    /// Whether the application is in `area`, see [`Location::in_area`].
+    pub fn in_area(&self, area: &str) -> bool {
+        self.location.in_area(area)
+    }
+}
+
+impl JobStore {
+    /// Changes where job `id` is to `text` and parses it again. Blank text
+    /// clears the location. `correction`, if given, is kept instead of
+    /// what the text was read as, like [`Self::set_location`] but in the
+    /// same undo step.
+    pub fn update_location(
+        &mut self,
+        id: u32,
+        text: &str,
+        correction: Option<Location>,
+    ) -> Result<()> {
+        self.find_job(id)?;
+        let text = text.trim();
+        let location = correction.map_or_else(|| Location::parse(text), |l| l.trimmed());
+        self.modify_job(id, "Change location", |job| {
+            job.role_location = (!text.is_empty()).then(|| text.to_string());
+            job.location = location;
+        })
+    }
+
+    /// Overrides how the location of job `id` was parsed, e.g. to fix a
+    /// place the gazetteer doesn't know. The free text is left alone.
+    pub fn set_location(&mut self, id: u32, location: Location) -> Result<()> {
+        self.find_job(id)?;
+        let location = location.trimmed();
+        self.modify_job(id, "Change location", |job| job.location = location)
+    }
+
+    /// Every area applications outside the trash are in, see
+    /// [`Location::area`], with how many are in it, by name.
+    pub fn areas(&self) -> Vec<(String, usize)> {
+        let mut areas: BTreeMap<String, usize> = BTreeMap::new();
+        for area in self.active_jobs().filter_map(|job| job.location.area()) {
+            *areas.entry(area).or_default() += 1;
+        }
+        areas.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place(city: Option<&str>, region: Option<&str>, country: Option<&str>) -> Location {
+        Location {
+            city: city.map(str::to_string),
+            region: region.map(str::to_string),
+            country: country.map(str::to_string),
+            arrangement: None,
+        }
+    }
+
+    #[test]
+    fn reads_city_region_and_country() {
+        assert_eq!(
+            Location::parse("Austin, TX"),
+            place(Some("Austin"), Some("Texas"), Some("United States"))
+        );
+        assert_eq!(
+            Location::parse("NYC"),
+            place(Some("New York"), Some("New York"), Some("United States"))
+        );
+        assert_eq!(
+            Location::parse("Berlin, Germany"),
+            place(Some("Berlin"), Some("Berlin"), Some("Germany"))
+        );
+    }
+
+    #[test]
+    fn region_picks_between_cities_of_the_same_name() {
+        assert_eq!(
+            Location::parse("Portland, ME"),
+            place(Some("Portland"), Some("Maine"), Some("United States"))
+        );
+        assert_eq!(
+            Location::parse("Portland"),
+            place(Some("Portland"), Some("Oregon"), Some("United States"))
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_places_as_written() {
+        // Not the Paris the gazetteer knows
+        assert_eq!(
+            Location::parse("Paris, TX"),
+            place(Some("Paris"), Some("Texas"), Some("United States"))
+        );
+        assert_eq!(
+            Location::parse("Springfield, Freedonia"),
+            place(Some("Springfield"), Some("Freedonia"), None)
+        );
+    }
+
+    #[test]
+    fn reads_work_arrangement() {
+        let remote = Location::parse("Remote (US)");
+        assert_eq!(remote.arrangement, Some(WorkArrangement::Remote));
+        assert_eq!(remote.country.as_deref(), Some("United States"));
+        assert_eq!(remote.city, None);
+
+        let hybrid = Location::parse("Hybrid remote - Greater Boston Area");
+        assert_eq!(hybrid.arrangement, Some(WorkArrangement::Hybrid));
+
+        assert_eq!(Location::parse("").arrangement, None);
+        assert!(Location::parse("").is_empty());
+    }
+
+    #[test]
+    fn matches_areas_by_name_or_abbreviation() {
+        let austin = Location::parse("Austin, TX");
+        assert_eq!(austin.area().as_deref(), Some("Texas, United States"));
+        assert!(austin.in_area("TX"));
+        assert!(austin.in_area("texas, united states"));
+        assert!(austin.in_area("USA"));
+        assert!(!austin.in_area("Oregon"));
+    }
+
+    #[test]
+    fn text_and_correction_are_one_undo_step() {
+        let mut store = crate::test_support::store_with_job();
+        let correction = Location {
+            city: Some(" Springfield ".to_string()),
+            ..Location::parse("Springfield")
+        };
+        store
+            .update_location(1, "Springfield", Some(correction))
+            .unwrap();
+        assert_eq!(store.jobs[0].role_location.as_deref(), Some("Springfield"));
+        assert_eq!(store.jobs[0].location.city.as_deref(), Some("Springfield"));
+
+        assert_eq!(store.undo().unwrap().as_deref(), Some("Change location"));
+        assert_eq!(store.jobs[0].role_location.as_deref(), Some("Remote"));
+        assert_eq!(store.jobs[0].location, Location::parse("Remote"));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::Contact;
+use crate::Job;
+use crate::MemoryStorage;
+use crate::Source;
+use crate::Stage;
+use crate::Storage;
+use anyhow::Result;
+
+impl MemoryStorage {
+    pub fn new(jobs: Vec<Job>) -> Self {
+        Self {
+            jobs,
+            stages: Vec::new(),
+            contacts: Vec::new(),
+            sources: Vec::new(),
+        }
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn load(&mut self) -> Result<Vec<Job>> {
+        Ok(self.jobs.clone())
+    }
+
+    fn save(&mut self, jobs: &[Job]) -> Result<()> {
+        self.jobs = jobs.to_vec();
+        Ok(())
+    }
+
+    fn insert(&mut self, job: &Job) -> Result<()> {
+        self.jobs.push(job.clone());
+        Ok(())
+    }
+
+    fn update(&mut self, job: &Job) -> Result<()> {
+        if let Some(existing) = self.jobs.iter_mut().find(|j| j.id == job.id) {
+            *existing = job.clone();
+        }
+        Ok(())
+    }
+
+    fn delete(&mut self, id: u32) -> Result<()> {
+        self.jobs.retain(|j| j.id != id);
+        Ok(())
+    }
+
+    fn load_stages(&mut self) -> Result<Vec<Stage>> {
+        if self.stages.is_empty() {
+            return Ok(Stage::defaults());
+        }
+        Ok(self.stages.clone())
+    }
+
+    fn save_stages(&mut self, stages: &[Stage]) -> Result<()> {
+        self.stages = stages.to_vec();
+        Ok(())
+    }
+
+    fn load_contacts(&mut self) -> Result<Vec<Contact>> {
+        Ok(self.contacts.clone())
+    }
+
+    fn save_contacts(&mut self, contacts: &[Contact]) -> Result<()> {
+        self.contacts = contacts.to_vec();
+        Ok(())
+    }
+
+    fn load_sources(&mut self) -> Result<Vec<Source>> {
+        if self.sources.is_empty() {
+            return Ok(Source::defaults());
+        }
+        Ok(self.sources.clone())
+    }
+
+    fn save_sources(&mut self, sources: &[Source]) -> Result<()> {
+        self.sources = sources.to_vec();
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use crate::Job;
+use crate::JobStore;
+use crate::Note;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+
+impl Job {
+    /// Appends a note written at `at` and returns its ID.
+    pub fn add_note(&mut self, text: String, at: DateTime<Utc>) -> u32 {
+        let id = self.notes.iter().map(|note| note.id).max().unwrap_or(0) + 1;
+        self.notes.push(Note {
+            id,
+            at,
+            text,
+            edited_at: None,
+        });
+        id
+    }
+}
+
+impl JobStore {
+    /// Adds a note to job `id`.
+    pub fn add_note(&mut self, id: u32, text: String) -> Result<Vec<Job>> {
+        self.find_job(id)?;
+        self.modify_job(id, "Add note", |job| {
+            job.add_note(text, Utc::now());
+        })?;
+        Ok(self.jobs.clone())
+    }
+
+    /// Replaces the text of note `note_id` on job `id`.
+    pub fn edit_note(&mut self, id: u32, note_id: u32, text: String) -> Result<Vec<Job>> {
+        self.find_note(id, note_id)?;
+        self.modify_job(id, "Edit note", |job| {
+            if let Some(note) = job.notes.iter_mut().find(|note| note.id == note_id) {
+                if note.text != text {
+                    note.text = text;
+                    note.edited_at = Some(Utc::now());
+                }
+            }
+        })?;
+        Ok(self.jobs.clone())
+    }
+
+    /// Removes note `note_id` from job `id`.
+    pub fn delete_note(&mut self, id: u32, note_id: u32) -> Result<Vec<Job>> {
+        self.find_note(id, note_id)?;
+        self.modify_job(id, "Delete note", |job| {
+            job.notes.retain(|note| note.id != note_id)
+        })?;
+        Ok(self.jobs.clone())
+    }
+
+    pub(crate) fn find_job(&self, id: u32) -> Result<&Job> {
+        self.jobs
+            .iter()
+            .find(|job| job.id == id)
+            .ok_or_else(|| anyhow!("No job application with ID {id}"))
+    }
+
+    fn find_note(&self, id: u32, note_id: u32) -> Result<&Note> {
+        self.find_job(id)?
+            .notes
+            .iter()
+            .find(|note| note.id == note_id)
+            .ok_or_else(|| anyhow!("Job {id} has no note {note_id}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::store_with_job;
+
+    #[test]
+    fn notes_are_added_edited_and_deleted() {
+        let mut store = store_with_job();
+        store.add_note(1, "Recruiter called".to_string()).unwrap();
+        store.add_note(1, "Sent **portfolio**".to_string()).unwrap();
+        let ids: Vec<u32> = store.jobs[0].notes.iter().map(|note| note.id).collect();
+        assert_eq!(ids, [1, 2]);
+
+        store
+            .edit_note(1, 1, "Recruiter called back".to_string())
+            .unwrap();
+        let note = &store.jobs[0].notes[0];
+        assert_eq!(note.text, "Recruiter called back");
+        assert!(note.edited_at.is_some());
+        store
+            .edit_note(1, 2, "Sent **portfolio**".to_string())
+            .unwrap();
+        assert_eq!(store.jobs[0].notes[1].edited_at, None);
+
+        store.delete_note(1, 1).unwrap();
+        let ids: Vec<u32> = store.jobs[0].notes.iter().map(|note| note.id).collect();
+        assert_eq!(ids, [2]);
+        store.add_note(1, "Onsite booked".to_string()).unwrap();
+        assert_eq!(store.jobs[0].notes[1].id, 3);
+
+        assert!(store.add_note(2, "Nope".to_string()).is_err());
+        assert!(store.edit_note(1, 1, "Gone".to_string()).is_err());
+        assert!(store.delete_note(1, 9).is_err());
+        assert_eq!(store.undo().unwrap().as_deref(), Some("Add note"));
+        assert_eq!(store.undo().unwrap().as_deref(), Some("Delete note"));
+        assert_eq!(store.jobs[0].notes.len(), 2);
+    }
+
+    #[test]
+    fn search_looks_at_note_text() {
+        let mut store = store_with_job();
+        store
+            .add_note(1, "Asked about Kubernetes".to_string())
+            .unwrap();
+        let job = &store.jobs[0];
+        assert!(job.matches_search("kubernetes"));
+        assert!(!job.matches_search("terraform"));
+    }
+}
@@ -0,0 +1,187 @@
+use crate::FlowPath;
+use crate::FunnelStage;
+use crate::Job;
+use crate::JobStatus;
+use crate::JobStore;
+use crate::PipelineFlow;
+use crate::StageOutcome;
+
+impl JobStore {
+    /// Position in the pipeline of the furthest stage without a failure
+    /// outcome `job` has been in, going by its history. The first stage if
+    /// it's never been in one, e.g. when it went straight to a rejection.
+    fn furthest_position(&self, job: &Job) -> usize {
+        job.history
+            .iter()
+            .map(|change| &change.to)
+            .chain([&job.status])
+            .filter_map(|status| {
+                self.stages.iter().position(|stage| {
+                    &stage.name == status && stage.outcome != Some(StageOutcome::Failure)
+                })
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The furthest stage without a failure outcome `job` has been in, so
+    /// a rejection after an interview still counts as reaching the
+    /// interview.
+    pub fn furthest_stage(&self, job: &Job) -> JobStatus {
+        self.stages
+            .get(self.furthest_position(job))
+            .map_or_else(|| self.initial_status(), |stage| stage.name.clone())
+    }
+
+    /// How far applications got and where they ended up, by source.
+    pub fn pipeline_flow(&self) -> PipelineFlow {
+        let reached: Vec<(&Job, usize)> = self
+            .active_jobs()
+            .map(|job| (job, self.furthest_position(job)))
+            .collect();
+
+        let funnel = self
+            .stages
+            .iter()
+            .enumerate()
+            .filter(|(_, stage)| stage.outcome != Some(StageOutcome::Failure))
+            .map(|(position, stage)| FunnelStage {
+                stage: stage.name.clone(),
+                job_ids: reached
+                    .iter()
+                    .filter(|(_, furthest)| *furthest >= position)
+                    .map(|(job, _)| job.id)
+                    .collect(),
+            })
+            .collect();
+
+        let mut paths: Vec<FlowPath> = Vec::new();
+        for (job, _) in &reached {
+            let reached = self.furthest_stage(job);
+            let outcome = self
+                .stage(&job.status)
+                .filter(|stage| stage.terminal)
+                .map(|stage| stage.name.clone());
+            let existing = paths.iter_mut().find(|path| {
+                path.source == job.source && path.reached == reached && path.outcome == outcome
+            });
+            match existing {
+                Some(path) => path.job_ids.push(job.id),
+                None => paths.push(FlowPath {
+                    source: job.source.clone(),
+                    reached,
+                    outcome,
+                    job_ids: vec![job.id],
+                }),
+            }
+        }
+        let source_position = |path: &FlowPath| {
+            path.source.as_ref().map_or(self.sources.len(), |name| {
+                self.sources
+                    .iter()
+                    .position(|source| &source.name == name)
+                    .unwrap_or(self.sources.len())
+            })
+        };
+        let stage_position = |status: Option<&JobStatus>| {
+            status.map_or(self.stages.len(), |status| {
+                self.stages
+                    .iter()
+                    .position(|stage| &stage.name == status)
+                    .unwrap_or(self.stages.len())
+            })
+        };
+        paths.sort_by_key(|path| {
+            (
+                source_position(path),
+                stage_position(Some(&path.reached)),
+                stage_position(path.outcome.as_ref()),
+            )
+        });
+        PipelineFlow { funnel, paths }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JobSource;
+
+    /// A store with an application for each of `paths`, moved through the
+    /// given stages in order.
+    fn store_with(paths: &[(&str, &[JobStatus])]) -> JobStore {
+        let mut store = JobStore::default();
+        for (source, path) in paths {
+            store
+                .add_job(
+                    "Acme".into(),
+                    "Dev".into(),
+                    String::new(),
+                    source.to_string(),
+                )
+                .unwrap();
+            let id = store.next_id() - 1;
+            for status in *path {
+                store.update_status(id, status.clone()).unwrap();
+            }
+        }
+        store
+    }
+
+    #[test]
+    fn funnel_counts_the_furthest_stage_reached() {
+        let store = store_with(&[
+            ("LinkedIn", &[]),
+            ("LinkedIn", &[JobStatus::INTERVIEW, JobStatus::REJECTED]),
+            ("Referral", &[JobStatus::INTERVIEW, JobStatus::OFFER]),
+            ("", &[JobStatus::GHOSTED]),
+        ]);
+        let rejected = &store.jobs[1];
+        assert_eq!(store.furthest_stage(rejected), JobStatus::INTERVIEW);
+        assert_eq!(store.furthest_stage(&store.jobs[3]), JobStatus::APPLIED);
+
+        let flow = store.pipeline_flow();
+        let funnel: Vec<(JobStatus, Vec<u32>)> = flow
+            .funnel
+            .into_iter()
+            .map(|stage| (stage.stage, stage.job_ids))
+            .collect();
+        assert_eq!(
+            funnel,
+            [
+                (JobStatus::APPLIED, vec![1, 2, 3, 4]),
+                (JobStatus::INTERVIEW, vec![2, 3]),
+                (JobStatus::OFFER, vec![3]),
+            ]
+        );
+    }
+
+    #[test]
+    fn paths_group_by_source_stage_and_outcome() {
+        let store = store_with(&[
+            ("", &[JobStatus::REJECTED]),
+            ("LinkedIn", &[JobStatus::INTERVIEW, JobStatus::REJECTED]),
+            ("LinkedIn", &[]),
+            ("LinkedIn", &[JobStatus::INTERVIEW, JobStatus::REJECTED]),
+        ]);
+        let paths: Vec<_> = store
+            .pipeline_flow()
+            .paths
+            .into_iter()
+            .map(|path| (path.source, path.reached, path.outcome, path.job_ids))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                (Some(JobSource::LINKEDIN), JobStatus::APPLIED, None, vec![3]),
+                (
+                    Some(JobSource::LINKEDIN),
+                    JobStatus::INTERVIEW,
+                    Some(JobStatus::REJECTED),
+                    vec![2, 4]
+                ),
+                (None, JobStatus::APPLIED, Some(JobStatus::REJECTED), vec![1]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,468 @@
+use crate::html::{decode_entities, elements, tags, to_text, Tag};
+use crate::job_store::parse_posting_url;
+use crate::normalize_tag;
+use crate::Job;
+use crate::JobChange;
+use crate::JobSource;
+use crate::JobStore;
+use crate::Location;
+use crate::PayPeriod;
+use crate::SalaryRange;
+use anyhow::{bail, Result};
+use chrono::Utc;
+use serde_json::Value;
+use std::io::Read;
+
+/// Job boards recognised from a page's URL or site name.
+const BOARDS: [(&str, JobSource); 6] = [
+    ("linkedin", JobSource::LINKEDIN),
+    ("indeed", JobSource::INDEED),
+    ("glassdoor", JobSource::GLASSDOOR),
+    ("ziprecruiter", JobSource::ZIPRECRUITER),
+    ("monster", JobSource::MONSTER),
+    ("talent.com", JobSource::TALENT),
+];
+
+impl Job {
+    /// A draft application read from a saved job posting page, for the user
+    /// to check and complete before adding it with [`JobStore::add_draft`].
+    /// The schema.org `JobPosting` data most job boards embed is used where
+    /// present; otherwise the page title and `<meta>` tags are read the way
+    /// the common boards lay them out. Fails if neither names a role or
+    /// company.
+    pub fn from_posting_html<R: Read>(mut reader: R) -> Result<Job> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        // Saved pages aren't always UTF-8; a stray character beats failing
+        let html = String::from_utf8_lossy(&bytes);
+        let tags = tags(&html);
+
+        let mut job = Job::default();
+        if let Some(posting) = elements(&html, &tags, "script")
+            .into_iter()
+            .filter(|(tag, _)| tag.attr("type") == Some("application/ld+json"))
+            .filter_map(|(_, json)| serde_json::from_str::<Value>(json.trim()).ok())
+            .find_map(|value| find_job_posting(&value).cloned())
+        {
+            read_job_posting(&mut job, &posting);
+        }
+        read_page(&mut job, &html, &tags);
+
+        if job.company.is_empty() && job.role.is_empty() {
+            bail!("Couldn't find a job posting in the page: it has no JobPosting data and its title doesn't name a role");
+        }
+        Ok(job)
+    }
+}
+
+impl JobStore {
+    /// Adds `draft`, e.g. from [`Job::from_posting_html`], as a new
+    /// application in the first stage. Its ID, status, history and date
+    /// applied are set here, and its referrer, if any, has to be a contact
+    /// and its source a referral source. Its tags and notes are kept.
+    /// Returns the added application.
+    pub fn add_draft(&mut self, draft: Job) -> Result<Job> {
+        if draft.company.trim().is_empty() || draft.role.trim().is_empty() {
+            bail!("An application needs a company and a role");
+        }
+        let source = draft
+            .source
+            .map(|source| self.parse_source(source.as_str()))
+            .transpose()?;
+        self.check_referrer(source.as_ref(), draft.referrer)?;
+        let posting_url = draft
+            .posting_url
+            .as_deref()
+            .map(parse_posting_url)
+            .transpose()?
+            .flatten();
+        let mut job = Job {
+            location: Location::parse(draft.role_location.as_deref().unwrap_or_default()),
+            role_location: draft.role_location,
+            source,
+            referrer: draft.referrer,
+            salary_range: draft.salary_range,
+            posting_url,
+            description: draft.description,
+            tags: draft
+                .tags
+                .iter()
+                .filter_map(|tag| normalize_tag(tag))
+                .collect(),
+            ..Job::new(
+                self.next_id(),
+                draft.company.trim().to_string(),
+                draft.role.trim().to_string(),
+                self.initial_status(),
+                Utc::now(),
+            )
+        };
+        for note in draft.notes {
+            job.add_note(note.text, note.at);
+        }
+        self.jobs.push(job.clone());
+        self.record(
+            "Add application",
+            vec![JobChange {
+                before: None,
+                after: Some(job.clone()),
+            }],
+        )?;
+        Ok(job)
+    }
+}
+
+/// The first object whose `@type` is `JobPosting`, looking through arrays
+/// and `@graph` lists.
+fn find_job_posting(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(values) => values.iter().find_map(find_job_posting),
+        Value::Object(object) => {
+            let is_posting = match object.get("@type") {
+                Some(Value::String(kind)) => kind == "JobPosting",
+                Some(Value::Array(kinds)) => kinds.iter().any(|kind| kind == "JobPosting"),
+                _ => false,
+            };
+            if is_posting {
+                Some(value)
+            } else {
+                object.get("@graph").and_then(find_job_posting)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// A string or number as trimmed text, `None` if blank.
+fn text(value: Option<&Value>) -> Option<String> {
+    let text = match value? {
+        Value::String(text) => decode_entities(text).trim().to_string(),
+        Value::Number(number) => number.to_string(),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+fn number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.replace(',', "").trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// A string, or the `name` of an object such as an `Organization`.
+fn name(value: Option<&Value>) -> Option<String> {
+    text(value).or_else(|| text(value?.get("name")))
+}
+
+/// Whether `url` can be kept as a posting URL; pages sometimes give
+/// relative links.
+fn is_web_address(url: &str) -> bool {
+    parse_posting_url(url).is_ok()
+}
+
+fn read_job_posting(job: &mut Job, posting: &Value) {
+    job.role = text(posting.get("title")).unwrap_or_default();
+    job.company = name(posting.get("hiringOrganization")).unwrap_or_default();
+    job.posting_url = text(posting.get("url")).filter(|url| is_web_address(url));
+    job.description = text(posting.get("description"))
+        .map(|html| to_text(&html))
+        .filter(|description| !description.is_empty());
+
+    let places: Vec<&Value> = match posting.get("jobLocation") {
+        Some(Value::Array(places)) => places.iter().collect(),
+        Some(place) => vec![place],
+        None => Vec::new(),
+    };
+    let mut locations: Vec<String> = places
+        .into_iter()
+        .filter_map(|place| {
+            let address = place.get("address")?;
+            text(Some(address)).or_else(|| {
+                let parts: Vec<String> = ["addressLocality", "addressRegion", "addressCountry"]
+                    .into_iter()
+                    .filter_map(|field| name(address.get(field)))
+                    .collect();
+                (!parts.is_empty()).then(|| parts.join(", "))
+            })
+        })
+        .collect();
+    if text(posting.get("jobLocationType")).as_deref() == Some("TELECOMMUTE") {
+        locations.push("Remote".to_string());
+    }
+    job.role_location = (!locations.is_empty()).then(|| locations.join("; "));
+
+    job.salary_range = posting.get("baseSalary").and_then(read_salary);
+}
+
+/// A schema.org `MonetaryAmount`, whose `value` is a number or a
+/// `QuantitativeValue` with a range and a unit. Weekly and daily pay are
+/// turned into yearly pay.
+fn read_salary(salary: &Value) -> Option<SalaryRange> {
+    let value = salary.get("value")?;
+    let (min, max, unit) = match number(Some(value)) {
+        Some(amount) => (amount, amount, text(salary.get("unitText"))),
+        None => {
+            let exact = number(value.get("value"));
+            let min = number(value.get("minValue")).or(exact)?;
+            let max = number(value.get("maxValue")).or(exact).unwrap_or(min);
+            let unit = text(value.get("unitText")).or_else(|| text(salary.get("unitText")));
+            (min, max, unit)
+        }
+    };
+    let (period, factor) = match unit.unwrap_or_default().to_uppercase().as_str() {
+        "HOUR" => (PayPeriod::Hour, 1.0),
+        "DAY" => (PayPeriod::Year, 260.0),
+        "WEEK" => (PayPeriod::Year, 52.0),
+        "MONTH" => (PayPeriod::Month, 1.0),
+        _ => (PayPeriod::Year, 1.0),
+    };
+    Some(SalaryRange {
+        min: min * factor,
+        max: max * factor,
+        currency: text(salary.get("currency"))
+            .or_else(|| text(value.get("currency")))
+            .unwrap_or_else(|| "USD".to_string())
+            .to_uppercase(),
+        period,
+    })
+}
+
+/// Fills whatever the `JobPosting` data didn't from the page title and
+/// `<meta>` tags.
+fn read_page(job: &mut Job, html: &str, tags: &[Tag]) {
+    let meta = |key: &str| {
+        tags.iter()
+            .filter(|tag| tag.name == "meta")
+            .find(|tag| tag.attr("property").or(tag.attr("name")) == Some(key))
+            .and_then(|tag| tag.attr("content"))
+            .map(|content| content.trim().to_string())
+            .filter(|content| !content.is_empty())
+    };
+    let canonical = tags
+        .iter()
+        .find(|tag| tag.name == "link" && tag.attr("rel") == Some("canonical"))
+        .and_then(|tag| tag.attr("href"))
+        .map(str::to_string);
+    if job.posting_url.is_none() {
+        job.posting_url = meta("og:url")
+            .into_iter()
+            .chain(canonical)
+            .find(|url| is_web_address(url));
+    }
+    let site = meta("og:site_name").unwrap_or_default();
+    let title = meta("og:title")
+        .or_else(|| meta("twitter:title"))
+        .or_else(|| {
+            elements(html, tags, "title")
+                .first()
+                .map(|(_, title)| decode_entities(title).trim().to_string())
+        });
+    // The URL, the site name, or the "| Site" end of the title
+    let title_suffix = title
+        .as_deref()
+        .and_then(|title| title.rsplit_once(" | "))
+        .map_or("", |(_, suffix)| suffix);
+    let page = format!(
+        "{} {site} {title_suffix}",
+        job.posting_url.as_deref().unwrap_or_default()
+    )
+    .to_lowercase();
+    job.source = BOARDS
+        .iter()
+        .find(|(name, _)| page.contains(name))
+        .map(|(_, source)| source.clone());
+
+    if let Some(title) = title {
+        let (company, role, location) = split_title(&title, &site);
+        if job.company.is_empty() {
+            job.company = company.unwrap_or_default();
+        }
+        if job.role.is_empty() {
+            job.role = role.unwrap_or_default();
+        }
+        if job.role_location.is_none() {
+            job.role_location = location;
+        }
+    }
+    if job.description.is_none() {
+        job.description = meta("og:description").or_else(|| meta("description"));
+    }
+}
+
+/// Company, role and location from a posting's title in one of the forms
+/// job boards use:
+///
+/// - `Acme hiring Engineer in Berlin | LinkedIn`
+/// - `Engineer job in Berlin at Acme | ZipRecruiter`
+/// - `Engineer at Acme`
+/// - `Engineer - Acme - Berlin | Indeed.com`
+fn split_title(title: &str, site: &str) -> (Option<String>, Option<String>, Option<String>) {
+    let mut title = title.trim();
+    // Drop the site name, e.g. " | LinkedIn" or " - Indeed.com"
+    for separator in [" | ", " - ", " – "] {
+        if let Some((rest, suffix)) = title.rsplit_once(separator) {
+            let suffix = suffix.to_lowercase();
+            let names_site = (!site.is_empty() && suffix.contains(&site.to_lowercase()))
+                || BOARDS.iter().any(|(name, _)| suffix.contains(name));
+            if names_site {
+                title = rest.trim();
+            }
+        }
+    }
+    let title = title
+        .strip_prefix("Job Application for ")
+        .unwrap_or(title)
+        .trim();
+    let some = |s: &str| Some(s.trim().to_string()).filter(|s| !s.is_empty());
+
+    if let Some((company, rest)) = title.split_once(" hiring ") {
+        let (role, location) = rest.rsplit_once(" in ").unwrap_or((rest, ""));
+        return (some(company), some(role), some(location));
+    }
+    if let Some((role, rest)) = title.split_once(" job in ") {
+        let (location, company) = rest.rsplit_once(" at ").unwrap_or((rest, ""));
+        return (some(company), some(role), some(location));
+    }
+    if let Some((role, company)) = title.rsplit_once(" at ") {
+        return (some(company), some(role), None);
+    }
+    let parts: Vec<&str> = title.split(" - ").collect();
+    match parts.as_slice() {
+        [role, company, location, ..] => (some(company), some(role), some(location)),
+        [role, company] => (some(company), some(role), None),
+        _ => (None, some(title), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_job_posting_data() {
+        let html = r#"<html><head><title>Ignored</title>
+<script type="application/ld+json">{"@context": "https://schema.org", "@graph": [{"@type": "WebPage"}, {
+  "@type": "JobPosting",
+  "title": "Backend Engineer",
+  "hiringOrganization": {"@type": "Organization", "name": "Acme &amp; Co"},
+  "url": "https://www.linkedin.com/jobs/view/1",
+  "description": "<p>Build <b>things</b>.</p>",
+  "jobLocation": [{"address": {"addressLocality": "Austin", "addressRegion": "TX"}}],
+  "jobLocationType": "TELECOMMUTE",
+  "baseSalary": {"currency": "usd", "value": {"minValue": "2,000", "maxValue": 2500, "unitText": "WEEK"}}
+}]}</script></head></html>"#;
+        let job = Job::from_posting_html(html.as_bytes()).unwrap();
+        assert_eq!(job.role, "Backend Engineer");
+        assert_eq!(job.company, "Acme & Co");
+        assert_eq!(
+            job.posting_url.as_deref(),
+            Some("https://www.linkedin.com/jobs/view/1")
+        );
+        assert_eq!(job.source, Some(JobSource::LINKEDIN));
+        assert_eq!(job.description.as_deref(), Some("Build things."));
+        assert_eq!(job.role_location.as_deref(), Some("Austin, TX; Remote"));
+        let salary = job.salary_range.unwrap();
+        assert_eq!((salary.min, salary.max), (104_000.0, 130_000.0));
+        assert_eq!(salary.currency, "USD");
+        assert_eq!(salary.period, PayPeriod::Year);
+    }
+
+    #[test]
+    fn reads_titles_and_meta_tags() {
+        let html = r#"<meta property="og:title" content="Acme hiring Data Engineer in Berlin, Germany | LinkedIn">
+<meta property="og:url" content="https://example.com/jobs/2">
+<meta name="description" content="Crunch numbers">"#;
+        let job = Job::from_posting_html(html.as_bytes()).unwrap();
+        assert_eq!(job.company, "Acme");
+        assert_eq!(job.role, "Data Engineer");
+        assert_eq!(job.role_location.as_deref(), Some("Berlin, Germany"));
+        assert_eq!(
+            job.posting_url.as_deref(),
+            Some("https://example.com/jobs/2")
+        );
+        assert_eq!(job.source, Some(JobSource::LINKEDIN));
+        assert_eq!(job.description.as_deref(), Some("Crunch numbers"));
+    }
+
+    #[test]
+    fn split_title_reads_board_formats() {
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(
+            split_title("Engineer job in Austin, TX at Acme | ZipRecruiter", ""),
+            (some("Acme"), some("Engineer"), some("Austin, TX"))
+        );
+        assert_eq!(
+            split_title("Engineer - Acme - Berlin | Indeed.com", ""),
+            (some("Acme"), some("Engineer"), some("Berlin"))
+        );
+        assert_eq!(
+            split_title("Job Application for Engineer at Acme", ""),
+            (some("Acme"), some("Engineer"), None)
+        );
+        assert_eq!(split_title("Careers", ""), (None, some("Careers"), None));
+    }
+
+    #[test]
+    fn pages_without_a_posting_are_rejected() {
+        assert!(Job::from_posting_html(&b"<html><body>Hello</body></html>"[..]).is_err());
+    }
+
+    #[test]
+    fn add_draft_needs_a_company_and_role() {
+        let mut store = JobStore::default();
+        let draft = Job {
+            company: " Acme ".to_string(),
+            role: " ".to_string(),
+            posting_url: Some("https://example.com/1".to_string()),
+            ..Job::default()
+        };
+        assert!(store.add_draft(draft.clone()).is_err());
+        assert!(store.jobs.is_empty());
+
+        let job = store
+            .add_draft(Job {
+                role: "Dev".to_string(),
+                ..draft
+            })
+            .unwrap();
+        assert_eq!(job.company, "Acme");
+        assert_eq!(job.status, store.initial_status());
+        assert_eq!(job.posting_url.as_deref(), Some("https://example.com/1"));
+        assert_eq!(store.jobs, [job]);
+        assert_eq!(store.undo().unwrap().as_deref(), Some("Add application"));
+        assert!(store
+            .add_job(" ".into(), "Dev".into(), String::new(), String::new())
+            .is_err());
+        assert!(store.jobs.is_empty());
+    }
+
+    #[test]
+    fn drafts_keep_their_tags_and_notes() {
+        let mut store = JobStore::default();
+        let mut draft = Job {
+            company: "Acme".to_string(),
+            role: "Dev".to_string(),
+            tags: ["Remote ".to_string(), " ".to_string()].into(),
+            ..Job::default()
+        };
+        let at = Utc::now() - chrono::Duration::days(1);
+        draft.add_note("Found on the company blog".to_string(), at);
+        draft.add_note("Ask about on-call".to_string(), at);
+        draft.notes[0].id = 7;
+
+        let job = store.add_draft(draft).unwrap();
+        assert_eq!(job.tags, ["remote".to_string()].into());
+        let notes: Vec<(u32, &str)> = job
+            .notes
+            .iter()
+            .map(|note| (note.id, note.text.as_str()))
+            .collect();
+        assert_eq!(
+            notes,
+            [(1, "Found on the company blog"), (2, "Ask about on-call")]
+        );
+        assert_eq!(job.notes[0].at, at);
+    }
+}
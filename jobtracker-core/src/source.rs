@@ -0,0 +1,370 @@
+use crate::JobChange;
+use crate::JobSource;
+use crate::JobStore;
+use crate::ListChange;
+use crate::Source;
+use crate::StoreCommand;
+use anyhow::{anyhow, bail, Result};
+use std::collections::HashSet;
+
+impl Source {
+    pub fn new(name: JobSource) -> Self {
+        Self {
+            name,
+            aliases: Vec::new(),
+            referral: false,
+        }
+    }
+
+    /// Also recognizes the source as each of `aliases`.
+    pub fn with_aliases(mut self, aliases: &[&str]) -> Self {
+        self.aliases = aliases.iter().map(|alias| alias.to_string()).collect();
+        self
+    }
+
+    /// Marks the source as someone referring the user.
+    pub fn referral(mut self) -> Self {
+        self.referral = true;
+        self
+    }
+
+    /// The sources the tracker has always had, plus referrals.
+    pub fn defaults() -> Vec<Source> {
+        vec![
+            Source::new(JobSource::RECRUITER).with_aliases(&["Headhunter", "Agency"]),
+            Source::new(JobSource::LINKEDIN),
+            Source::new(JobSource::MONSTER).with_aliases(&["Monster.com"]),
+            Source::new(JobSource::INDEED),
+            Source::new(JobSource::TALENT).with_aliases(&["Talent"]),
+            Source::new(JobSource::GLASSDOOR),
+            Source::new(JobSource::ZIPRECRUITER).with_aliases(&["Zip Recruiter"]),
+            Source::new(JobSource::REFERRAL)
+                .with_aliases(&["Referred"])
+                .referral(),
+        ]
+    }
+
+    /// Whether `text` is the source's name or one of its aliases, ignoring
+    /// case and surrounding whitespace.
+    pub fn is_called(&self, text: &str) -> bool {
+        let text = text.trim();
+        self.name.as_str().eq_ignore_ascii_case(text)
+            || self
+                .aliases
+                .iter()
+                .any(|alias| alias.trim().eq_ignore_ascii_case(text))
+    }
+
+    /// Checks that `sources` can be used: no blank names or aliases, and no
+    /// name or alias used twice, so typed text always means one source.
+    pub fn validate(sources: &[Source]) -> Result<()> {
+        let mut seen = HashSet::new();
+        for source in sources {
+            let name = source.name.as_str().trim();
+            if name.is_empty() {
+                bail!("Source names can't be empty");
+            }
+            for text in std::iter::once(name).chain(source.aliases.iter().map(|a| a.trim())) {
+                if text.is_empty() {
+                    bail!("Source \"{name}\" has an empty alias");
+                }
+                if !seen.insert(text.to_lowercase()) {
+                    bail!("More than one source is called \"{text}\"");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl JobStore {
+    /// Definition of the source `name` names, if it's still defined.
+    pub fn source(&self, name: &JobSource) -> Option<&Source> {
+        self.sources.iter().find(|source| &source.name == name)
+    }
+
+    /// The source called `text`, by name or alias, ignoring case.
+    pub fn parse_source(&self, text: &str) -> Result<JobSource> {
+        let text = text.trim();
+        self.sources
+            .iter()
+            .find(|source| source.is_called(text))
+            .map(|source| source.name.clone())
+            .ok_or_else(|| {
+                let names: Vec<&str> = self.sources.iter().map(|s| s.name.as_str()).collect();
+                anyhow!(
+                    "Unknown job source: {text}. Sources are {}. Add it as a new source to use it",
+                    names.join(", ")
+                )
+            })
+    }
+
+    /// Whether applications from `source` can name who referred the user.
+    pub fn is_referral(&self, source: Option<&JobSource>) -> bool {
+        source
+            .and_then(|source| self.source(source))
+            .is_some_and(|source| source.referral)
+    }
+
+    /// Replaces the list of sources with `sources`. `renamed` maps old
+    /// source names to new ones, and every job from an old source is moved
+    /// to the new name. Fails if a source that jobs come from would
+    /// disappear. Jobs whose source stops being a referral lose their
+    /// referrer. The edit is undone and redone like any other change.
+    pub fn set_sources(
+        &mut self,
+        sources: Vec<Source>,
+        renamed: &[(JobSource, JobSource)],
+    ) -> Result<()> {
+        Source::validate(&sources)?;
+        let rename = |source: &JobSource| {
+            renamed
+                .iter()
+                .find(|(old, _)| old == source)
+                .map_or_else(|| source.clone(), |(_, new)| new.clone())
+        };
+        for job in &self.jobs {
+            let Some(source) = &job.source else {
+                continue;
+            };
+            if !sources.iter().any(|s| s.name == rename(source)) {
+                bail!(
+                    "Can't remove source \"{source}\": job {} ({} - {}) came from it",
+                    job.id,
+                    job.company,
+                    job.role
+                );
+            }
+        }
+
+        let is_referral = |source: &Option<JobSource>| {
+            source
+                .as_ref()
+                .and_then(|name| sources.iter().find(|s| &s.name == name))
+                .is_some_and(|s| s.referral)
+        };
+        // As with stages, older commands only come up for undo after this
+        // one has put the old names back
+        let mut changes = Vec::new();
+        for job in &mut self.jobs {
+            let source = job.source.as_ref().map(rename);
+            let referrer = job.referrer.filter(|_| is_referral(&source));
+            if source != job.source || referrer != job.referrer {
+                let before = job.clone();
+                job.source = source;
+                job.referrer = referrer;
+                changes.push(JobChange {
+                    before: Some(before),
+                    after: Some(job.clone()),
+                });
+            }
+        }
+        let source_change = (sources != self.sources).then(|| ListChange {
+            before: std::mem::replace(&mut self.sources, sources),
+            after: self.sources.clone(),
+        });
+        self.record_command(StoreCommand {
+            label: "Change sources".to_string(),
+            changes,
+            source_change,
+            ..Default::default()
+        })
+    }
+
+    /// Adds `source` to the end of the list.
+    pub fn add_source(&mut self, source: Source) -> Result<()> {
+        let mut sources = self.sources.clone();
+        sources.push(source);
+        self.set_sources(sources, &[])
+    }
+
+    /// Takes the source called `text` off the list. Fails if jobs came
+    /// from it.
+    pub fn remove_source(&mut self, text: &str) -> Result<()> {
+        let name = self.parse_source(text)?;
+        let sources = self
+            .sources
+            .iter()
+            .filter(|source| source.name != name)
+            .cloned()
+            .collect();
+        self.set_sources(sources, &[])
+    }
+
+    /// Names contact `referrer` as the person who referred the user for job
+    /// `id`, or with `None`, nobody. Only applications from a referral
+    /// source can have a referrer.
+    pub fn set_referrer(&mut self, id: u32, referrer: Option<u32>) -> Result<()> {
+        let job = self.find_job(id)?;
+        self.check_referrer(job.source.as_ref(), referrer)?;
+        self.modify_job(id, "Change referrer", |job| job.referrer = referrer)
+    }
+
+    /// Sets where job `id` came from and who referred the user for it as a
+    /// single change. `source` has to name one of the store's sources, see
+    /// [`Self::parse_source`].
+    pub fn set_source_and_referrer(
+        &mut self,
+        id: u32,
+        source: Option<JobSource>,
+        referrer: Option<u32>,
+    ) -> Result<()> {
+        let source = source
+            .map(|source| self.parse_source(source.as_str()))
+            .transpose()?;
+        self.check_referrer(source.as_ref(), referrer)?;
+        self.modify_job(id, "Change source", |job| {
+            job.source = source;
+            job.referrer = referrer;
+        })
+    }
+
+    /// Fails unless contact `referrer`, if any, exists and `source` is a
+    /// referral source.
+    pub(crate) fn check_referrer(
+        &self,
+        source: Option<&JobSource>,
+        referrer: Option<u32>,
+    ) -> Result<()> {
+        let Some(referrer) = referrer else {
+            return Ok(());
+        };
+        if !self.is_referral(source) {
+            let source = source.map_or("Not provided".to_string(), |s| s.to_string());
+            bail!("Only applications from a referral source can have a referrer, not {source}");
+        }
+        self.find_contact(referrer)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::store_with_job;
+    use crate::Contact;
+
+    fn referrer(store: &mut JobStore) -> u32 {
+        store
+            .add_contact(Contact {
+                name: "Sam".to_string(),
+                ..Contact::default()
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn sources_are_found_by_name_or_alias() {
+        let store = JobStore::default();
+        assert_eq!(store.parse_source("linkedin").unwrap(), JobSource::LINKEDIN);
+        assert_eq!(
+            store.parse_source("headhunter").unwrap(),
+            JobSource::RECRUITER
+        );
+        assert_eq!(
+            store.parse_source(" referred ").unwrap(),
+            JobSource::REFERRAL
+        );
+        let error = store
+            .parse_source("Carrier pigeon")
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("Unknown job source: Carrier pigeon. Sources are Recruiter"));
+        assert!(error.ends_with("Add it as a new source to use it"));
+    }
+
+    #[test]
+    fn source_lists_are_checked() {
+        let mut store = store_with_job();
+        store
+            .set_source_and_referrer(1, Some(JobSource::INDEED), None)
+            .unwrap();
+
+        let mut sources = Source::defaults();
+        sources.push(Source::new(JobSource::new("Agency")));
+        let error = store.set_sources(sources, &[]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "More than one source is called \"Agency\""
+        );
+        let mut sources = Source::defaults();
+        sources[0].aliases.push(" ".to_string());
+        let error = store.set_sources(sources, &[]).unwrap_err();
+        assert_eq!(error.to_string(), "Source \"Recruiter\" has an empty alias");
+
+        let error = store.remove_source("indeed").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Can't remove source \"Indeed\": job 1 (Acme - Dev) came from it"
+        );
+        assert!(store.source(&JobSource::INDEED).is_some());
+        store.remove_source("glassdoor").unwrap();
+        assert!(store.source(&JobSource::GLASSDOOR).is_none());
+
+        let board = JobSource::new("Job board");
+        let mut sources = store.sources.clone();
+        sources[3] = Source::new(board.clone());
+        store
+            .set_sources(sources, &[(JobSource::INDEED, board.clone())])
+            .unwrap();
+        assert_eq!(store.jobs[0].source, Some(board));
+    }
+
+    #[test]
+    fn only_referrals_have_referrers() {
+        let mut store = store_with_job();
+        let sam = referrer(&mut store);
+        let error = store.set_referrer(1, Some(sam)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Only applications from a referral source can have a referrer, not Not provided"
+        );
+        let error = store
+            .set_source_and_referrer(1, Some(JobSource::LINKEDIN), Some(sam))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Only applications from a referral source can have a referrer, not LinkedIn"
+        );
+        assert!(store
+            .set_source_and_referrer(1, Some(JobSource::REFERRAL), Some(sam + 1))
+            .is_err());
+        assert_eq!(store.jobs[0].source, None);
+
+        store
+            .set_source_and_referrer(1, Some(JobSource::new("referred")), Some(sam))
+            .unwrap();
+        assert_eq!(store.jobs[0].source, Some(JobSource::REFERRAL));
+        assert_eq!(store.jobs[0].referrer, Some(sam));
+        store.set_referrer(1, None).unwrap();
+        assert_eq!(store.jobs[0].referrer, None);
+    }
+
+    #[test]
+    fn source_edits_can_be_undone() {
+        let mut store = store_with_job();
+        let sam = referrer(&mut store);
+        store
+            .set_source_and_referrer(1, Some(JobSource::REFERRAL), Some(sam))
+            .unwrap();
+        let friend = JobSource::new("Friend");
+        let mut sources = Source::defaults();
+        sources[7] = Source::new(friend.clone());
+
+        store
+            .set_sources(sources.clone(), &[(JobSource::REFERRAL, friend.clone())])
+            .unwrap();
+        assert_eq!(store.jobs[0].source, Some(friend));
+        // Friends aren't a referral source any more, so nobody referred the user
+        assert_eq!(store.jobs[0].referrer, None);
+        assert_eq!(store.sources, sources);
+
+        assert_eq!(store.undo().unwrap().as_deref(), Some("Change sources"));
+        assert_eq!(store.sources, Source::defaults());
+        assert_eq!(store.jobs[0].source, Some(JobSource::REFERRAL));
+        assert_eq!(store.jobs[0].referrer, Some(sam));
+        // Changes from before the edit still undo
+        assert_eq!(store.undo().unwrap().as_deref(), Some("Change source"));
+        assert_eq!(store.jobs[0].referrer, None);
+    }
+}
@@ -0,0 +1,600 @@
+use crate::Contact;
+use crate::DataFileError;
+use crate::Job;
+use crate::JobChange;
+use crate::JobSource;
+use crate::JobStatus;
+use crate::Location;
+use crate::Source;
+use crate::SqliteStorage;
+use crate::Stage;
+use crate::Storage;
+use anyhow::{bail, Result};
+use rusqlite::{params, Connection, Row, Transaction};
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+
+/// Schema changes, applied in order. The database's `user_version` pragma
+/// records how many of these have run, so only append to this list: never
+/// edit or reorder a migration that has already shipped.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE jobs (
+        id INTEGER PRIMARY KEY,
+        company TEXT NOT NULL,
+        role TEXT NOT NULL,
+        role_location TEXT,
+        status TEXT NOT NULL,
+        timestamp TEXT NOT NULL,
+        source TEXT
+    );",
+    // Status history as a JSON array of `StatusChange`. Existing rows start
+    // from their current status, like `seed_status_history` does for JSON
+    // files.
+    "ALTER TABLE jobs ADD COLUMN history TEXT NOT NULL DEFAULT '[]';
+    UPDATE jobs SET history = json_array(json_object(
+        'from', NULL,
+        'to', status,
+        'at', replace(timestamp, ' ', 'T'),
+        'note', 'Status when history tracking began'
+    ));",
+    "ALTER TABLE jobs ADD COLUMN deleted_at TEXT;",
+    // Pipeline stages, seeded with the ones `Stage::defaults` returns.
+    "CREATE TABLE stages (
+        name TEXT PRIMARY KEY,
+        position INTEGER NOT NULL,
+        color TEXT NOT NULL,
+        terminal INTEGER NOT NULL DEFAULT 0,
+        outcome TEXT
+    );
+    INSERT INTO stages (name, position, color, terminal, outcome) VALUES
+        ('Applied', 0, '#4169e1', 0, NULL),
+        ('Interview', 1, '#00ffff', 0, NULL),
+        ('Offer', 2, '#00ff00', 1, 'Success'),
+        ('Rejected', 3, '#ff0000', 1, 'Failure'),
+        ('Ghosted', 4, '#808080', 1, 'Failure');",
+    // Allowed next stages as a JSON array of names. NULL keeps the default
+    // rule, see `Stage::next`.
+    "ALTER TABLE stages ADD COLUMN next TEXT;",
+    // Notes as a JSON array of `Note`.
+    "ALTER TABLE jobs ADD COLUMN notes TEXT NOT NULL DEFAULT '[]';",
+    "CREATE TABLE contacts (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        email TEXT,
+        phone TEXT,
+        role TEXT,
+        company TEXT,
+        notes TEXT
+    );
+    CREATE TABLE contact_jobs (
+        contact_id INTEGER NOT NULL REFERENCES contacts(id) ON DELETE CASCADE,
+        job_id INTEGER NOT NULL,
+        PRIMARY KEY (contact_id, job_id)
+    );",
+    // Interviews as a JSON array of `Interview`.
+    "ALTER TABLE jobs ADD COLUMN interviews TEXT NOT NULL DEFAULT '[]';",
+    // Salary range and offer as JSON objects, NULL when not set.
+    "ALTER TABLE jobs ADD COLUMN salary_range TEXT;
+     ALTER TABLE jobs ADD COLUMN offer TEXT;",
+    "ALTER TABLE jobs ADD COLUMN posting_url TEXT;
+     ALTER TABLE jobs ADD COLUMN description TEXT;",
+    // Tags as a JSON array of strings.
+    "ALTER TABLE jobs ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';",
+    // Structured location as a JSON `Location`, filled in by
+    // `parse_locations`.
+    "ALTER TABLE jobs ADD COLUMN location TEXT NOT NULL DEFAULT '{}';",
+    // Job sources, seeded with the ones `Source::defaults` returns. Aliases
+    // are a JSON array of strings. Jobs used to say "Not provided" when they
+    // had no source, and "Talent" for Talent.com.
+    "CREATE TABLE sources (
+        name TEXT PRIMARY KEY,
+        position INTEGER NOT NULL,
+        aliases TEXT NOT NULL DEFAULT '[]',
+        referral INTEGER NOT NULL DEFAULT 0
+    );
+    INSERT INTO sources (name, position, aliases, referral) VALUES
+        ('Recruiter', 0, '[\"Headhunter\",\"Agency\"]', 0),
+        ('LinkedIn', 1, '[]', 0),
+        ('Monster', 2, '[\"Monster.com\"]', 0),
+        ('Indeed', 3, '[]', 0),
+        ('Talent.com', 4, '[\"Talent\"]', 0),
+        ('Glassdoor', 5, '[]', 0),
+        ('ZipRecruiter', 6, '[\"Zip Recruiter\"]', 0),
+        ('Referral', 7, '[\"Referred\"]', 1);
+    UPDATE jobs SET source = NULL WHERE source = 'Not provided';
+    UPDATE jobs SET source = 'Talent.com' WHERE source = 'Talent';
+    ALTER TABLE jobs ADD COLUMN referrer INTEGER;",
+];
+
+/// Data changes SQL can't make on its own, each run in the same transaction
+/// as the migration it follows, by how many migrations have run by then.
+const DATA_MIGRATIONS: &[(usize, DataMigration)] = &[(12, parse_locations)];
+
+type DataMigration = fn(&Transaction) -> Result<()>;
+
+/// Parses the free-text location of every existing job, like
+/// `parse_locations` in `job_document` does for JSON files.
+fn parse_locations(tx: &Transaction) -> Result<()> {
+    let mut stmt = tx.prepare("SELECT id, role_location FROM jobs")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, u32>(0)?, row.get::<_, Option<String>>(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for (id, text) in rows {
+        let location = Location::parse(text.as_deref().unwrap_or_default());
+        tx.execute(
+            "UPDATE jobs SET location = ?1 WHERE id = ?2",
+            params![serde_json::to_string(&location)?, id],
+        )?;
+    }
+    Ok(())
+}
+
+/// Columns of the `jobs` table in the order `job_from_row` and `write_job`
+/// expect them.
+const COLUMNS: &str =
+    "id, company, role, role_location, status, timestamp, source, history, deleted_at, notes, interviews, salary_range, offer, posting_url, description, tags, location, referrer";
+
+/// Reads a column holding JSON. NULL reads as JSON `null`, so optional
+/// values come back as `None`.
+fn json_column<T: DeserializeOwned>(row: &Row, idx: usize) -> rusqlite::Result<T> {
+    let text: Option<String> = row.get(idx)?;
+    serde_json::from_str(text.as_deref().unwrap_or("null")).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, e.into())
+    })
+}
+
+impl SqliteStorage {
+    /// Opens (or creates) the database at `path` and brings its schema up
+    /// to date.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let conn = Connection::open(&path)?;
+        let mut storage = Self { path, conn };
+        storage.migrate()?;
+        Ok(storage)
+    }
+
+    /// Runs every migration the database hasn't seen yet, each in its own
+    /// transaction together with the version bump.
+    fn migrate(&mut self) -> Result<()> {
+        let version: usize = self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > MIGRATIONS.len() {
+            bail!(DataFileError::NewerVersion {
+                path: self.path.clone(),
+                found: version as u64,
+                supported: MIGRATIONS.len() as u32,
+            });
+        }
+        for (idx, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let tx = self.conn.transaction()?;
+            tx.execute_batch(migration)?;
+            for (_, migrate_data) in DATA_MIGRATIONS
+                .iter()
+                .filter(|(after, _)| *after == idx + 1)
+            {
+                migrate_data(&tx)?;
+            }
+            tx.pragma_update(None, "user_version", idx + 1)?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    fn job_from_row(row: &Row) -> rusqlite::Result<Job> {
+        let status: String = row.get(4)?;
+        let source: Option<String> = row.get(6)?;
+        Ok(Job {
+            id: row.get(0)?,
+            company: row.get(1)?,
+            role: row.get(2)?,
+            role_location: row.get(3)?,
+            status: JobStatus::new(status),
+            timestamp: row.get(5)?,
+            source: source.map(JobSource::new),
+            history: json_column(row, 7)?,
+            deleted_at: row.get(8)?,
+            notes: json_column(row, 9)?,
+            interviews: json_column(row, 10)?,
+            salary_range: json_column(row, 11)?,
+            offer: json_column(row, 12)?,
+            posting_url: row.get(13)?,
+            description: row.get(14)?,
+            tags: json_column(row, 15)?,
+            location: json_column(row, 16)?,
+            referrer: row.get(17)?,
+        })
+    }
+
+    /// Writes every column of `job` with `verb`, either `INSERT` or
+    /// `REPLACE`.
+    fn write_job(conn: &Connection, verb: &str, job: &Job) -> Result<()> {
+        conn.execute(
+            &format!(
+                "{verb} INTO jobs ({COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)"
+            ),
+            params![
+                job.id,
+                job.company,
+                job.role,
+                job.role_location,
+                job.status.to_string(),
+                job.timestamp,
+                job.source.as_ref().map(JobSource::to_string),
+                serde_json::to_string(&job.history)?,
+                job.deleted_at,
+                serde_json::to_string(&job.notes)?,
+                serde_json::to_string(&job.interviews)?,
+                job.salary_range.as_ref().map(serde_json::to_string).transpose()?,
+                job.offer.as_ref().map(serde_json::to_string).transpose()?,
+                job.posting_url,
+                job.description,
+                serde_json::to_string(&job.tags)?,
+                serde_json::to_string(&job.location)?,
+                job.referrer,
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn load(&mut self) -> Result<Vec<Job>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT {COLUMNS} FROM jobs ORDER BY id"))?;
+        let jobs = stmt
+            .query_map([], Self::job_from_row)?
+            .collect::<rusqlite::Result<Vec<Job>>>()?;
+        Ok(jobs)
+    }
+
+    fn save(&mut self, jobs: &[Job]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM jobs", [])?;
+        for job in jobs {
+            Self::write_job(&tx, "INSERT", job)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn insert(&mut self, job: &Job) -> Result<()> {
+        Self::write_job(&self.conn, "INSERT", job)
+    }
+
+    fn update(&mut self, job: &Job) -> Result<()> {
+        Self::write_job(&self.conn, "REPLACE", job)
+    }
+
+    fn delete(&mut self, id: u32) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM jobs WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    fn apply_changes(&mut self, changes: &[JobChange]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        for change in changes {
+            match (&change.before, &change.after) {
+                (None, Some(after)) => Self::write_job(&tx, "INSERT", after)?,
+                (Some(_), Some(after)) => Self::write_job(&tx, "REPLACE", after)?,
+                (Some(before), None) => {
+                    tx.execute("DELETE FROM jobs WHERE id = ?1", params![before.id])?;
+                }
+                (None, None) => {}
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn load_stages(&mut self) -> Result<Vec<Stage>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name, position, color, terminal, outcome, next FROM stages ORDER BY position",
+        )?;
+        let stages = stmt
+            .query_map([], |row| {
+                let outcome: Option<String> = row.get(4)?;
+                let next: Option<String> = row.get(5)?;
+                Ok(Stage {
+                    name: JobStatus::new(row.get::<_, String>(0)?),
+                    order: row.get(1)?,
+                    color: row.get(2)?,
+                    terminal: row.get(3)?,
+                    outcome: outcome.and_then(|outcome| outcome.parse().ok()),
+                    next: next
+                        .map(|next| serde_json::from_str(&next))
+                        .transpose()
+                        .map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                5,
+                                rusqlite::types::Type::Text,
+                                e.into(),
+                            )
+                        })?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<Stage>>>()?;
+        Ok(stages)
+    }
+
+    fn save_stages(&mut self, stages: &[Stage]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM stages", [])?;
+        for stage in stages {
+            tx.execute(
+                "INSERT INTO stages (name, position, color, terminal, outcome, next)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    stage.name.as_str(),
+                    stage.order,
+                    stage.color,
+                    stage.terminal,
+                    stage.outcome.map(|outcome| outcome.to_string()),
+                    stage.next.as_ref().map(serde_json::to_string).transpose()?,
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn load_sources(&mut self) -> Result<Vec<Source>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name, aliases, referral FROM sources ORDER BY position")?;
+        let sources = stmt
+            .query_map([], |row| {
+                Ok(Source {
+                    name: JobSource::new(row.get::<_, String>(0)?),
+                    aliases: json_column(row, 1)?,
+                    referral: row.get(2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<Source>>>()?;
+        Ok(sources)
+    }
+
+    fn save_sources(&mut self, sources: &[Source]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM sources", [])?;
+        for (position, source) in sources.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO sources (name, position, aliases, referral) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    source.name.as_str(),
+                    position,
+                    serde_json::to_string(&source.aliases)?,
+                    source.referral,
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn load_contacts(&mut self) -> Result<Vec<Contact>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, email, phone, role, company, notes FROM contacts ORDER BY id",
+        )?;
+        let mut contacts = stmt
+            .query_map([], |row| {
+                Ok(Contact {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    email: row.get(2)?,
+                    phone: row.get(3)?,
+                    role: row.get(4)?,
+                    company: row.get(5)?,
+                    notes: row.get(6)?,
+                    job_ids: Vec::new(),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<Contact>>>()?;
+        let mut stmt = self
+            .conn
+            .prepare("SELECT contact_id, job_id FROM contact_jobs ORDER BY job_id")?;
+        let links = stmt.query_map([], |row| Ok((row.get::<_, u32>(0)?, row.get(1)?)))?;
+        for link in links {
+            let (contact_id, job_id) = link?;
+            if let Some(contact) = contacts.iter_mut().find(|c| c.id == contact_id) {
+                contact.job_ids.push(job_id);
+            }
+        }
+        Ok(contacts)
+    }
+
+    fn save_contacts(&mut self, contacts: &[Contact]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM contact_jobs", [])?;
+        tx.execute("DELETE FROM contacts", [])?;
+        for contact in contacts {
+            tx.execute(
+                "INSERT INTO contacts (id, name, email, phone, role, company, notes)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    contact.id,
+                    contact.name,
+                    contact.email,
+                    contact.phone,
+                    contact.role,
+                    contact.company,
+                    contact.notes,
+                ],
+            )?;
+            for job_id in &contact.job_ids {
+                tx.execute(
+                    "INSERT OR IGNORE INTO contact_jobs (contact_id, job_id) VALUES (?1, ?2)",
+                    params![contact.id, job_id],
+                )?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job_document::IMPORTED_HISTORY_NOTE;
+    use crate::JobStatus;
+    use serde_json::json;
+
+    /// An in-memory database that has only run the first `version`
+    /// migrations, like one written by an older build.
+    fn database_at(version: usize) -> SqliteStorage {
+        let conn = Connection::open_in_memory().unwrap();
+        for migration in &MIGRATIONS[..version] {
+            conn.execute_batch(migration).unwrap();
+        }
+        conn.pragma_update(None, "user_version", version).unwrap();
+        SqliteStorage {
+            path: PathBuf::from("jobs.db"),
+            conn,
+        }
+    }
+
+    /// A job with every field not given here left at its default.
+    fn job(id: u32, company: &str) -> Job {
+        serde_json::from_value(json!({
+            "id": id,
+            "company": company,
+            "role": "Dev",
+            "role_location": "Remote",
+            "status": "Applied",
+            "timestamp": "2024-01-02T03:04:05Z",
+            "source": "LinkedIn",
+            "history": [],
+        }))
+        .unwrap()
+    }
+
+    fn companies(storage: &mut SqliteStorage) -> Vec<String> {
+        storage
+            .load()
+            .unwrap()
+            .into_iter()
+            .map(|job| job.company)
+            .collect()
+    }
+
+    #[test]
+    fn migrates_the_first_schema() {
+        let mut storage = database_at(1);
+        storage
+            .conn
+            .execute_batch(
+                "INSERT INTO jobs VALUES
+                    (1, 'Acme', 'Dev', 'Austin, TX (hybrid)', 'Ghosted', '2024-01-02 03:04:05+00:00', 'Not provided'),
+                    (2, 'Initech', 'QA', NULL, 'Applied', '2024-02-03 04:05:06+00:00', 'Talent');",
+            )
+            .unwrap();
+        storage.migrate().unwrap();
+
+        let jobs = storage.load().unwrap();
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].source, None);
+        assert_eq!(jobs[1].source, Some(JobSource::new("Talent.com")));
+        assert_eq!(jobs[0].history.len(), 1);
+        assert_eq!(jobs[0].history[0].from, None);
+        assert_eq!(jobs[0].history[0].to, JobStatus::GHOSTED);
+        assert_eq!(jobs[0].history[0].at, jobs[0].timestamp);
+        assert_eq!(
+            jobs[0].history[0].note.as_deref(),
+            Some(IMPORTED_HISTORY_NOTE)
+        );
+        assert_eq!(jobs[1].history[0].to, JobStatus::APPLIED);
+        assert_eq!(jobs[0].location, Location::parse("Austin, TX (hybrid)"));
+        assert_eq!(jobs[0].location.city.as_deref(), Some("Austin"));
+        assert_eq!(jobs[1].location, Location::default());
+        assert_eq!(storage.load_stages().unwrap(), Stage::defaults());
+        assert_eq!(storage.load_sources().unwrap(), Source::defaults());
+    }
+
+    #[test]
+    fn migrates_new_databases_once() {
+        let mut storage = database_at(0);
+        storage.migrate().unwrap();
+        storage.migrate().unwrap();
+        let version: usize = storage
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+    }
+
+    #[test]
+    fn refuses_newer_databases() {
+        let mut storage = database_at(MIGRATIONS.len());
+        storage
+            .conn
+            .pragma_update(None, "user_version", MIGRATIONS.len() + 1)
+            .unwrap();
+        let error = storage.migrate().unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<DataFileError>(),
+            Some(DataFileError::NewerVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn writes_single_rows() {
+        let mut storage = database_at(0);
+        storage.migrate().unwrap();
+        storage.insert(&job(1, "Acme")).unwrap();
+        storage.insert(&job(2, "Initech")).unwrap();
+        storage.update(&job(1, "Globex")).unwrap();
+        assert_eq!(companies(&mut storage), ["Globex", "Initech"]);
+
+        let read = &storage.load().unwrap()[1];
+        assert_eq!(read.timestamp, job(2, "").timestamp);
+        assert_eq!(read.source, Some(JobSource::LINKEDIN));
+        assert_eq!(read.role_location.as_deref(), Some("Remote"));
+
+        storage.delete(1).unwrap();
+        assert_eq!(companies(&mut storage), ["Initech"]);
+        storage.save(&[job(5, "Hooli"), job(3, "Acme")]).unwrap();
+        assert_eq!(companies(&mut storage), ["Acme", "Hooli"]);
+    }
+
+    #[test]
+    fn keeps_posting_details() {
+        let mut storage = database_at(MIGRATIONS.len());
+        let mut posted = job(1, "Acme");
+        posted.posting_url = Some("https://example.com/jobs/1".to_string());
+        posted.description = Some("Build the billing pipeline".to_string());
+        storage.insert(&posted).unwrap();
+        storage.insert(&job(2, "Initech")).unwrap();
+        let jobs = storage.load().unwrap();
+        assert_eq!(jobs[0], posted);
+        assert_eq!((&jobs[1].posting_url, &jobs[1].description), (&None, &None));
+    }
+
+    #[test]
+    fn writes_batches_in_one_transaction() {
+        let mut storage = database_at(MIGRATIONS.len());
+        storage.save(&[job(1, "Acme"), job(2, "Initech")]).unwrap();
+        let change = |before: Option<Job>, after: Option<Job>| JobChange { before, after };
+        storage
+            .apply_changes(&[
+                change(None, Some(job(3, "Hooli"))),
+                change(Some(job(1, "Acme")), Some(job(1, "Globex"))),
+                change(Some(job(2, "Initech")), None),
+            ])
+            .unwrap();
+        assert_eq!(companies(&mut storage), ["Globex", "Hooli"]);
+
+        // Job 3 already exists, so the whole batch is rolled back
+        let error = storage.apply_changes(&[
+            change(Some(job(1, "Globex")), None),
+            change(None, Some(job(3, "Hooli"))),
+        ]);
+        assert!(error.is_err());
+        assert_eq!(companies(&mut storage), ["Globex", "Hooli"]);
+    }
+}
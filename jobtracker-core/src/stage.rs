@@ -0,0 +1,209 @@
+use crate::JobChange;
+use crate::JobStatus;
+use crate::JobStore;
+use crate::ListChange;
+use crate::Stage;
+use crate::StageOutcome;
+use crate::StoreCommand;
+use anyhow::{anyhow, bail, Result};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+impl Stage {
+    pub fn new(name: JobStatus, order: u32, color: &str) -> Self {
+        Self {
+            name,
+            order,
+            color: color.to_string(),
+            terminal: false,
+            outcome: None,
+            next: None,
+        }
+    }
+
+    /// Marks the stage as the end of the pipeline with the given outcome.
+    pub fn terminal(mut self, outcome: Option<StageOutcome>) -> Self {
+        self.terminal = true;
+        self.outcome = outcome;
+        self
+    }
+
+    /// The five stages the tracker has always had.
+    pub fn defaults() -> Vec<Stage> {
+        vec![
+            Stage::new(JobStatus::APPLIED, 0, "#4169e1"),
+            Stage::new(JobStatus::INTERVIEW, 1, "#00ffff"),
+            Stage::new(JobStatus::OFFER, 2, "#00ff00").terminal(Some(StageOutcome::Success)),
+            Stage::new(JobStatus::REJECTED, 3, "#ff0000").terminal(Some(StageOutcome::Failure)),
+            Stage::new(JobStatus::GHOSTED, 4, "#808080").terminal(Some(StageOutcome::Failure)),
+        ]
+    }
+
+    /// `color` as red, green and blue, if it's a valid `#rrggbb` string.
+    pub fn rgb(&self) -> Option<[u8; 3]> {
+        let hex = self.color.strip_prefix('#')?;
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        Some([channel(0)?, channel(2)?, channel(4)?])
+    }
+
+    /// Checks that `stages` can be used as a pipeline: at least one stage,
+    /// no blank or duplicate names, only valid colors and only existing
+    /// stages as next steps.
+    pub fn validate(stages: &[Stage]) -> Result<()> {
+        if stages.is_empty() {
+            bail!("The pipeline needs at least one stage");
+        }
+        let mut seen = HashSet::new();
+        for stage in stages {
+            let name = stage.name.as_str().trim();
+            if name.is_empty() {
+                bail!("Stage names can't be empty");
+            }
+            if !seen.insert(name.to_lowercase()) {
+                bail!("There is more than one stage named \"{name}\"");
+            }
+            let unknown = stage
+                .next
+                .iter()
+                .flatten()
+                .find(|next| !stages.iter().any(|other| &other.name == *next));
+            if let Some(unknown) = unknown {
+                bail!("Stage \"{name}\" leads to \"{unknown}\", which isn't a stage");
+            }
+            if stage.rgb().is_none() {
+                bail!(
+                    "Stage \"{name}\" has color \"{}\", expected #rrggbb",
+                    stage.color
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for StageOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StageOutcome::Success => write!(f, "Success"),
+            StageOutcome::Failure => write!(f, "Failure"),
+        }
+    }
+}
+
+impl FromStr for StageOutcome {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "success" => Ok(StageOutcome::Success),
+            "failure" => Ok(StageOutcome::Failure),
+            _ => Err(format!("Unknown stage outcome: {s}")),
+        }
+    }
+}
+
+impl JobStore {
+    /// Definition of the stage `status` names, if it's still defined.
+    pub fn stage(&self, status: &JobStatus) -> Option<&Stage> {
+        self.stages.iter().find(|stage| &stage.name == status)
+    }
+
+    /// The stage named `name`, ignoring case.
+    pub fn parse_status(&self, name: &str) -> Result<JobStatus> {
+        let name = name.trim();
+        self.stages
+            .iter()
+            .find(|stage| stage.name.as_str().eq_ignore_ascii_case(name))
+            .map(|stage| stage.name.clone())
+            .ok_or_else(|| {
+                let names: Vec<&str> = self.stages.iter().map(|s| s.name.as_str()).collect();
+                anyhow!(
+                    "Unknown job status: {name}. Stages are {}",
+                    names.join(", ")
+                )
+            })
+    }
+
+    /// Stage new applications start in: the first one in the pipeline.
+    pub fn initial_status(&self) -> JobStatus {
+        self.stages
+            .first()
+            .map_or_else(JobStatus::default, |stage| stage.name.clone())
+    }
+
+    /// Replaces the pipeline with `stages`. `renamed` maps old stage names to
+    /// new ones, and every job and history entry in an old stage is moved
+    /// to the new name. Fails if a stage that jobs are in would disappear.
+    /// The edit is undone and redone like any other change.
+    pub fn set_stages(
+        &mut self,
+        mut stages: Vec<Stage>,
+        renamed: &[(JobStatus, JobStatus)],
+    ) -> Result<()> {
+        Stage::validate(&stages)?;
+        stages.sort_by_key(|stage| stage.order);
+
+        let rename = |status: &JobStatus| {
+            renamed
+                .iter()
+                .find(|(old, _)| old == status)
+                .map_or_else(|| status.clone(), |(_, new)| new.clone())
+        };
+        for job in &self.jobs {
+            if !stages.iter().any(|stage| stage.name == rename(&job.status)) {
+                bail!(
+                    "Can't remove stage \"{}\": job {} ({} - {}) is in it",
+                    job.status,
+                    job.id,
+                    job.company,
+                    job.role
+                );
+            }
+        }
+
+        // The rule comes from the config file, which renames don't reach
+        let ghosting_stages = self
+            .ghosting
+            .iter()
+            .flat_map(|rule| rule.statuses.iter().chain([&rule.target]));
+        for status in ghosting_stages {
+            if !stages.iter().any(|stage| &stage.name == status) {
+                bail!(
+                    "Can't remove or rename stage \"{status}\": \
+                     the ghosting rule in config.toml uses it"
+                );
+            }
+        }
+
+        // Recorded commands hold the old names, but any older command can
+        // only be undone after this one has put those names back
+        let mut changes = Vec::new();
+        for job in &mut self.jobs {
+            let before = job.clone();
+            job.status = rename(&job.status);
+            for change in &mut job.history {
+                change.from = change.from.as_ref().map(rename);
+                change.to = rename(&change.to);
+            }
+            if *job != before {
+                changes.push(JobChange {
+                    before: Some(before),
+                    after: Some(job.clone()),
+                });
+            }
+        }
+        let stage_change = (stages != self.stages).then(|| ListChange {
+            before: std::mem::replace(&mut self.stages, stages),
+            after: self.stages.clone(),
+        });
+        self.record_command(StoreCommand {
+            label: "Change stages".to_string(),
+            changes,
+            stage_change,
+            ..Default::default()
+        })
+    }
+}
@@ -1,23 +1,54 @@
 use crate::SummaryCounts;
 use std::fmt;
 
+/// Middle value of `values`, or the mean of the two middle ones for an
+/// even count. Sorts `values` in place.
+pub(crate) fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    })
+}
+
 impl fmt::Display for SummaryCounts {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let padding = " ".repeat(20);
         write!(f, "Total Applications: {}", self.total)?;
-        write!(f, "{padding}Applied: {}", self.applied)?;
-        write!(f, "{padding}Rejected: {}", self.rejected)?;
-        write!(f, "{padding}Ghosted: {}", self.ghosted)?;
-        write!(f, "{padding}Interviews: {}", self.interviews)?;
+        for count in &self.by_stage {
+            write!(f, "{padding}{}: {}", count.stage, count.count)?;
+        }
         write!(
             f,
             "{padding}Rejection Rate: {:.2}%",
-            (self.rejected as f32 / self.total as f32) * 100.0
+            self.rejection_rate.unwrap_or_default()
         )?;
         write!(
             f,
             "{padding}Interview Rate: {:.2}%",
-            (self.interviews as f32 / self.total as f32) * 100.0
-        )
+            self.interview_rate.unwrap_or_default()
+        )?;
+        if let Some(days) = self.median_days_to_response {
+            write!(f, "{padding}Median Response: {days:.1} days")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_of_odd_even_and_empty_lists() {
+        assert_eq!(median(&mut []), None);
+        assert_eq!(median(&mut [4.0]), Some(4.0));
+        assert_eq!(median(&mut [9.0, 1.0, 3.0]), Some(3.0));
+        assert_eq!(median(&mut [9.0, 1.0, 3.0, 4.0]), Some(3.5));
     }
 }
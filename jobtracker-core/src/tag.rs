@@ -0,0 +1,163 @@
+use crate::Job;
+use crate::JobStore;
+use anyhow::{bail, Result};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// `tag` trimmed, lowercased and with runs of whitespace squeezed, or
+/// `None` if that leaves nothing.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!tag.is_empty()).then(|| tag.to_lowercase())
+}
+
+/// Comma-separated tags, normalized.
+pub fn parse_tags(text: &str) -> BTreeSet<String> {
+    text.split(',').filter_map(normalize_tag).collect()
+}
+
+impl Job {
+    /// Whether the application has every one of `tags`.
+    pub fn has_tags(&self, tags: &[String]) -> bool {
+        tags.iter().all(|tag| self.tags.contains(tag))
+    }
+}
+
+impl JobStore {
+    /// Every tag on an application outside the trash, with how many
+    /// applications have it, by name.
+    pub fn all_tags(&self) -> Vec<(String, usize)> {
+        let mut tags: BTreeMap<&str, usize> = BTreeMap::new();
+        for tag in self.active_jobs().flat_map(|job| &job.tags) {
+            *tags.entry(tag).or_default() += 1;
+        }
+        tags.into_iter()
+            .map(|(tag, count)| (tag.to_string(), count))
+            .collect()
+    }
+
+    /// Existing tags for completing `partial`, most used first: those that
+    /// start with it, then those that merely contain it. Tags in `exclude`
+    /// are left out.
+    pub fn tag_suggestions(&self, partial: &str, exclude: &BTreeSet<String>) -> Vec<String> {
+        let partial = normalize_tag(partial).unwrap_or_default();
+        let mut matches: Vec<(bool, usize, String)> = self
+            .all_tags()
+            .into_iter()
+            .filter(|(tag, _)| !exclude.contains(tag) && tag.contains(&partial))
+            .map(|(tag, count)| (!tag.starts_with(&partial), count, tag))
+            .collect();
+        matches.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2)));
+        matches.into_iter().map(|(_, _, tag)| tag).collect()
+    }
+
+    /// Replaces the tags of job `id`. Tags are normalized with
+    /// [`normalize_tag`] and blank ones dropped.
+    pub fn set_tags(&mut self, id: u32, tags: impl IntoIterator<Item = String>) -> Result<()> {
+        let tags: BTreeSet<String> = tags
+            .into_iter()
+            .filter_map(|tag| normalize_tag(&tag))
+            .collect();
+        if tags.iter().any(|tag| tag.contains(',')) {
+            bail!("Tags can't contain commas");
+        }
+        self.find_job(id)?;
+        self.modify_job(id, "Change tags", |job| job.tags = tags)
+    }
+
+    pub fn add_tag(&mut self, id: u32, tag: &str) -> Result<()> {
+        self.add_tags(id, [tag])
+    }
+
+    pub fn remove_tag(&mut self, id: u32, tag: &str) -> Result<()> {
+        self.remove_tags(id, [tag])
+    }
+
+    /// Adds each of `tags` to job `id` as a single change. Fails if any is
+    /// blank.
+    pub fn add_tags<'a>(&mut self, id: u32, tags: impl IntoIterator<Item = &'a str>) -> Result<()> {
+        let mut new_tags = self.find_job(id)?.tags.clone();
+        for tag in tags {
+            let Some(tag) = normalize_tag(tag) else {
+                bail!("Tags can't be empty");
+            };
+            new_tags.insert(tag);
+        }
+        self.set_tags(id, new_tags)
+    }
+
+    /// Removes each of `tags` from job `id` as a single change.
+    pub fn remove_tags<'a>(
+        &mut self,
+        id: u32,
+        tags: impl IntoIterator<Item = &'a str>,
+    ) -> Result<()> {
+        let mut new_tags = self.find_job(id)?.tags.clone();
+        for tag in tags.into_iter().filter_map(normalize_tag) {
+            new_tags.remove(&tag);
+        }
+        self.set_tags(id, new_tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_normalized() {
+        assert_eq!(
+            normalize_tag("  Remote   First "),
+            Some("remote first".to_string())
+        );
+        assert_eq!(normalize_tag(" \t "), None);
+        let tags: Vec<String> = parse_tags("Rust, ,rust,  Dream  Job").into_iter().collect();
+        assert_eq!(tags, ["dream job", "rust"]);
+    }
+
+    #[test]
+    fn tags_are_added_and_removed_normalized() {
+        let mut store = JobStore::default();
+        store
+            .add_job("Acme".into(), "Dev".into(), String::new(), String::new())
+            .unwrap();
+        store.add_tag(1, " Rust ").unwrap();
+        store.add_tag(1, "remote").unwrap();
+        store.add_tag(1, "RUST").unwrap();
+        assert_eq!(
+            store.all_tags(),
+            [("remote".to_string(), 1), ("rust".to_string(), 1)]
+        );
+        assert!(store.add_tag(1, " ").is_err());
+        assert!(store.add_tag(2, "sql").is_err());
+        assert!(store.set_tags(1, ["a,b".to_string()]).is_err());
+        assert!(store.jobs[0].has_tags(&["rust".to_string()]));
+
+        store.remove_tag(1, "RUST").unwrap();
+        assert!(!store.jobs[0].has_tags(&["rust".to_string()]));
+        assert_eq!(store.undo().unwrap().as_deref(), Some("Change tags"));
+        assert_eq!(store.jobs[0].tags.len(), 2);
+    }
+
+    #[test]
+    fn suggestions_prefer_prefixes_then_popular_tags() {
+        let mut store = JobStore::default();
+        for tags in [
+            &["backend", "rust"][..],
+            &["rust", "trusted"],
+            &["rustacean"],
+        ] {
+            store
+                .add_job("Acme".into(), "Dev".into(), String::new(), String::new())
+                .unwrap();
+            let id = store.next_id() - 1;
+            store
+                .set_tags(id, tags.iter().map(|tag| tag.to_string()))
+                .unwrap();
+        }
+        let exclude = BTreeSet::from(["backend".to_string()]);
+        assert_eq!(
+            store.tag_suggestions(" RUST", &exclude),
+            ["rust", "rustacean", "trusted"]
+        );
+    }
+}
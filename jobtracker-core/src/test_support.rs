@@ -0,0 +1,104 @@
+//! Helpers shared by the unit tests of several modules.
+
+use crate::Contact;
+use crate::Job;
+use crate::JobStore;
+use crate::MemoryStorage;
+use crate::Source;
+use crate::Stage;
+use crate::Storage;
+use anyhow::{bail, Result};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// An in-memory store holding job 1, a Dev role at Acme.
+pub(crate) fn store_with_job() -> JobStore {
+    let mut store = JobStore::default();
+    store
+        .add_job(
+            "Acme".to_string(),
+            "Dev".to_string(),
+            "Remote".to_string(),
+            String::new(),
+        )
+        .unwrap();
+    store
+}
+
+/// In-memory storage whose writes all fail while `failing` is set, like a
+/// full disk would make them.
+#[derive(Debug, Default)]
+pub(crate) struct FailingStorage {
+    pub(crate) inner: MemoryStorage,
+    pub(crate) failing: Rc<Cell<bool>>,
+}
+
+impl FailingStorage {
+    /// A store over empty failing storage, and the switch that makes its
+    /// writes fail.
+    pub(crate) fn store() -> (JobStore, Rc<Cell<bool>>) {
+        let storage = FailingStorage::default();
+        let failing = storage.failing.clone();
+        (JobStore::new(Box::new(storage)), failing)
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.failing.get() {
+            bail!("No space left on device");
+        }
+        Ok(())
+    }
+}
+
+impl Storage for FailingStorage {
+    fn load(&mut self) -> Result<Vec<Job>> {
+        self.inner.load()
+    }
+
+    fn save(&mut self, jobs: &[Job]) -> Result<()> {
+        self.check()?;
+        self.inner.save(jobs)
+    }
+
+    fn insert(&mut self, job: &Job) -> Result<()> {
+        self.check()?;
+        self.inner.insert(job)
+    }
+
+    fn update(&mut self, job: &Job) -> Result<()> {
+        self.check()?;
+        self.inner.update(job)
+    }
+
+    fn delete(&mut self, id: u32) -> Result<()> {
+        self.check()?;
+        self.inner.delete(id)
+    }
+
+    fn load_stages(&mut self) -> Result<Vec<Stage>> {
+        self.inner.load_stages()
+    }
+
+    fn save_stages(&mut self, stages: &[Stage]) -> Result<()> {
+        self.check()?;
+        self.inner.save_stages(stages)
+    }
+
+    fn load_contacts(&mut self) -> Result<Vec<Contact>> {
+        self.inner.load_contacts()
+    }
+
+    fn save_contacts(&mut self, contacts: &[Contact]) -> Result<()> {
+        self.check()?;
+        self.inner.save_contacts(contacts)
+    }
+
+    fn load_sources(&mut self) -> Result<Vec<Source>> {
+        self.inner.load_sources()
+    }
+
+    fn save_sources(&mut self, sources: &[Source]) -> Result<()> {
+        self.check()?;
+        self.inner.save_sources(sources)
+    }
+}
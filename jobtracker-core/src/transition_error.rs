@@ -0,0 +1,17 @@
+use crate::TransitionError;
+use std::error::Error;
+use std::fmt;
+
+impl fmt::Display for TransitionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransitionError::NotAllowed { id, from, to } => write!(
+                f,
+                "Job {id} can't move from {from} to {to}. \
+                 Override the pipeline rules if this corrects a mistake."
+            ),
+        }
+    }
+}
+
+impl Error for TransitionError {}
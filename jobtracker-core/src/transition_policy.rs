@@ -0,0 +1,119 @@
+use crate::JobStatus;
+use crate::JobStore;
+use crate::Stage;
+use crate::TransitionError;
+
+impl Stage {
+    /// Whether an application may move from this stage to `to` without an
+    /// override. See [`Stage::next`] for the rule when no edges are set.
+    pub fn allows(&self, to: &Stage) -> bool {
+        match &self.next {
+            Some(next) => next.contains(&to.name),
+            None => !self.terminal && (to.order > self.order || to.terminal),
+        }
+    }
+}
+
+impl JobStore {
+    /// Stages an application in `from` may move to, in pipeline order. An
+    /// application in a stage that's no longer defined may go anywhere.
+    pub fn next_statuses(&self, from: &JobStatus) -> Vec<JobStatus> {
+        let current = self.stage(from);
+        self.stages
+            .iter()
+            .filter(|to| &to.name != from && current.is_none_or(|stage| stage.allows(to)))
+            .map(|to| to.name.clone())
+            .collect()
+    }
+
+    /// Checks a status change for job `id` against the pipeline's rules.
+    /// Staying in the same status is always allowed.
+    pub fn check_transition(
+        &self,
+        id: u32,
+        from: &JobStatus,
+        to: &JobStatus,
+    ) -> Result<(), TransitionError> {
+        if from == to || self.next_statuses(from).contains(to) {
+            return Ok(());
+        }
+        Err(TransitionError::NotAllowed {
+            id,
+            from: from.clone(),
+            to: to.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::store_with_job;
+
+    #[test]
+    fn default_pipeline_only_moves_forward() {
+        let store = JobStore::default();
+        let allowed =
+            |from: JobStatus, to: JobStatus| store.check_transition(1, &from, &to).is_ok();
+        assert!(allowed(JobStatus::APPLIED, JobStatus::INTERVIEW));
+        assert!(allowed(JobStatus::APPLIED, JobStatus::GHOSTED));
+        assert!(allowed(JobStatus::INTERVIEW, JobStatus::REJECTED));
+        assert!(allowed(JobStatus::OFFER, JobStatus::OFFER));
+        assert!(!allowed(JobStatus::INTERVIEW, JobStatus::APPLIED));
+        assert!(!allowed(JobStatus::OFFER, JobStatus::APPLIED));
+        assert!(!allowed(JobStatus::REJECTED, JobStatus::OFFER));
+        assert!(!allowed(JobStatus::GHOSTED, JobStatus::INTERVIEW));
+        assert!(!allowed(JobStatus::GHOSTED, JobStatus::APPLIED));
+
+        let error = store
+            .check_transition(7, &JobStatus::REJECTED, &JobStatus::OFFER)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            TransitionError::NotAllowed { id: 7, ref from, ref to }
+                if *from == JobStatus::REJECTED && *to == JobStatus::OFFER
+        ));
+    }
+
+    #[test]
+    fn unknown_stages_may_go_anywhere() {
+        let store = JobStore::default();
+        let from = JobStatus::new("Phone screen");
+        assert_eq!(store.next_statuses(&from).len(), store.stages.len());
+    }
+
+    #[test]
+    fn corrections_skip_the_rules() {
+        let mut store = store_with_job();
+        store.update_status(1, JobStatus::REJECTED).unwrap();
+
+        let error = store.update_status(1, JobStatus::OFFER).unwrap_err();
+        assert!(error.downcast_ref::<TransitionError>().is_some());
+        assert_eq!(store.jobs[0].status, JobStatus::REJECTED);
+
+        store
+            .update_status_with_note(1, JobStatus::OFFER, Some("Misclicked".into()), true)
+            .unwrap();
+        let change = store.jobs[0].history.last().unwrap();
+        assert_eq!(change.to, JobStatus::OFFER);
+        assert!(change.correction);
+        assert_eq!(change.note.as_deref(), Some("Misclicked"));
+
+        assert!(store
+            .update_status_with_note(1, JobStatus::new("Hired"), None, true)
+            .is_err());
+    }
+
+    #[test]
+    fn custom_edges_replace_the_default_rule() {
+        let mut store = store_with_job();
+        let mut stages = store.stages.clone();
+        stages[0].next = Some(vec![JobStatus::REJECTED]);
+        store.set_stages(stages, &[]).unwrap();
+        assert_eq!(
+            store.next_statuses(&JobStatus::APPLIED),
+            [JobStatus::REJECTED]
+        );
+        assert!(store.update_status(1, JobStatus::INTERVIEW).is_err());
+    }
+}
@@ -0,0 +1,258 @@
+use crate::json_file_storage::write_atomically;
+use crate::ContactChange;
+use crate::JobChange;
+use crate::ListChange;
+use crate::StoreCommand;
+use crate::UndoConfig;
+use crate::UndoHistory;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+const DEFAULT_UNDO_LIMIT: usize = 100;
+
+/// Largest undo history file written. Commands hold whole copies of the
+/// applications they touched, so a long history of edits to applications
+/// with archived postings can get big; the oldest commands are left out
+/// of the file to stay under this.
+const MAX_SAVED_BYTES: usize = 4 * 1024 * 1024;
+
+/// The part of an [`UndoHistory`] that's saved, laid out the same way.
+#[derive(Serialize)]
+struct SavedHistory<'a> {
+    undo: Vec<&'a StoreCommand>,
+    redo: Vec<&'a StoreCommand>,
+}
+
+impl StoreCommand {
+    /// The command that takes every change made by this one back, in
+    /// reverse order.
+    pub fn inverse(&self) -> StoreCommand {
+        StoreCommand {
+            label: self.label.clone(),
+            changes: self
+                .changes
+                .iter()
+                .rev()
+                .map(|change| JobChange {
+                    before: change.after.clone(),
+                    after: change.before.clone(),
+                })
+                .collect(),
+            contact_changes: self
+                .contact_changes
+                .iter()
+                .rev()
+                .map(|change| ContactChange {
+                    before: change.after.clone(),
+                    after: change.before.clone(),
+                })
+                .collect(),
+            stage_change: self.stage_change.as_ref().map(ListChange::inverse),
+            source_change: self.source_change.as_ref().map(ListChange::inverse),
+        }
+    }
+}
+
+impl<T: Clone> ListChange<T> {
+    /// The change that puts `before` back.
+    pub fn inverse(&self) -> ListChange<T> {
+        ListChange {
+            before: self.after.clone(),
+            after: self.before.clone(),
+        }
+    }
+}
+
+impl Default for UndoConfig {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_UNDO_LIMIT,
+            persist: false,
+        }
+    }
+}
+
+impl UndoHistory {
+    /// An empty history keeping at most `limit` commands, saved to `path`
+    /// after every change if given.
+    pub fn new(limit: usize, path: Option<PathBuf>) -> Self {
+        Self {
+            limit,
+            path,
+            ..Default::default()
+        }
+    }
+
+    /// Reads the saved history from `path`, if any. A missing or unreadable
+    /// file just means starting over: losing undo history shouldn't keep the
+    /// tracker from opening.
+    pub fn load(&mut self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let saved = fs::read_to_string(path)
+            .ok()
+            .and_then(|data| serde_json::from_str::<UndoHistory>(&data).ok());
+        if let Some(saved) = saved {
+            self.undo = saved.undo;
+            self.redo = saved.redo;
+            self.trim();
+        }
+    }
+
+    /// Writes the history to its file, if it has one, replacing the file
+    /// in one step. If it would be bigger than [`MAX_SAVED_BYTES`], the
+    /// oldest commands to undo, then the furthest ones to redo, are left
+    /// out of the file.
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let (mut undo_from, mut redo_from) = (0, 0);
+        let data = loop {
+            let data = serde_json::to_vec(&SavedHistory {
+                undo: self.undo.iter().skip(undo_from).collect(),
+                redo: self.redo.iter().skip(redo_from).collect(),
+            })?;
+            if data.len() <= MAX_SAVED_BYTES
+                || redo_from == self.redo.len() && undo_from == self.undo.len()
+            {
+                break data;
+            }
+            if undo_from < self.undo.len() {
+                undo_from += 1;
+            } else {
+                redo_from += 1;
+            }
+        };
+        write_atomically(path, &data)
+            .with_context(|| format!("Could not save undo history to {}", path.display()))
+    }
+
+    /// Records a new command. Anything that could be redone is dropped.
+    pub fn push(&mut self, command: StoreCommand) {
+        self.undo.push_back(command);
+        self.redo.clear();
+        self.trim();
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    fn trim(&mut self) {
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Job;
+    use crate::JobChange;
+
+    /// A command adding a job whose description is `size` bytes long.
+    fn command(label: &str, size: usize) -> StoreCommand {
+        StoreCommand {
+            label: label.to_string(),
+            changes: vec![JobChange {
+                before: None,
+                after: Some(Job {
+                    description: Some("x".repeat(size)),
+                    ..Job::default()
+                }),
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn labels<'a>(commands: impl IntoIterator<Item = &'a StoreCommand>) -> Vec<&'a str> {
+        commands.into_iter().map(|c| c.label.as_str()).collect()
+    }
+
+    /// Saves `history` to a fresh file and reads it back with `limit`.
+    fn round_trip(name: &str, history: &mut UndoHistory, limit: usize) -> UndoHistory {
+        let path = std::env::temp_dir().join(format!(
+            "jobtracker-test-{name}-{}.json",
+            std::process::id()
+        ));
+        history.path = Some(path.clone());
+        history.save().unwrap();
+        let mut read = UndoHistory::new(limit, Some(path.clone()));
+        read.load();
+        fs::remove_file(path).unwrap();
+        read
+    }
+
+    #[test]
+    fn pushing_trims_and_drops_redo() {
+        let mut history = UndoHistory::new(2, None);
+        for label in ["a", "b", "c"] {
+            history.push(command(label, 0));
+        }
+        assert_eq!(labels(&history.undo), ["b", "c"]);
+        history.redo.push(command("d", 0));
+        history.push(command("e", 0));
+        assert_eq!(labels(&history.undo), ["c", "e"]);
+        assert!(!history.can_redo());
+        assert!(history.save().is_ok());
+    }
+
+    #[test]
+    fn saved_history_reads_back() {
+        let mut history = UndoHistory::new(10, None);
+        for label in ["a", "b", "c"] {
+            history.push(command(label, 10));
+        }
+        history.redo.push(command("d", 10));
+        let read = round_trip("undo-read-back", &mut history, 2);
+        assert_eq!(labels(&read.undo), ["b", "c"]);
+        assert_eq!(labels(&read.redo), ["d"]);
+    }
+
+    #[test]
+    fn big_histories_leave_out_the_oldest_commands() {
+        for (tenths, undo, redo) in [(3, &["c"][..], &["e", "d"][..]), (6, &[], &["d"])] {
+            let size = MAX_SAVED_BYTES * tenths / 10;
+            let mut history = UndoHistory::new(10, None);
+            for label in ["a", "b", "c"] {
+                history.push(command(label, size));
+            }
+            history
+                .redo
+                .extend([command("e", size), command("d", size)]);
+            let read = round_trip("undo-size-cap", &mut history, 10);
+            assert_eq!(labels(&read.undo), undo);
+            assert_eq!(labels(&read.redo), redo);
+            assert_eq!(history.undo.len(), 3);
+        }
+    }
+
+    #[test]
+    fn missing_or_corrupt_files_start_over() {
+        let path = std::env::temp_dir().join(format!(
+            "jobtracker-test-undo-corrupt-{}.json",
+            std::process::id()
+        ));
+        fs::write(&path, "{\"undo\": [").unwrap();
+        let mut history = UndoHistory::new(10, Some(path.clone()));
+        history.load();
+        fs::remove_file(&path).unwrap();
+        assert!(!history.can_undo());
+        history.load();
+        assert!(!history.can_undo());
+    }
+}
@@ -0,0 +1,201 @@
+use crate::content_line::{components, escape, parse_line, unescape, unfold, write_line};
+use crate::Contact;
+use crate::ContactChange;
+use crate::ContactImport;
+use crate::JobStore;
+use crate::StoreCommand;
+use anyhow::Result;
+use std::collections::HashSet;
+use std::io::{Read, Write};
+
+impl Contact {
+    /// The contact as a vCard 3.0 card. Links to jobs aren't part of it.
+    pub fn to_vcard(&self) -> String {
+        let mut card = String::new();
+        write_line(&mut card, "BEGIN:VCARD");
+        write_line(&mut card, "VERSION:3.0");
+        write_line(&mut card, &format!("FN:{}", escape(&self.name)));
+        // N is required in 3.0. Guess family and given names from the full one.
+        let (given, family) = self.name.rsplit_once(' ').unwrap_or(("", &self.name));
+        write_line(
+            &mut card,
+            &format!("N:{};{};;;", escape(family), escape(given)),
+        );
+        let fields = [
+            ("EMAIL;TYPE=INTERNET", &self.email),
+            ("TEL", &self.phone),
+            ("TITLE", &self.role),
+            ("ORG", &self.company),
+            ("NOTE", &self.notes),
+        ];
+        for (property, value) in fields {
+            if let Some(value) = value {
+                write_line(&mut card, &format!("{property}:{}", escape(value)));
+            }
+        }
+        write_line(&mut card, "END:VCARD");
+        card
+    }
+
+    /// Reads every card in `text`, vCard 2.1, 3.0 or 4.0. Cards with neither
+    /// a name nor an email address are left out. The returned contacts have
+    /// no ID yet.
+    pub fn parse_vcards(text: &str) -> Vec<Contact> {
+        let mut contacts = Vec::new();
+        let mut card: Option<(Contact, Option<String>)> = None;
+        for line in unfold(text).lines() {
+            let Some((property, _, value)) = parse_line(line) else {
+                continue;
+            };
+            match (property.as_str(), &mut card) {
+                ("BEGIN", _) if value.eq_ignore_ascii_case("VCARD") => {
+                    card = Some((Contact::default(), None));
+                }
+                ("END", Some(_)) if value.eq_ignore_ascii_case("VCARD") => {
+                    let (mut contact, structured_name) = card.take().unwrap();
+                    if contact.name.is_empty() {
+                        contact.name = structured_name
+                            .or_else(|| contact.email.clone())
+                            .unwrap_or_default();
+                    }
+                    if !contact.name.is_empty() {
+                        contacts.push(contact);
+                    }
+                }
+                ("FN", Some((contact, _))) => contact.name = unescape(value).trim().to_string(),
+                ("N", Some((_, structured_name))) => {
+                    let parts = components(value);
+                    let given = parts.get(1).map_or("", |s| s.trim());
+                    let family = parts.first().map_or("", |s| s.trim());
+                    let full = format!("{given} {family}").trim().to_string();
+                    if !full.is_empty() {
+                        *structured_name = Some(full);
+                    }
+                }
+                ("EMAIL", Some((contact, _))) if contact.email.is_none() => {
+                    contact.email = Some(unescape(value).trim().to_string());
+                }
+                ("TEL", Some((contact, _))) if contact.phone.is_none() => {
+                    let phone = unescape(value);
+                    contact.phone = Some(phone.trim_start_matches("tel:").trim().to_string());
+                }
+                ("TITLE", Some((contact, _))) => contact.role = Some(unescape(value)),
+                ("ROLE", Some((contact, _))) if contact.role.is_none() => {
+                    contact.role = Some(unescape(value));
+                }
+                ("ORG", Some((contact, _))) => {
+                    contact.company = components(value).into_iter().next();
+                }
+                ("NOTE", Some((contact, _))) => contact.notes = Some(unescape(value)),
+                _ => {}
+            }
+        }
+        contacts
+    }
+}
+
+/// Two contacts count as the same if they share an email address, or a
+/// name when there's no email.
+fn duplicate_key(contact: &Contact) -> String {
+    contact
+        .email
+        .as_deref()
+        .unwrap_or(&contact.name)
+        .trim()
+        .to_lowercase()
+}
+
+impl JobStore {
+    /// Adds every contact in a `.vcf` file, skipping ones already in the
+    /// store. The whole import is undone as one command.
+    pub fn import_vcf<R: Read>(&mut self, mut reader: R) -> Result<ContactImport> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let mut report = ContactImport::default();
+        let mut seen: HashSet<String> = self.contacts.iter().map(duplicate_key).collect();
+        let mut next_id = self.next_contact_id();
+        for mut contact in Contact::parse_vcards(&text) {
+            if !seen.insert(duplicate_key(&contact)) {
+                report.duplicates.push(contact.name);
+                continue;
+            }
+            contact.id = next_id;
+            next_id += 1;
+            report.imported.push(contact);
+        }
+
+        self.contacts.extend(report.imported.iter().cloned());
+        let contact_changes = report
+            .imported
+            .iter()
+            .map(|contact| ContactChange {
+                before: None,
+                after: Some(contact.clone()),
+            })
+            .collect();
+        self.record_command(StoreCommand {
+            label: "Import contacts".to_string(),
+            contact_changes,
+            ..Default::default()
+        })?;
+        Ok(report)
+    }
+
+    /// Writes every contact as a vCard 3.0 file.
+    pub fn export_vcf<W: Write>(&self, mut writer: W) -> Result<()> {
+        for contact in &self.contacts {
+            writer.write_all(contact.to_vcard().as_bytes())?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_cards_of_each_version() {
+        let text = "BEGIN:VCARD\r\nVERSION:2.1\r\nN:Doe;Jane;;;\r\nTEL;WORK:555-0100\r\nORG:Acme;Hiring\r\nEND:VCARD\r\n\
+BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Sam Lee\r\nEMAIL;TYPE=work:sam@example.com\r\nEMAIL:other@example.com\r\nTEL;VALUE=uri:tel:+1-555-0101\r\nTITLE:Recruiter\r\nNOTE:Met at the fair\\, liked Rust\r\nEND:VCARD\r\n\
+BEGIN:VCARD\r\nVERSION:3.0\r\nEMAIL:nameless@example.com\r\nEND:VCARD\r\n\
+BEGIN:VCARD\r\nVERSION:3.0\r\nTITLE:Nobody\r\nEND:VCARD\r\n";
+        let contacts = Contact::parse_vcards(text);
+        let names: Vec<&str> = contacts.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Jane Doe", "Sam Lee", "nameless@example.com"]);
+        assert_eq!(contacts[0].phone.as_deref(), Some("555-0100"));
+        assert_eq!(contacts[0].company.as_deref(), Some("Acme"));
+        assert_eq!(contacts[1].email.as_deref(), Some("sam@example.com"));
+        assert_eq!(contacts[1].phone.as_deref(), Some("+1-555-0101"));
+        assert_eq!(contacts[1].role.as_deref(), Some("Recruiter"));
+        assert_eq!(
+            contacts[1].notes.as_deref(),
+            Some("Met at the fair, liked Rust")
+        );
+    }
+
+    #[test]
+    fn export_reads_back_and_import_skips_duplicates() {
+        let mut store = JobStore::default();
+        store
+            .add_contact(Contact {
+                name: "Jane Doe".to_string(),
+                email: Some("jane@example.com".to_string()),
+                notes: Some("Line one\nLine two; with a semicolon".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        let mut file = Vec::new();
+        store.export_vcf(&mut file).unwrap();
+        let read = Contact::parse_vcards(std::str::from_utf8(&file).unwrap());
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].name, "Jane Doe");
+        assert_eq!(read[0].notes, store.contacts[0].notes);
+
+        let report = store.import_vcf(&file[..]).unwrap();
+        assert!(report.imported.is_empty());
+        assert_eq!(report.duplicates, ["Jane Doe"]);
+        assert_eq!(store.contacts.len(), 1);
+    }
+}
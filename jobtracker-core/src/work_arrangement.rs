@@ -0,0 +1,25 @@
+use crate::WorkArrangement;
+use std::fmt;
+use std::str::FromStr;
+
+impl fmt::Display for WorkArrangement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorkArrangement::Onsite => write!(f, "On-site"),
+            WorkArrangement::Hybrid => write!(f, "Hybrid"),
+            WorkArrangement::Remote => write!(f, "Remote"),
+        }
+    }
+}
+
+impl FromStr for WorkArrangement {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "on-site" | "onsite" | "on site" | "office" => Ok(WorkArrangement::Onsite),
+            "hybrid" => Ok(WorkArrangement::Hybrid),
+            "remote" => Ok(WorkArrangement::Remote),
+            _ => Err(format!("Unknown work arrangement: {s}")),
+        }
+    }
+}
@@ -0,0 +1,1345 @@
+mod compensation;
+mod contacts;
+mod ghosting;
+mod interviews;
+mod location;
+mod notes;
+mod pipeline_flow;
+mod posting;
+mod source_editor;
+mod stage_editor;
+mod trash;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::America::New_York;
+use eframe::egui::{self, Align, Layout, TextEdit, Ui};
+use eframe::egui::{Color32, Stroke};
+use egui_plot::PlotPoint;
+use egui_plot::{Bar, BarChart, Legend, Plot, Text};
+use std::collections::HashMap;
+
+use crate::{CompensationForm, JobApp, PendingConfirmation, StatusColor};
+use jobtracker_core::{
+    parse_tags, AreaCounts, ArrangementCounts, Job, JobSource, JobStatus, Source, SourceCounts,
+    SummaryCounts, TagCounts, WorkArrangement,
+};
+use strum::IntoEnumIterator;
+
+pub const DEFAULT_FIELD_ELEMENT_HEIGHT: f32 = 20.0;
+pub const COLUMN_HEADER_AND_WIDTH_FIELDS: [(&str, f32); 9] = [
+    ("ID", 50.0),
+    ("Date Applied", 180.0),
+    ("Company", 120.0),
+    ("Role", 120.0),
+    ("Location", 100.0),
+    ("Status", 100.0),
+    ("Source", 60.0),
+    ("Tags", 140.0),
+    ("Action", 120.0),
+];
+
+impl JobApp {
+    fn add_search_box(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Search:");
+            ui.add(
+                TextEdit::singleline(&mut self.search_text)
+                    .desired_width(ui.available_width() * 0.3),
+            );
+        });
+        self.add_tag_filter(ui);
+        self.add_location_filter(ui);
+        if let Some((label, job_ids)) = &self.flow_filter {
+            let mut clear = false;
+            ui.horizontal(|ui| {
+                ui.label(format!("Showing: {label} ({})", job_ids.len()));
+                clear = ui
+                    .small_button("x")
+                    .on_hover_text("Show every application again")
+                    .clicked();
+            });
+            if clear {
+                self.flow_filter = None;
+            }
+        }
+    }
+
+    /// Tags the job list is filtered by, each removable, and a picker to
+    /// add another.
+    fn add_tag_filter(&mut self, ui: &mut Ui) {
+        let tags = self.store.all_tags();
+        if tags.is_empty() && self.tag_filter.is_empty() {
+            return;
+        }
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Tags:");
+            let mut to_remove = None;
+            for tag in &self.tag_filter {
+                if ui
+                    .small_button(format!("{tag} x"))
+                    .on_hover_text("Stop filtering by this tag")
+                    .clicked()
+                {
+                    to_remove = Some(tag.clone());
+                }
+            }
+            if let Some(tag) = to_remove {
+                self.tag_filter.retain(|t| *t != tag);
+            }
+            egui::ComboBox::from_id_source("tag_filter")
+                .selected_text("Filter by tag...")
+                .show_ui(ui, |ui| {
+                    for (tag, count) in tags {
+                        if !self.tag_filter.contains(&tag)
+                            && ui
+                                .selectable_label(false, format!("{tag} ({count})"))
+                                .clicked()
+                        {
+                            self.tag_filter.push(tag);
+                        }
+                    }
+                });
+        });
+    }
+
+    /// Work arrangement and region pickers for the job list.
+    fn add_location_filter(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Arrangement:");
+            let selected = self
+                .arrangement_filter
+                .map_or("Any".to_string(), |a| a.to_string());
+            egui::ComboBox::from_id_source("arrangement_filter")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.arrangement_filter, None, "Any");
+                    for arrangement in WorkArrangement::iter() {
+                        ui.selectable_value(
+                            &mut self.arrangement_filter,
+                            Some(arrangement),
+                            arrangement.to_string(),
+                        );
+                    }
+                });
+            ui.label("Region:");
+            let selected = self.area_filter.clone().unwrap_or("Any".to_string());
+            egui::ComboBox::from_id_source("area_filter")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.area_filter, None, "Any");
+                    for (area, count) in self.store.areas() {
+                        let label = format!("{area} ({count})");
+                        ui.selectable_value(&mut self.area_filter, Some(area), label);
+                    }
+                });
+        });
+    }
+
+    /// Per-tag versions of the headline numbers: how many applications
+    /// have each tag and how they've fared.
+    fn add_tag_stats(&mut self, ui: &mut Ui) {
+        ui.collapsing("Tag statistics", |ui| {
+            let by_tag = &self.store.summary_stats.by_tag;
+            if by_tag.is_empty() {
+                ui.label("No tags yet");
+                return;
+            }
+            let rows = by_tag
+                .iter()
+                .map(|TagCounts { tag, counts }| (tag.clone(), counts));
+            add_breakdown_grid(ui, "tag_stats", "Tag", rows);
+        });
+    }
+
+    /// The headline numbers by where applications came from, as a table and
+    /// as a chart of each source's rates side by side.
+    fn add_source_stats(&mut self, ui: &mut Ui) {
+        ui.collapsing("Source statistics", |ui| {
+            let by_source = &self.store.summary_stats.by_source;
+            if by_source.is_empty() {
+                ui.label("No applications yet");
+                return;
+            }
+            let name = |source: &Option<JobSource>| {
+                source
+                    .as_ref()
+                    .map_or_else(|| "Not provided".to_string(), |s| s.to_string())
+            };
+            let rows = by_source
+                .iter()
+                .map(|SourceCounts { source, counts }| (name(source), counts));
+            add_breakdown_grid(ui, "source_stats", "Source", rows);
+            ui.add_space(8.0);
+
+            let series = [
+                ("Interview", Color32::LIGHT_BLUE),
+                ("Offer", Color32::GREEN),
+                ("Rejection", Color32::RED),
+            ];
+            let rates = |c: &SummaryCounts| [c.interview_rate, c.offer_rate, c.rejection_rate];
+            Plot::new("source_chart")
+                .legend(Legend::default())
+                .include_y(0.0)
+                .include_y(100.0)
+                .show_x(false)
+                .show_axes([false, true])
+                .allow_drag(false)
+                .allow_scroll(false)
+                .allow_zoom(false)
+                .height(200.0)
+                .show(ui, |plot_ui| {
+                    for (idx, (rate_name, color)) in series.into_iter().enumerate() {
+                        let offset = (idx as f64 - 1.0) * 0.25;
+                        let bars = by_source
+                            .iter()
+                            .enumerate()
+                            .map(|(x, breakdown)| {
+                                Bar::new(
+                                    x as f64 + offset,
+                                    rates(&breakdown.counts)[idx].unwrap_or_default(),
+                                )
+                                .width(0.25)
+                                .name(name(&breakdown.source))
+                            })
+                            .collect();
+                        plot_ui.bar_chart(BarChart::new(bars).color(color).name(rate_name));
+                    }
+                    for (x, breakdown) in by_source.iter().enumerate() {
+                        plot_ui.text(
+                            Text::new(PlotPoint::new(x as f64, -4.0), name(&breakdown.source))
+                                .color(Color32::GRAY)
+                                .anchor(egui::Align2::CENTER_TOP),
+                        );
+                    }
+                });
+        });
+    }
+
+    /// The headline numbers by work arrangement and by region.
+    fn add_location_stats(&mut self, ui: &mut Ui) {
+        ui.collapsing("Location statistics", |ui| {
+            let stats = &self.store.summary_stats;
+            let by_arrangement = stats.by_arrangement.iter().map(
+                |ArrangementCounts {
+                     arrangement,
+                     counts,
+                 }| {
+                    let name = arrangement.map_or_else(|| "Unknown".to_string(), |a| a.to_string());
+                    (name, counts)
+                },
+            );
+            add_breakdown_grid(ui, "arrangement_stats", "Arrangement", by_arrangement);
+            ui.add_space(8.0);
+            if stats.by_area.is_empty() {
+                ui.label("No known regions yet");
+                return;
+            }
+            let by_area = stats
+                .by_area
+                .iter()
+                .map(|AreaCounts { area, counts }| (area.clone(), counts));
+            add_breakdown_grid(ui, "area_stats", "Region", by_area);
+        });
+    }
+
+    /// Comma-separated tags of `job` in its row, committed on Enter or when
+    /// the field loses focus. While it has focus, existing tags that
+    /// complete the last one typed are offered below it.
+    fn add_tag_editor(&mut self, ui: &mut Ui, job: &Job) -> Option<(u32, String)> {
+        let text = self
+            .edit_tags
+            .entry(job.id)
+            .or_insert_with(|| job.tags.iter().cloned().collect::<Vec<_>>().join(", "));
+        let response = ui.add_sized(
+            [
+                COLUMN_HEADER_AND_WIDTH_FIELDS[7].1,
+                DEFAULT_FIELD_ELEMENT_HEIGHT,
+            ],
+            TextEdit::singleline(text).hint_text("Add tags"),
+        );
+
+        let (done, partial) = text.rsplit_once(',').unwrap_or(("", text.as_str()));
+        let partial = partial.trim();
+        let suggestions = if partial.is_empty() {
+            Vec::new()
+        } else {
+            let typed = parse_tags(done);
+            let mut suggestions = self.store.tag_suggestions(partial, &typed);
+            suggestions.retain(|tag| tag != partial);
+            suggestions.truncate(8);
+            suggestions
+        };
+        let popup_id = ui.make_persistent_id(("tag_suggestions", job.id));
+        if response.has_focus() && !suggestions.is_empty() {
+            ui.memory_mut(|memory| memory.open_popup(popup_id));
+        }
+        let done = done.to_string();
+        let picked = egui::popup_below_widget(
+            ui,
+            popup_id,
+            &response,
+            egui::PopupCloseBehavior::CloseOnClickOutside,
+            |ui| {
+                suggestions
+                    .iter()
+                    .find(|tag| ui.selectable_label(false, tag.as_str()).clicked())
+                    .cloned()
+            },
+        )
+        .flatten();
+
+        let text = self.edit_tags.get_mut(&job.id).unwrap();
+        if let Some(tag) = picked {
+            *text = if done.trim().is_empty() {
+                format!("{tag}, ")
+            } else {
+                format!("{}, {tag}, ", done.trim_end())
+            };
+            ui.memory_mut(|memory| memory.close_popup());
+            return Some((job.id, text.clone()));
+        }
+        let pressed_enter = response.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        if response.lost_focus() || pressed_enter {
+            ui.memory_mut(|memory| memory.close_popup());
+            return Some((job.id, text.clone()));
+        }
+        None
+    }
+
+    fn add_refresh_button(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            if ui.add(egui::Button::new("Refresh")).clicked() {
+                match self.store.load() {
+                    Ok(()) => {
+                        self.last_refresh = Utc::now();
+                        self.refresh_message = None;
+                    }
+                    Err(e) => self.refresh_message = Some(format!("Refresh failed: {e:#}")),
+                }
+            }
+            ui.label(format!(
+                "Last Refresh: {}",
+                self.last_refresh
+                    .with_timezone(&New_York)
+                    .format("%Y-%m-%d %H:%M:%S")
+            ));
+        });
+        if let Some(message) = &self.refresh_message {
+            ui.colored_label(Color32::RED, message);
+        }
+        if let Some(warning) = &self.data_file_warning {
+            ui.colored_label(ui.visuals().warn_fg_color, warning);
+        }
+    }
+
+    fn add_undo_controls(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let history = &self.store.undo_history;
+            let undo_label = history.undo.back().map(|command| command.label.clone());
+            let redo_label = history.redo.last().map(|command| command.label.clone());
+            let undo = ui
+                .add_enabled(undo_label.is_some(), egui::Button::new("Undo"))
+                .on_hover_text(undo_label.unwrap_or_default());
+            if undo.clicked() {
+                self.undo(false);
+            }
+            let redo = ui
+                .add_enabled(redo_label.is_some(), egui::Button::new("Redo"))
+                .on_hover_text(redo_label.unwrap_or_default());
+            if redo.clicked() {
+                self.undo(true);
+            }
+            if let Some(message) = &self.undo_message {
+                ui.label(message);
+            }
+        });
+    }
+
+    /// Ctrl+Z / Ctrl+Shift+Z. Left to the text field when one has focus so
+    /// they keep working inside it.
+    fn handle_undo_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.memory(|memory| memory.focused().is_some()) {
+            return;
+        }
+        let redo = egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+            egui::Key::Z,
+        );
+        let undo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+        // Check the longer shortcut first, it also matches the shorter one
+        if ctx.input_mut(|input| input.consume_shortcut(&redo)) {
+            self.undo(true);
+        } else if ctx.input_mut(|input| input.consume_shortcut(&undo)) {
+            self.undo(false);
+        }
+    }
+
+    fn undo(&mut self, redo: bool) {
+        let result = if redo {
+            self.store.redo()
+        } else {
+            self.store.undo()
+        };
+        self.undo_message = match result {
+            Ok(Some(label)) if redo => Some(format!("Redid: {label}")),
+            Ok(Some(label)) => Some(format!("Undid: {label}")),
+            Ok(None) => None,
+            Err(e) => Some(format!("{e:#}")),
+        };
+        // Pending edits refer to values that may no longer be there
+        self.edit_timestamps.clear();
+        self.edit_companies.clear();
+        self.edit_tags.clear();
+    }
+
+    /// Opens the details panel on job `id` with its inputs cleared.
+    fn show_details(&mut self, id: u32) {
+        self.detail_job = Some(id);
+        self.detail_status = None;
+        self.detail_note.clear();
+        self.detail_correction = false;
+        self.detail_message = None;
+        self.new_note.clear();
+        self.editing_note = None;
+        self.note_message = None;
+        self.interview_message = None;
+        self.location_form = None;
+        self.location_message = None;
+        self.posting_edit = None;
+        self.posting_message = None;
+        self.compensation_form = self
+            .store
+            .jobs
+            .iter()
+            .find(|job| job.id == id)
+            .map(CompensationForm::from_job)
+            .unwrap_or_default();
+        self.compensation_message = None;
+    }
+
+    fn add_csv_controls(&mut self, ui: &mut Ui) {
+        ui.collapsing("CSV import/export", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.add(TextEdit::singleline(&mut self.csv_path).hint_text("applications.csv"));
+            });
+            ui.collapsing("Column names", |ui| {
+                egui::Grid::new("csv_columns").show(ui, |ui| {
+                    let columns = &mut self.csv_columns;
+                    for (label, value) in [
+                        ("Company", &mut columns.company),
+                        ("Role", &mut columns.role),
+                        ("Location", &mut columns.location),
+                        ("Status", &mut columns.status),
+                        ("Source", &mut columns.source),
+                        ("Date applied", &mut columns.date_applied),
+                        ("Notes", &mut columns.notes),
+                        ("Tags", &mut columns.tags),
+                    ] {
+                        ui.label(label);
+                        ui.text_edit_singleline(value);
+                        ui.end_row();
+                    }
+                });
+            });
+
+            ui.horizontal(|ui| {
+                let path = self.csv_path.trim().to_string();
+                if ui.button("Preview import").clicked() {
+                    self.run_csv_import(&path, true);
+                }
+                if ui.button("Import").clicked() {
+                    self.run_csv_import(&path, false);
+                }
+                if ui.button("Export").clicked() {
+                    let result = std::fs::File::create(&path)
+                        .map_err(anyhow::Error::from)
+                        .and_then(|file| self.store.export_csv(file, &self.csv_columns));
+                    self.csv_report = None;
+                    self.csv_message = Some(match result {
+                        Ok(()) => {
+                            format!("Exported {} applications", self.store.active_jobs().count())
+                        }
+                        Err(e) => format!("Export failed: {e:#}"),
+                    });
+                }
+            });
+
+            if let Some(message) = &self.csv_message {
+                ui.label(message);
+            }
+            if let Some(report) = &self.csv_report {
+                egui::ScrollArea::vertical()
+                    .id_source("csv_report")
+                    .max_height(150.0)
+                    .show(ui, |ui| {
+                        for row in &report.skipped {
+                            ui.colored_label(
+                                Color32::RED,
+                                format!("Line {}: skipped, {}", row.line, row.reason),
+                            );
+                        }
+                        for row in &report.duplicates {
+                            ui.colored_label(
+                                Color32::YELLOW,
+                                format!("Line {}: duplicate, {}", row.line, row.reason),
+                            );
+                        }
+                    });
+            }
+        });
+    }
+
+    fn run_csv_import(&mut self, path: &str, dry_run: bool) {
+        let result = std::fs::File::open(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|file| self.store.import_csv(file, &self.csv_columns, dry_run));
+        match result {
+            Ok(report) => {
+                let verb = if dry_run { "Would import" } else { "Imported" };
+                self.csv_message = Some(format!(
+                    "{verb} {}, skip {}, {} duplicate(s)",
+                    report.imported.len(),
+                    report.skipped.len(),
+                    report.duplicates.len()
+                ));
+                self.csv_report = Some(report);
+            }
+            Err(e) => {
+                self.csv_message = Some(format!("Import failed: {e:#}"));
+                self.csv_report = None;
+            }
+        }
+    }
+
+    fn add_backup_controls(&mut self, ui: &mut Ui) {
+        ui.collapsing("Backups", |ui| {
+            let mut to_restore: Option<usize> = None;
+            match self.store.backups() {
+                Ok(backups) if backups.is_empty() => {
+                    ui.label("No backups yet");
+                }
+                Ok(backups) => {
+                    for backup in backups {
+                        ui.horizontal(|ui| {
+                            ui.label(format!(
+                                "#{}: {}",
+                                backup.index,
+                                backup
+                                    .taken
+                                    .with_timezone(&New_York)
+                                    .format("%Y-%m-%d %H:%M:%S")
+                            ));
+                            if ui.button("Restore").clicked() {
+                                to_restore = Some(backup.index);
+                            }
+                        });
+                    }
+                }
+                Err(e) => {
+                    ui.colored_label(Color32::RED, e.to_string());
+                }
+            }
+
+            if let Some(index) = to_restore {
+                match self.store.restore_backup(index) {
+                    Ok(_) => {
+                        // Edit buffers are keyed by job ID and would show
+                        // stale values
+                        self.edit_timestamps.clear();
+                        self.edit_companies.clear();
+                        self.edit_tags.clear();
+                        self.last_refresh = Utc::now();
+                        self.backup_message = Some(format!("Restored backup #{index}"));
+                    }
+                    Err(e) => self.backup_message = Some(format!("Restore failed: {e:#}")),
+                }
+            }
+            if let Some(message) = &self.backup_message {
+                ui.label(message);
+            }
+        });
+    }
+
+    fn add_job_app_input_form(&mut self, ui: &mut Ui) {
+        ui.with_layout(Layout::top_down(Align::Center), |ui| {
+            ui.vertical(|ui| {
+                let label_width = 80.0;
+                let field_width = (ui.available_width() / 2.0) - label_width - 10.0;
+
+                ui.horizontal(|ui| {
+                    ui.add_sized([label_width, 20.0], egui::Label::new("Company:"));
+                    ui.add_sized(
+                        [field_width, 20.0],
+                        TextEdit::singleline(&mut self.new_company),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.add_sized([label_width, 20.0], egui::Label::new("Role:"));
+                    ui.add_sized(
+                        [field_width, 20.0],
+                        TextEdit::singleline(&mut self.new_role),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.add_sized([label_width, 20.0], egui::Label::new("Location:"));
+                    ui.add_sized(
+                        [field_width, 20.0],
+                        TextEdit::singleline(&mut self.new_role_location),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.add_sized([label_width, 20.0], egui::Label::new("Source:"));
+                    ui.add_sized(
+                        [field_width, 20.0],
+                        TextEdit::singleline(&mut self.new_source),
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_sized([label_width, 20.0], egui::Label::new("Posting:"));
+                    ui.add_sized(
+                        [field_width - 60.0, 20.0],
+                        TextEdit::singleline(&mut self.posting_path).hint_text("posting.html"),
+                    );
+                    if ui.button("Fill in").clicked() {
+                        self.load_posting();
+                    }
+                });
+                if let Some(message) = &self.posting_import_message {
+                    ui.label(message);
+                }
+
+                let add = if self.posting_draft.is_some() {
+                    let (add, discard) = ui
+                        .horizontal(|ui| {
+                            (
+                                ui.button("Add").clicked(),
+                                ui.button("Discard posting").clicked(),
+                            )
+                        })
+                        .inner;
+                    if discard {
+                        self.posting_draft = None;
+                        self.posting_import_message = None;
+                    }
+                    add
+                } else {
+                    ui.button("Add").clicked()
+                        && !self.new_company.is_empty()
+                        && !self.new_role.is_empty()
+                        && !self.new_role_location.is_empty()
+                };
+                if add {
+                    let source = self.new_source.trim();
+                    if !source.is_empty() && self.store.parse_source(source).is_err() {
+                        self.unknown_source = Some(source.to_string());
+                        self.source_message = None;
+                    } else {
+                        self.add_from_form();
+                    }
+                }
+
+                if let Some(name) = self.unknown_source.clone() {
+                    ui.label(format!("\"{name}\" isn't one of your sources yet."));
+                    let (add, add_referral, cancel) = ui
+                        .horizontal(|ui| {
+                            (
+                                ui.button("Add as new source").clicked(),
+                                ui.button("Add as referral source").clicked(),
+                                ui.button("Cancel").clicked(),
+                            )
+                        })
+                        .inner;
+                    if add || add_referral {
+                        let mut source = Source::new(JobSource::new(&name));
+                        source.referral = add_referral;
+                        match self.store.add_source(source) {
+                            Ok(()) => {
+                                self.unknown_source = None;
+                                self.add_from_form();
+                            }
+                            Err(e) => self.source_message = Some(format!("{e:#}")),
+                        }
+                    }
+                    if cancel || self.new_source.trim() != name {
+                        self.unknown_source = None;
+                        self.source_message = None;
+                    }
+                    if let Some(message) = &self.source_message {
+                        ui.colored_label(Color32::RED, message);
+                    }
+                }
+            });
+        });
+    }
+
+    /// Adds the application in the add form, with the rest of the posting
+    /// draft if there is one, and clears the form.
+    fn add_from_form(&mut self) {
+        let source = Some(self.new_source.trim())
+            .filter(|source| !source.is_empty())
+            .map(JobSource::new);
+        let draft = Job {
+            company: self.new_company.clone(),
+            role: self.new_role.clone(),
+            role_location: Some(self.new_role_location.trim().to_string())
+                .filter(|location| !location.is_empty()),
+            source,
+            ..self.posting_draft.clone().unwrap_or_default()
+        };
+        if let Err(e) = self.store.add_draft(draft) {
+            self.posting_import_message = Some(format!("{e:#}"));
+            return;
+        }
+        if self.posting_draft.take().is_some() {
+            self.posting_path.clear();
+        }
+        self.posting_import_message = None;
+        self.new_company.clear();
+        self.new_role.clear();
+        self.new_role_location.clear();
+    }
+
+    fn add_bar_chart_stats(&mut self, ui: &mut Ui) {
+        self.store.calculate_summary_stats().unwrap();
+
+        // Find earliest application date (fallback: today if no jobs yet)
+        let today = Utc::now();
+        let earliest_date = self
+            .store
+            .active_jobs()
+            .map(|job| job.timestamp.date_naive())
+            .min()
+            .unwrap_or_else(|| today.date_naive());
+
+        // Collect every day from earliest_date..=today
+        let all_dates: Vec<NaiveDate> = {
+            let mut dates = Vec::new();
+            let mut d = earliest_date;
+            while d <= today.date_naive() {
+                dates.push(d);
+                d = d.succ_opt().unwrap(); // go to next day safely
+            }
+            dates
+        };
+
+        // Initialize the map with empty vectors
+        let mut date_to_jobs: HashMap<NaiveDate, Vec<Job>> =
+            all_dates.iter().map(|&d| (d, Vec::new())).collect();
+
+        // Assign jobs to their dates
+        for job in self.store.active_jobs() {
+            let job_date = job.timestamp.date_naive();
+            if date_to_jobs.contains_key(&job_date) {
+                date_to_jobs.get_mut(&job_date).unwrap().push(job.clone());
+            }
+        }
+
+        // Sorted list of dates for x-axis
+        let mut sorted_dates: Vec<NaiveDate> = date_to_jobs.keys().cloned().collect();
+        sorted_dates.sort();
+
+        ui.with_layout(Layout::top_down(Align::Center), |ui| {
+            let padding = " ".repeat(20);
+            ui.label(format!(
+                "Timeline:\n\nRejection: {:.2}%{padding}Offer: {:.2}%",
+                (self.store.summary_stats.failed as f32 / self.store.summary_stats.total as f32)
+                    * 100.0,
+                (self.store.summary_stats.succeeded as f32 / self.store.summary_stats.total as f32)
+                    * 100.0
+            ));
+
+            Plot::new("applications_chart")
+                .legend(Legend::default())
+                .include_y(0.0)
+                .show_grid(true)
+                .height(250.0)
+                .show(ui, |plot_ui| {
+                    for (date_idx, date) in sorted_dates.iter().enumerate() {
+                        if let Some(jobs) = date_to_jobs.get(date) {
+                            let x_position = date_idx as f64;
+
+                            // Create a bar for this date with height = number of jobs
+                            for (k, j) in jobs.iter().enumerate() {
+                                let is_selected =
+                                    self.selected_company.as_ref() == Some(&j.company);
+                                let stroke = if is_selected {
+                                    Stroke::new(3.0, Color32::GOLD) // thicker border
+                                } else {
+                                    Stroke::new(0.3, Color32::BLACK) // normal border
+                                };
+
+                                if self.search_text.is_empty() {
+                                    self.selected_company = None;
+                                }
+
+                                let bar = Bar::new(x_position, 1_f64)
+                                    .width(0.8)
+                                    .base_offset(k as f64) // offset to stack values
+                                    .fill(j.get_status_color_mapping(&self.store.stages))
+                                    .stroke(stroke)
+                                    .name(format!("{}\n{}", j.company, j.role));
+                                plot_ui.bar_chart(BarChart::new(vec![bar]));
+                            }
+
+                            // Add date label below the bar
+                            if date_idx % 4 == 0 {
+                                plot_ui.text(
+                                    Text::new(
+                                        PlotPoint::new(x_position, -0.5),
+                                        date.format("%m/%d").to_string(),
+                                    )
+                                    .color(Color32::GRAY)
+                                    .anchor(egui::Align2::CENTER_TOP),
+                                );
+                            }
+                        }
+                    }
+
+                    // Selectable chart entries that'll dynamically search for the app clicked
+                    if plot_ui.response().clicked() {
+                        if let Some(pointer_pos) = plot_ui.pointer_coordinate() {
+                            let x_idx = pointer_pos.x.round() as usize;
+                            if let Some(date) = sorted_dates.get(x_idx) {
+                                if let Some(jobs) = date_to_jobs.get(date) {
+                                    // Find the "stack level" based on y coordinate
+                                    let stack_idx = pointer_pos.y.floor() as usize;
+                                    if let Some(job) = jobs.get(stack_idx) {
+                                        // Update search text to clicked company
+                                        self.search_text = job.company.clone();
+                                        self.selected_company = Some(job.company.clone());
+                                    }
+                                }
+                            }
+                        }
+                    }
+                });
+        });
+    }
+
+    fn add_bar_chart_legend(&mut self, ui: &mut Ui) {
+        let counts = &self.store.summary_stats.by_stage;
+        if counts.is_empty() {
+            return;
+        }
+        ui.with_layout(Layout::top_down(Align::Center), |ui| {
+            ui.columns(counts.len(), |columns| {
+                for (column, count) in columns.iter_mut().zip(counts) {
+                    column.vertical_centered(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.painter().rect_filled(
+                                egui::Rect::from_min_size(ui.cursor().min, egui::vec2(16.0, 16.0)),
+                                2.0,
+                                count.stage.get_status_color_mapping(&self.store.stages),
+                            );
+                            ui.add_space(20.0);
+                            ui.label(format!("{}: {}", count.stage, count.count));
+                            ui.add_space(10.0);
+                        });
+                    });
+                }
+            });
+        });
+    }
+
+    /// Side panel with the status timeline of the job picked via its
+    /// "Details" button.
+    fn add_job_details_panel(&mut self, ctx: &egui::Context) {
+        let Some(job) = self
+            .detail_job
+            .and_then(|id| self.store.active_jobs().find(|job| job.id == id))
+            .cloned()
+        else {
+            return;
+        };
+
+        egui::SidePanel::right("job_details")
+            .resizable(true)
+            .default_width(300.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading(format!("{} - {}", job.company, job.role));
+                    if ui.button("Close").clicked() {
+                        self.detail_job = None;
+                    }
+                });
+                ui.separator();
+
+                ui.label("Timeline:");
+                egui::ScrollArea::vertical()
+                    .id_source("timeline")
+                    .max_height(ui.available_height() * 0.35)
+                    .show(ui, |ui| {
+                        let mut previous: Option<DateTime<Utc>> = None;
+                        for change in &job.history {
+                            ui.horizontal(|ui| {
+                                ui.painter().rect_filled(
+                                    egui::Rect::from_min_size(
+                                        ui.cursor().min,
+                                        egui::vec2(12.0, 12.0),
+                                    ),
+                                    2.0,
+                                    change.to.get_status_color_mapping(&self.store.stages),
+                                );
+                                ui.add_space(16.0);
+                                let when =
+                                    change.at.with_timezone(&New_York).format("%Y-%m-%d %H:%M");
+                                match &change.from {
+                                    Some(from) => {
+                                        ui.label(format!("{when}  {from} -> {}", change.to))
+                                    }
+                                    None => ui.label(format!("{when}  Added as {}", change.to)),
+                                };
+                            });
+                            if let Some(previous) = previous {
+                                let days = (change.at - previous).num_days();
+                                ui.weak(format!("    after {days} day(s)"));
+                            }
+                            if let Some(note) = &change.note {
+                                ui.label(egui::RichText::new(format!("    {note}")).italics());
+                            }
+                            if change.automatic {
+                                ui.weak("    (set automatically)");
+                            }
+                            if change.correction {
+                                ui.weak("    (correction)");
+                            }
+                            previous = Some(change.at);
+                        }
+                    });
+                if job.history.last().is_some_and(|change| change.automatic)
+                    && ui.button("Undo automatic change").clicked()
+                {
+                    let result = self.store.undo_ghosting(&[job.id]);
+                    self.detail_message = result.err().map(|e| format!("{e:#}"));
+                }
+                ui.separator();
+
+                ui.label("Change status:");
+                let options: Vec<JobStatus> = if self.detail_correction {
+                    self.store.stages.iter().map(|s| s.name.clone()).collect()
+                } else {
+                    self.store.next_statuses(&job.status)
+                };
+                // Drop a pick the rules stopped allowing when Correction was unticked
+                if self
+                    .detail_status
+                    .as_ref()
+                    .is_some_and(|status| !options.contains(status))
+                {
+                    self.detail_status = None;
+                }
+                let selected_text = self
+                    .detail_status
+                    .as_ref()
+                    .map_or("Choose...".to_string(), |s| s.to_string());
+                egui::ComboBox::from_id_source("detail_status")
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        for status in options {
+                            let label = status.to_string();
+                            ui.selectable_value(&mut self.detail_status, Some(status), label);
+                        }
+                    });
+                ui.checkbox(&mut self.detail_correction, "Correction")
+                    .on_hover_text("Allow any stage, ignoring the pipeline's rules");
+                ui.add(
+                    TextEdit::multiline(&mut self.detail_note)
+                        .hint_text("Note (optional)")
+                        .desired_rows(2),
+                );
+                if ui.button("Record").clicked() {
+                    let note = Some(self.detail_note.trim().to_string()).filter(|n| !n.is_empty());
+                    if let Some(status) = self.detail_status.take() {
+                        let result = self.store.update_status_with_note(
+                            job.id,
+                            status,
+                            note,
+                            self.detail_correction,
+                        );
+                        match result {
+                            Ok(_) => {
+                                self.detail_note.clear();
+                                self.detail_correction = false;
+                                self.detail_message = None;
+                            }
+                            // The note stays to try again with
+                            Err(e) => self.detail_message = Some(format!("{e:#}")),
+                        }
+                    }
+                }
+                if let Some(message) = &self.detail_message {
+                    ui.colored_label(Color32::RED, message);
+                }
+                ui.separator();
+
+                self.add_job_location(ui, &job);
+                ui.separator();
+
+                self.add_job_posting(ui, &job);
+                ui.separator();
+
+                self.add_job_interviews(ui, &job);
+                ui.separator();
+
+                self.add_job_compensation(ui, &job);
+                ui.separator();
+
+                self.add_job_contacts(ui, &job);
+                ui.separator();
+
+                self.add_job_notes(ui, &job);
+            });
+    }
+
+    fn write_header_row(ui: &mut Ui) {
+        for (idx, header_field) in COLUMN_HEADER_AND_WIDTH_FIELDS.iter().enumerate() {
+            ui.add_sized(
+                [header_field.1, DEFAULT_FIELD_ELEMENT_HEIGHT],
+                egui::Label::new(COLUMN_HEADER_AND_WIDTH_FIELDS[idx].0),
+            );
+        }
+    }
+}
+
+/// Application count, interview, offer and rejection rates and median
+/// response time for each group of applications in `groups`, with `name`
+/// heading the group column.
+fn add_breakdown_grid<'a>(
+    ui: &mut Ui,
+    id: &str,
+    name: &str,
+    groups: impl Iterator<Item = (String, &'a SummaryCounts)>,
+) {
+    let percent = |rate: Option<f64>| format!("{:.0}%", rate.unwrap_or_default());
+    egui::Grid::new(id).striped(true).show(ui, |ui| {
+        for header in [
+            name,
+            "Applications",
+            "Interview",
+            "Offer",
+            "Rejection",
+            "Median response",
+        ] {
+            ui.strong(header);
+        }
+        ui.end_row();
+        for (group, counts) in groups {
+            ui.label(group);
+            ui.label(counts.total.to_string());
+            ui.label(percent(counts.interview_rate));
+            ui.label(percent(counts.offer_rate));
+            ui.label(percent(counts.rejection_rate));
+            ui.label(
+                counts
+                    .median_days_to_response
+                    .map_or_else(String::new, |days| format!("{days:.1} days")),
+            );
+            ui.end_row();
+        }
+    });
+}
+
+impl eframe::App for JobApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if let Some(load_error) = &self.load_error {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.heading("Could not open the data file");
+                ui.colored_label(Color32::RED, load_error);
+            });
+            return;
+        }
+
+        self.handle_undo_shortcuts(ctx);
+        self.add_job_details_panel(ctx);
+        self.add_confirmation_dialog(ctx);
+        self.add_offer_comparison(ctx);
+        self.add_pipeline_flow(ctx);
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            self.add_bar_chart_stats(ui);
+            self.add_bar_chart_legend(ui);
+            ui.separator();
+
+            ui.with_layout(Layout::top_down(Align::Center), |ui| {
+                ui.horizontal(|ui| {
+                    self.add_job_app_input_form(ui);
+                    ui.vertical(|ui| {
+                        self.add_search_box(ui);
+                        self.add_refresh_button(ui);
+                        self.add_undo_controls(ui);
+                        self.add_interview_controls(ui);
+                        ui.horizontal(|ui| {
+                            if ui.button("Compare offers").clicked() {
+                                self.show_offer_comparison = true;
+                            }
+                            if ui.button("Pipeline flow").clicked() {
+                                self.show_pipeline_flow = true;
+                            }
+                        });
+                        self.add_csv_controls(ui);
+                        self.add_contact_controls(ui);
+                        self.add_tag_stats(ui);
+                        self.add_location_stats(ui);
+                        self.add_source_stats(ui);
+                        self.add_stage_controls(ui);
+                        self.add_source_controls(ui);
+                        self.add_ghosting_controls(ui);
+                        self.add_trash_controls(ui);
+                        self.add_backup_controls(ui);
+                    });
+                });
+            });
+            ui.separator();
+            if let Some(message) = &self.list_message {
+                ui.colored_label(Color32::RED, message);
+            }
+
+            // ----------------------------
+            // Scrollable job list grid
+            // ----------------------------
+            let mut to_remove: Option<u32> = None;
+            let mut to_show_details: Option<u32> = None;
+            let mut to_update_status: Option<(u32, JobStatus)> = None;
+            let mut to_update_source: Option<(u32, Option<JobSource>)> = None;
+            let mut to_update_timestamp: Option<(u32, chrono::DateTime<chrono::Local>)> = None;
+            let mut to_update_company: Option<(u32, String)> = None;
+            let mut to_update_tags: Option<(u32, String)> = None;
+
+            egui::ScrollArea::both()
+                .auto_shrink([false; 2])
+                .show(ui, |ui| {
+                    egui::Grid::new("jobs_grid").striped(true).show(ui, |ui| {
+                        // Header row
+                        JobApp::write_header_row(ui);
+                        ui.end_row();
+
+                        // Rows
+                        let search_text = self.search_text.to_lowercase();
+                        let jobs: Vec<Job> = self
+                            .store
+                            .active_jobs()
+                            .filter(|job| job.matches_search(&search_text))
+                            .filter(|job| job.has_tags(&self.tag_filter))
+                            .filter(|job| {
+                                self.arrangement_filter.is_none()
+                                    || job.location.arrangement == self.arrangement_filter
+                            })
+                            .filter(|job| {
+                                self.area_filter
+                                    .as_ref()
+                                    .is_none_or(|area| job.in_area(area))
+                            })
+                            .filter(|job| {
+                                self.flow_filter
+                                    .as_ref()
+                                    .is_none_or(|(_, job_ids)| job_ids.contains(&job.id))
+                            })
+                            .cloned()
+                            .collect();
+                        for job in &jobs {
+                            ui.add_sized(
+                                [50.0, DEFAULT_FIELD_ELEMENT_HEIGHT],
+                                egui::Label::new(job.id.to_string()),
+                            );
+
+                            // ---- Editable timestamp ----
+                            let ts_entry =
+                                self.edit_timestamps.entry(job.id).or_insert_with(|| {
+                                    job.timestamp
+                                        .with_timezone(&Local)
+                                        .format("%Y-%m-%d %H:%M:%S")
+                                        .to_string()
+                                });
+
+                            let response = ui.add_sized(
+                                [
+                                    COLUMN_HEADER_AND_WIDTH_FIELDS[1].1,
+                                    DEFAULT_FIELD_ELEMENT_HEIGHT,
+                                ],
+                                TextEdit::singleline(ts_entry),
+                            );
+
+                            let pressed_enter = response.has_focus()
+                                && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+                            if response.lost_focus() || pressed_enter {
+                                if let Ok(parsed) =
+                                    NaiveDateTime::parse_from_str(ts_entry, "%Y-%m-%d %H:%M:%S")
+                                {
+                                    if let chrono::LocalResult::Single(local_dt) =
+                                        Local.from_local_datetime(&parsed)
+                                    {
+                                        // defer update
+                                        to_update_timestamp = Some((job.id, local_dt));
+                                    }
+                                }
+                            }
+
+                            // ---- Company / Role / Location ----
+                            let curr_company = self
+                                .edit_companies
+                                .entry(job.id)
+                                .or_insert_with(|| job.company.clone());
+
+                            let response = ui.add_sized(
+                                [
+                                    COLUMN_HEADER_AND_WIDTH_FIELDS[2].1,
+                                    DEFAULT_FIELD_ELEMENT_HEIGHT,
+                                ],
+                                TextEdit::singleline(curr_company),
+                            );
+                            let pressed_enter = response.has_focus()
+                                && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                            if response.lost_focus() || pressed_enter {
+                                to_update_company = Some((job.id, curr_company.to_string()));
+                            }
+
+                            ui.add_sized(
+                                [
+                                    COLUMN_HEADER_AND_WIDTH_FIELDS[3].1,
+                                    DEFAULT_FIELD_ELEMENT_HEIGHT,
+                                ],
+                                egui::Label::new(&job.role),
+                            );
+                            ui.add_sized(
+                                [
+                                    COLUMN_HEADER_AND_WIDTH_FIELDS[4].1,
+                                    DEFAULT_FIELD_ELEMENT_HEIGHT,
+                                ],
+                                egui::Label::new(
+                                    job.role_location.clone().unwrap_or("N/A".to_string()),
+                                ),
+                            )
+                            .on_hover_text(job.location.to_string());
+
+                            // ---- Status dropdown ----
+                            let mut selected_status = job.status.clone();
+                            egui::ComboBox::from_id_source(format!("status_{}", job.id))
+                                .selected_text(selected_status.to_string())
+                                .show_ui(ui, |ui| {
+                                    let next = self.store.next_statuses(&job.status);
+                                    for status in &next {
+                                        if ui
+                                            .selectable_value(
+                                                &mut selected_status,
+                                                status.clone(),
+                                                status.to_string(),
+                                            )
+                                            .clicked()
+                                        {
+                                            to_update_status = Some((job.id, status.clone()));
+                                        }
+                                    }
+                                });
+
+                            // Source
+                            let mut selected_source = job.source.clone();
+                            egui::ComboBox::from_id_source(format!("source_{}", job.id))
+                                .selected_text(
+                                    selected_source
+                                        .as_ref()
+                                        .map_or("Not provided".to_string(), |s| s.to_string()),
+                                )
+                                .show_ui(ui, |ui| {
+                                    let choices = std::iter::once(None).chain(
+                                        self.store.sources.iter().map(|s| Some(s.name.clone())),
+                                    );
+                                    for src in choices {
+                                        let text = src
+                                            .as_ref()
+                                            .map_or("Not provided".to_string(), |s| s.to_string());
+                                        if ui
+                                            .selectable_value(
+                                                &mut selected_source,
+                                                src.clone(),
+                                                text,
+                                            )
+                                            .clicked()
+                                        {
+                                            to_update_source = Some((job.id, src));
+                                        }
+                                    }
+                                });
+
+                            if let Some(update) = self.add_tag_editor(ui, job) {
+                                to_update_tags = Some(update);
+                            }
+
+                            // ---- Details / Delete buttons ----
+                            ui.horizontal(|ui| {
+                                if ui.button("Details").clicked() {
+                                    to_show_details = Some(job.id);
+                                }
+                                if ui.button("Delete").clicked() {
+                                    to_remove = Some(job.id);
+                                }
+                            });
+
+                            ui.end_row();
+                        }
+                    });
+                });
+
+            // ----------------------------
+            // Apply updates
+            // ----------------------------
+            if let Some((id, new_status)) = to_update_status {
+                let result = self.store.update_status(id, new_status);
+                self.list_message = result.err().map(|e| format!("{e:#}"));
+            }
+            if let Some((id, new_source)) = to_update_source {
+                let result = self.store.update_source(id, new_source);
+                self.list_message = result.err().map(|e| format!("{e:#}"));
+            }
+            if let Some((id, new_ts)) = to_update_timestamp {
+                match self.store.update_timestamp(id, new_ts.into()) {
+                    Ok(_) => {
+                        self.list_message = None;
+                        // update the edit buffer so it shows canonical formatting
+                        if let Some(ts_text) = self.edit_timestamps.get_mut(&id) {
+                            *ts_text = new_ts.format("%Y-%m-%d %H:%M:%S").to_string();
+                        }
+                    }
+                    Err(e) => {
+                        self.list_message = Some(format!("{e:#}"));
+                        // Show the stored timestamp again
+                        self.edit_timestamps.remove(&id);
+                    }
+                }
+            }
+            if let Some((id, text)) = to_update_tags {
+                let result = self.store.set_tags(id, parse_tags(&text));
+                self.list_message = result.err().map(|e| format!("{e:#}"));
+                // Keep a trailing comma so the next tag can be typed after
+                // a completion, otherwise show the tags as stored
+                if !text.trim_end().ends_with(',') {
+                    self.edit_tags.remove(&id);
+                }
+            }
+            if let Some(id) = to_show_details {
+                self.show_details(id);
+            }
+            if let Some(id) = to_remove {
+                self.pending_confirmation = Some(PendingConfirmation::Trash(id));
+            }
+            if let Some((id, new_company)) = to_update_company {
+                match self.store.update_company(id, new_company.clone()) {
+                    Ok(_) => {
+                        self.list_message = None;
+                        // update the edit buffer so it shows canonical formatting
+                        if let Some(company) = self.edit_companies.get_mut(&id) {
+                            *company = new_company;
+                        }
+                    }
+                    Err(e) => {
+                        self.list_message = Some(format!("{e:#}"));
+                        // Show the stored company again
+                        self.edit_companies.remove(&id);
+                    }
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,286 @@
+use chrono::NaiveDate;
+use eframe::egui::{self, Color32, TextEdit, Ui};
+
+use crate::{CompensationForm, JobApp};
+use jobtracker_core::{format_amount, Job, Offer, OfferComparison, PayPeriod, SalaryRange};
+use strum::IntoEnumIterator;
+
+impl CompensationForm {
+    pub(super) fn from_job(job: &Job) -> Self {
+        let amount = |amount: Option<f64>| amount.map(format_amount).unwrap_or_default();
+        let mut form = Self::default();
+        if let Some(range) = &job.salary_range {
+            form.range_min = format_amount(range.min);
+            form.range_max = format_amount(range.max);
+            form.range_currency = range.currency.clone();
+            form.range_period = range.period;
+        }
+        if let Some(offer) = &job.offer {
+            form.base = format_amount(offer.base);
+            form.period = offer.period;
+            form.bonus = amount(offer.bonus);
+            form.equity = amount(offer.equity);
+            form.vesting_years = offer
+                .vesting_years
+                .map(|y| y.to_string())
+                .unwrap_or_default();
+            form.currency = offer.currency.clone();
+            form.benefits = offer.benefits.clone().unwrap_or_default();
+            form.deadline = offer.deadline.map(|d| d.to_string()).unwrap_or_default();
+        }
+        form
+    }
+
+    /// `None` when both ends of the range are blank.
+    fn salary_range(&self) -> anyhow::Result<Option<SalaryRange>> {
+        let min = parse_amount("Minimum", &self.range_min)?;
+        let max = parse_amount("Maximum", &self.range_max)?;
+        let (min, max) = match (min, max) {
+            (None, None) => return Ok(None),
+            (Some(min), None) => (min, min),
+            (None, Some(max)) => (max, max),
+            (Some(min), Some(max)) => (min, max),
+        };
+        Ok(Some(SalaryRange {
+            min,
+            max,
+            currency: default_currency(&self.range_currency),
+            period: self.range_period,
+        }))
+    }
+
+    /// `None` when base pay is blank.
+    fn offer(&self) -> anyhow::Result<Option<Offer>> {
+        let Some(base) = parse_amount("Base pay", &self.base)? else {
+            return Ok(None);
+        };
+        let vesting_years = match self.vesting_years.trim() {
+            "" => None,
+            years => Some(
+                years
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Vesting years must be a whole number"))?,
+            ),
+        };
+        let deadline = match self.deadline.trim() {
+            "" => None,
+            date => Some(
+                NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .map_err(|_| anyhow::anyhow!("Deadline must look like 2024-05-01"))?,
+            ),
+        };
+        Ok(Some(Offer {
+            base,
+            period: self.period,
+            bonus: parse_amount("Bonus", &self.bonus)?,
+            equity: parse_amount("Equity", &self.equity)?,
+            vesting_years,
+            currency: default_currency(&self.currency),
+            benefits: Some(self.benefits.trim().to_string()).filter(|b| !b.is_empty()),
+            deadline,
+        }))
+    }
+}
+
+impl JobApp {
+    /// Salary range and offer section of the details panel.
+    pub(super) fn add_job_compensation(&mut self, ui: &mut Ui, job: &Job) {
+        let form = &mut self.compensation_form;
+        ui.label("Salary range in posting:");
+        ui.horizontal(|ui| {
+            ui.add(
+                TextEdit::singleline(&mut form.range_min)
+                    .hint_text("Min")
+                    .desired_width(70.0),
+            );
+            ui.label("to");
+            ui.add(
+                TextEdit::singleline(&mut form.range_max)
+                    .hint_text("Max")
+                    .desired_width(70.0),
+            );
+            ui.add(
+                TextEdit::singleline(&mut form.range_currency)
+                    .hint_text("USD")
+                    .desired_width(40.0),
+            );
+            add_pay_period_combo(ui, "range_period", &mut form.range_period);
+        });
+
+        ui.label("Offer:");
+        egui::Grid::new("offer_form").show(ui, |ui| {
+            ui.label("Base");
+            ui.horizontal(|ui| {
+                ui.add(TextEdit::singleline(&mut form.base).desired_width(90.0));
+                add_pay_period_combo(ui, "offer_period", &mut form.period);
+            });
+            ui.end_row();
+            for (label, value, hint) in [
+                ("Bonus per year", &mut form.bonus, ""),
+                ("Equity grant", &mut form.equity, "Total value"),
+                ("Vesting years", &mut form.vesting_years, "4"),
+                ("Currency", &mut form.currency, "USD"),
+                ("Deadline", &mut form.deadline, "2024-05-01"),
+            ] {
+                ui.label(label);
+                ui.add(TextEdit::singleline(value).hint_text(hint));
+                ui.end_row();
+            }
+            ui.label("Benefits");
+            ui.add(TextEdit::multiline(&mut form.benefits).desired_rows(2));
+            ui.end_row();
+        });
+        if let Some(offer) = &job.offer {
+            ui.label(format!(
+                "Per year: {} {}",
+                offer.currency,
+                format_amount(offer.annual_total())
+            ));
+        }
+
+        if ui.button("Save compensation").clicked() {
+            let form = self.compensation_form.clone();
+            let result = form.salary_range().and_then(|range| {
+                self.store.set_salary_range(job.id, range)?;
+                self.store.set_offer(job.id, form.offer()?)
+            });
+            self.compensation_message = result.err().map(|e| format!("{e:#}"));
+        }
+        if let Some(message) = &self.compensation_message {
+            ui.colored_label(Color32::RED, message);
+        }
+    }
+
+    /// Window lining up every job in a successful final stage with its
+    /// offer, converted to yearly amounts.
+    pub(super) fn add_offer_comparison(&mut self, ctx: &egui::Context) {
+        if !self.show_offer_comparison {
+            return;
+        }
+        let offers = self.store.offer_comparison();
+        let base_currency = self.store.currency.base.clone();
+        let best = offers.first().and_then(|offer| offer.total_in_base);
+        let mut open = true;
+        let mut to_show_details = None;
+        egui::Window::new("Compare offers")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                if offers.is_empty() {
+                    ui.label("No applications have reached an offer yet");
+                    return;
+                }
+                egui::ScrollArea::horizontal().show(ui, |ui| {
+                    egui::Grid::new("offer_comparison")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("");
+                            for offer in &offers {
+                                ui.horizontal(|ui| {
+                                    ui.strong(format!("{} - {}", offer.company, offer.role));
+                                    if ui.small_button("Details").clicked() {
+                                        to_show_details = Some(offer.job_id);
+                                    }
+                                });
+                            }
+                            ui.end_row();
+
+                            let cells: Vec<[String; 6]> = offers.iter().map(offer_cells).collect();
+                            for (row, label) in OFFER_ROWS.iter().enumerate() {
+                                ui.label(*label);
+                                for cells in &cells {
+                                    ui.label(&cells[row]);
+                                }
+                                ui.end_row();
+                            }
+
+                            ui.strong(format!("Total in {base_currency}"));
+                            for offer in &offers {
+                                match offer.total_in_base {
+                                    Some(total) => {
+                                        let text =
+                                            format!("{base_currency} {}", format_amount(total));
+                                        if Some(total) == best {
+                                            ui.colored_label(Color32::GREEN, text);
+                                        } else {
+                                            ui.label(text);
+                                        }
+                                    }
+                                    None if offer.offer.is_some() => {
+                                        ui.weak("No exchange rate");
+                                    }
+                                    None => {
+                                        ui.label("");
+                                    }
+                                }
+                            }
+                            ui.end_row();
+                        });
+                });
+            });
+        self.show_offer_comparison = open;
+        if let Some(id) = to_show_details {
+            self.show_details(id);
+        }
+    }
+}
+
+const OFFER_ROWS: [&str; 6] = [
+    "Base per year",
+    "Bonus per year",
+    "Equity per year",
+    "Total per year",
+    "Deadline",
+    "Benefits",
+];
+
+/// Column of the offer comparison for one job, a cell per `OFFER_ROWS`.
+fn offer_cells(comparison: &OfferComparison) -> [String; 6] {
+    let Some(offer) = &comparison.offer else {
+        let mut cells: [String; 6] = Default::default();
+        cells[0] = "No offer details yet".to_string();
+        return cells;
+    };
+    let amount = |amount: Option<f64>| {
+        amount.map_or_else(String::new, |amount| {
+            format!("{} {}", offer.currency, format_amount(amount))
+        })
+    };
+    [
+        amount(comparison.annual_base),
+        amount(offer.bonus),
+        amount(comparison.annual_equity),
+        amount(comparison.annual_total),
+        offer.deadline.map_or_else(String::new, |d| d.to_string()),
+        offer.benefits.clone().unwrap_or_default(),
+    ]
+}
+
+fn add_pay_period_combo(ui: &mut Ui, id: &str, period: &mut PayPeriod) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(format!("per {}", period.to_string().to_lowercase()))
+        .show_ui(ui, |ui| {
+            for option in PayPeriod::iter() {
+                let label = format!("per {}", option.to_string().to_lowercase());
+                ui.selectable_value(period, option, label);
+            }
+        });
+}
+
+/// Blank is `None`. Thousands separators are allowed.
+fn parse_amount(name: &str, text: &str) -> anyhow::Result<Option<f64>> {
+    let text = text.trim().replace(',', "");
+    if text.is_empty() {
+        return Ok(None);
+    }
+    text.parse()
+        .map(Some)
+        .map_err(|_| anyhow::anyhow!("{name} must be a number"))
+}
+
+fn default_currency(currency: &str) -> String {
+    match currency.trim() {
+        "" => "USD".to_string(),
+        currency => currency.to_string(),
+    }
+}
@@ -0,0 +1,199 @@
+use eframe::egui::{self, Color32, TextEdit, Ui};
+
+use crate::JobApp;
+use jobtracker_core::{Contact, Job};
+
+impl JobApp {
+    pub(super) fn add_contact_controls(&mut self, ui: &mut Ui) {
+        ui.collapsing("Contacts", |ui| {
+            let search_text = self.search_text.to_lowercase();
+            let mut to_delete = None;
+            egui::ScrollArea::vertical()
+                .id_source("contacts")
+                .max_height(150.0)
+                .show(ui, |ui| {
+                    for contact in self
+                        .store
+                        .contacts
+                        .iter()
+                        .filter(|contact| contact.matches_search(&search_text))
+                    {
+                        ui.horizontal(|ui| {
+                            let details: Vec<&str> =
+                                [&contact.role, &contact.company, &contact.email]
+                                    .into_iter()
+                                    .flatten()
+                                    .map(String::as_str)
+                                    .collect();
+                            ui.label(&contact.name);
+                            ui.weak(details.join(", "));
+                            if ui.small_button("Edit").clicked() {
+                                self.contact_form = Some(contact.clone());
+                            }
+                            if ui.small_button("Delete").clicked() {
+                                to_delete = Some(contact.id);
+                            }
+                        });
+                    }
+                });
+            if let Some(id) = to_delete {
+                self.contact_message = self.store.delete_contact(id).err().map(|e| e.to_string());
+            }
+
+            if let Some(contact) = &mut self.contact_form {
+                let mut done = false;
+                egui::Grid::new("contact_form").show(ui, |ui| {
+                    ui.label("Name");
+                    ui.text_edit_singleline(&mut contact.name);
+                    ui.end_row();
+                    for (label, field) in [
+                        ("Email", &mut contact.email),
+                        ("Phone", &mut contact.phone),
+                        ("Role", &mut contact.role),
+                        ("Company", &mut contact.company),
+                    ] {
+                        let mut text = field.clone().unwrap_or_default();
+                        ui.label(label);
+                        if ui.text_edit_singleline(&mut text).changed() {
+                            *field = Some(text).filter(|t| !t.trim().is_empty());
+                        }
+                        ui.end_row();
+                    }
+                    let mut notes = contact.notes.clone().unwrap_or_default();
+                    ui.label("Notes");
+                    if ui.text_edit_multiline(&mut notes).changed() {
+                        contact.notes = Some(notes).filter(|t| !t.trim().is_empty());
+                    }
+                    ui.end_row();
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Save contact").clicked() {
+                        let contact = contact.clone();
+                        let result = if contact.id == 0 {
+                            self.store.add_contact(contact).map(|_| ())
+                        } else {
+                            self.store.update_contact(contact)
+                        };
+                        done = result.is_ok();
+                        self.contact_message = result.err().map(|e| e.to_string());
+                    }
+                    if ui.button("Cancel").clicked() {
+                        done = true;
+                    }
+                });
+                if done {
+                    self.contact_form = None;
+                }
+            } else if ui.button("New contact").clicked() {
+                self.contact_form = Some(Contact::default());
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("vCard file:");
+                ui.add(TextEdit::singleline(&mut self.vcf_path).hint_text("contacts.vcf"));
+            });
+            ui.horizontal(|ui| {
+                let path = self.vcf_path.trim().to_string();
+                if ui.button("Import").clicked() {
+                    let result = std::fs::File::open(&path)
+                        .map_err(anyhow::Error::from)
+                        .and_then(|file| self.store.import_vcf(file));
+                    self.contact_message = Some(match result {
+                        Ok(report) => format!(
+                            "Imported {} contacts, skipped {} already known",
+                            report.imported.len(),
+                            report.duplicates.len()
+                        ),
+                        Err(e) => format!("Import failed: {e:#}"),
+                    });
+                }
+                if ui.button("Export").clicked() {
+                    let result = std::fs::File::create(&path)
+                        .map_err(anyhow::Error::from)
+                        .and_then(|file| self.store.export_vcf(file));
+                    self.contact_message = Some(match result {
+                        Ok(()) => format!("Exported {} contacts", self.store.contacts.len()),
+                        Err(e) => format!("Export failed: {e:#}"),
+                    });
+                }
+            });
+            if let Some(message) = &self.contact_message {
+                ui.label(message);
+            }
+        });
+    }
+
+    /// Contacts section of the details panel: who's linked to `job`, with
+    /// a picker to link someone else.
+    pub(super) fn add_job_contacts(&mut self, ui: &mut Ui, job: &Job) {
+        if self.store.is_referral(job.source.as_ref()) {
+            let mut referrer = job.referrer;
+            ui.horizontal(|ui| {
+                ui.label("Referred by:");
+                let name = |id: Option<u32>| {
+                    id.and_then(|id| self.store.contacts.iter().find(|c| c.id == id))
+                        .map_or("Nobody".to_string(), |c| c.name.clone())
+                };
+                egui::ComboBox::from_id_source("referrer")
+                    .selected_text(name(referrer))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut referrer, None, "Nobody");
+                        for contact in &self.store.contacts {
+                            ui.selectable_value(&mut referrer, Some(contact.id), &contact.name);
+                        }
+                    });
+            });
+            if referrer != job.referrer {
+                self.contact_message = self
+                    .store
+                    .set_referrer(job.id, referrer)
+                    .err()
+                    .map(|e| format!("{e:#}"));
+            }
+        }
+
+        ui.label("Contacts:");
+        let mut to_unlink = None;
+        for contact in self.store.contacts_for_job(job.id) {
+            ui.horizontal(|ui| {
+                ui.label(&contact.name);
+                if let Some(role) = &contact.role {
+                    ui.weak(role);
+                }
+                if ui.small_button("Unlink").clicked() {
+                    to_unlink = Some(contact.id);
+                }
+            });
+        }
+        if let Some(id) = to_unlink {
+            self.contact_message = self
+                .store
+                .unlink_contact(id, job.id)
+                .err()
+                .map(|e| format!("{e:#}"));
+        }
+
+        let mut to_link = None;
+        egui::ComboBox::from_id_source("link_contact")
+            .selected_text("Link contact...")
+            .show_ui(ui, |ui| {
+                for contact in &self.store.contacts {
+                    if !contact.job_ids.contains(&job.id)
+                        && ui.selectable_label(false, &contact.name).clicked()
+                    {
+                        to_link = Some(contact.id);
+                    }
+                }
+            });
+        if let Some(id) = to_link {
+            self.contact_message = self
+                .store
+                .link_contact(id, job.id)
+                .err()
+                .map(|e| format!("{e:#}"));
+        }
+        if let Some(message) = &self.contact_message {
+            ui.colored_label(Color32::RED, message);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::Utc;
+use eframe::egui::{Color32, Ui};
+
+use crate::JobApp;
+
+impl JobApp {
+    pub(super) fn add_ghosting_controls(&mut self, ui: &mut Ui) {
+        ui.collapsing("Auto-ghosting", |ui| {
+            let Some(rule) = self.store.ghosting.clone() else {
+                ui.label("Off. Set [ghosting] after_days in config.toml to turn it on.");
+                return;
+            };
+            let statuses: Vec<String> = rule.statuses.iter().map(|s| s.to_string()).collect();
+            ui.label(format!(
+                "Ghost after {} days without a change while {}",
+                rule.after_days,
+                statuses.join("/")
+            ));
+
+            let pending = match self.store.preview_ghosting(Utc::now()) {
+                Ok(pending) => pending,
+                Err(e) => {
+                    ui.colored_label(Color32::RED, format!("{e:#}"));
+                    return;
+                }
+            };
+            if pending.is_empty() {
+                ui.label("Nothing to ghost right now");
+            } else {
+                ui.label(format!("Would ghost {}:", pending.len()));
+                for job in self.store.active_jobs().filter(|j| pending.contains(&j.id)) {
+                    ui.label(format!("  {} - {}", job.company, job.role));
+                }
+                if ui.button("Ghost now").clicked() {
+                    let result = self.store.apply_ghosting(Utc::now());
+                    self.ghosting_message = result.err().map(|e| format!("{e:#}"));
+                }
+            }
+
+            if !self.store.last_ghosted.is_empty()
+                && ui
+                    .button(format!(
+                        "Undo last auto-ghosting ({})",
+                        self.store.last_ghosted.len()
+                    ))
+                    .clicked()
+            {
+                let ids = self.store.last_ghosted.clone();
+                let result = self.store.undo_ghosting(&ids);
+                self.ghosting_message = result.err().map(|e| format!("{e:#}"));
+            }
+            if let Some(message) = &self.ghosting_message {
+                ui.colored_label(Color32::RED, message);
+            }
+        });
+    }
+}
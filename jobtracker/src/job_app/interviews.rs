@@ -0,0 +1,207 @@
+use chrono::{NaiveDateTime, Utc};
+use eframe::egui::{self, TextEdit, Ui};
+
+use crate::{InterviewForm, JobApp};
+use jobtracker_core::{local_timezone, Interview, InterviewOutcome, Job};
+use strum::IntoEnumIterator;
+
+impl JobApp {
+    /// Interviews still to come across all applications, soonest first,
+    /// and the iCalendar import and export.
+    pub(super) fn add_interview_controls(&mut self, ui: &mut Ui) {
+        let upcoming: Vec<(u32, String)> = self
+            .store
+            .upcoming_interviews(Utc::now())
+            .into_iter()
+            .map(|(job, interview)| {
+                (
+                    job.id,
+                    format!(
+                        "{}  {}: {} - {}",
+                        format_interview_time(interview),
+                        interview.round,
+                        job.company,
+                        job.role
+                    ),
+                )
+            })
+            .collect();
+        egui::CollapsingHeader::new(format!("Upcoming interviews ({})", upcoming.len()))
+            .default_open(true)
+            .show(ui, |ui| {
+                if upcoming.is_empty() {
+                    ui.label("Nothing scheduled");
+                }
+                let mut to_show_details = None;
+                egui::ScrollArea::vertical()
+                    .id_source("upcoming_interviews")
+                    .max_height(120.0)
+                    .show(ui, |ui| {
+                        for (job_id, text) in &upcoming {
+                            ui.horizontal(|ui| {
+                                ui.label(text);
+                                if ui.small_button("Details").clicked() {
+                                    to_show_details = Some(*job_id);
+                                }
+                            });
+                        }
+                    });
+                if let Some(id) = to_show_details {
+                    self.show_details(id);
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("iCalendar file:");
+                    ui.add(TextEdit::singleline(&mut self.ics_path).hint_text("interviews.ics"));
+                });
+                ui.horizontal(|ui| {
+                    let path = self.ics_path.trim().to_string();
+                    if ui.button("Import").clicked() {
+                        let result = std::fs::File::open(&path)
+                            .map_err(anyhow::Error::from)
+                            .and_then(|file| self.store.import_ics(file, None));
+                        self.interview_message = Some(match result {
+                            Ok(report) if report.unmatched.is_empty() => {
+                                format!("Imported {} interviews", report.imported.len())
+                            }
+                            Ok(report) => format!(
+                                "Imported {} interviews, couldn't match: {}",
+                                report.imported.len(),
+                                report.unmatched.join(", ")
+                            ),
+                            Err(e) => format!("Import failed: {e:#}"),
+                        });
+                    }
+                    if ui.button("Export").clicked() {
+                        let result = std::fs::File::create(&path)
+                            .map_err(anyhow::Error::from)
+                            .and_then(|file| self.store.export_ics(file, Utc::now()));
+                        self.interview_message = Some(match result {
+                            Ok(()) => "Exported interviews".to_string(),
+                            Err(e) => format!("Export failed: {e:#}"),
+                        });
+                    }
+                });
+                if let Some(message) = &self.interview_message {
+                    ui.label(message);
+                }
+            });
+    }
+
+    /// Interviews section of the details panel: every interview of `job`
+    /// with its outcome, and a form to schedule another.
+    pub(super) fn add_job_interviews(&mut self, ui: &mut Ui, job: &Job) {
+        ui.label("Interviews:");
+        let mut to_update = None;
+        let mut to_delete = None;
+        let mut interviews: Vec<&Interview> = job.interviews.iter().collect();
+        interviews.sort_by_key(|interview| interview.start);
+        for interview in interviews {
+            ui.horizontal(|ui| {
+                ui.weak(format_interview_time(interview));
+                ui.label(&interview.round);
+                let mut outcome = interview.outcome;
+                egui::ComboBox::from_id_source(format!("interview_outcome_{}", interview.id))
+                    .selected_text(outcome.map_or("Pending".to_string(), |o| o.to_string()))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut outcome, None, "Pending");
+                        for option in InterviewOutcome::iter() {
+                            ui.selectable_value(&mut outcome, Some(option), option.to_string());
+                        }
+                    });
+                if outcome != interview.outcome {
+                    to_update = Some((interview.id, outcome));
+                }
+                if ui.small_button("Delete").clicked() {
+                    to_delete = Some(interview.id);
+                }
+            });
+            let details: Vec<String> = [
+                Some(interview.interviewers.join(", ")).filter(|names| !names.is_empty()),
+                interview.location.clone(),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            if !details.is_empty() {
+                ui.weak(format!("    {}", details.join(" | ")));
+            }
+        }
+        if let Some((interview_id, outcome)) = to_update {
+            let result = self
+                .store
+                .set_interview_outcome(job.id, interview_id, outcome);
+            self.interview_message = result.err().map(|e| format!("{e:#}"));
+        }
+        if let Some(interview_id) = to_delete {
+            let result = self.store.delete_interview(job.id, interview_id);
+            self.interview_message = result.err().map(|e| format!("{e:#}"));
+        }
+
+        let form = &mut self.interview_form;
+        egui::Grid::new("interview_form").show(ui, |ui| {
+            for (label, value, hint) in [
+                ("Round", &mut form.round, "Phone screen"),
+                ("Start", &mut form.start, "2024-05-01 14:00"),
+                ("Minutes", &mut form.minutes, "60"),
+                ("Time zone", &mut form.timezone, "This computer's"),
+                ("Interviewers", &mut form.interviewers, "Comma-separated"),
+                ("Location", &mut form.location, "Address or meeting link"),
+            ] {
+                ui.label(label);
+                ui.add(TextEdit::singleline(value).hint_text(hint));
+                ui.end_row();
+            }
+        });
+        if ui.button("Schedule interview").clicked() {
+            let result = parse_interview_form(form)
+                .and_then(|interview| self.store.add_interview(job.id, interview));
+            match result {
+                Ok(_) => {
+                    self.interview_form = InterviewForm::default();
+                    self.interview_message = None;
+                }
+                Err(e) => self.interview_message = Some(format!("{e:#}")),
+            }
+        }
+        if let Some(message) = &self.interview_message {
+            ui.label(message);
+        }
+    }
+}
+
+/// When an interview starts, in the time zone it was scheduled in.
+fn format_interview_time(interview: &Interview) -> String {
+    interview
+        .start
+        .with_timezone(&interview.timezone)
+        .format("%a %Y-%m-%d %H:%M %Z")
+        .to_string()
+}
+
+fn parse_interview_form(form: &InterviewForm) -> anyhow::Result<Interview> {
+    let start = NaiveDateTime::parse_from_str(form.start.trim(), "%Y-%m-%d %H:%M")
+        .map_err(|_| anyhow::anyhow!("Start must look like 2024-05-01 14:00"))?;
+    let minutes = match form.minutes.trim() {
+        "" => 60,
+        minutes => minutes
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Minutes must be a whole number"))?,
+    };
+    let timezone = match form.timezone.trim() {
+        "" => local_timezone()?,
+        name => name
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Unknown time zone: {name}"))?,
+    };
+    let mut interview = Interview::new(form.round.trim().to_string(), start, minutes, timezone)?;
+    interview.interviewers = form
+        .interviewers
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect();
+    interview.location = Some(form.location.trim().to_string()).filter(|l| !l.is_empty());
+    Ok(interview)
+}
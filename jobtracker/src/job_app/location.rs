@@ -0,0 +1,131 @@
+use eframe::egui::{self, Color32, Ui};
+
+use crate::{JobApp, LocationForm};
+use jobtracker_core::{Job, Location, WorkArrangement};
+use strum::IntoEnumIterator;
+
+impl LocationForm {
+    fn from_location(location: &Location) -> Self {
+        Self {
+            text: String::new(),
+            city: location.city.clone().unwrap_or_default(),
+            region: location.region.clone().unwrap_or_default(),
+            country: location.country.clone().unwrap_or_default(),
+            arrangement: location.arrangement,
+        }
+    }
+
+    fn from_job(job: &Job) -> Self {
+        Self {
+            text: job.role_location.clone().unwrap_or_default(),
+            ..Self::from_location(&job.location)
+        }
+    }
+}
+
+impl JobApp {
+    /// Location section of the details panel: where the job is as entered,
+    /// how that was read, and a form to change either.
+    pub(super) fn add_job_location(&mut self, ui: &mut Ui, job: &Job) {
+        let Some(form) = &mut self.location_form else {
+            ui.horizontal(|ui| {
+                ui.label("Location:");
+                match &job.role_location {
+                    Some(text) => ui.label(text),
+                    None => ui.weak("Not given"),
+                };
+            });
+            if !job.location.is_empty() {
+                ui.weak(format!("Read as {}", job.location));
+            }
+            if ui.button("Edit location").clicked() {
+                self.location_form = Some(LocationForm::from_job(job));
+                self.location_message = None;
+            }
+            return;
+        };
+
+        let mut save = false;
+        let mut cancel = false;
+        egui::Grid::new("location_form").show(ui, |ui| {
+            ui.label("Location:");
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut form.text);
+                if ui
+                    .button("Read")
+                    .on_hover_text("Fill in the fields below from the location")
+                    .clicked()
+                {
+                    *form = LocationForm {
+                        text: form.text.clone(),
+                        ..LocationForm::from_location(&Location::parse(&form.text))
+                    };
+                }
+            });
+            ui.end_row();
+            for (label, field) in [
+                ("City:", &mut form.city),
+                ("Region:", &mut form.region),
+                ("Country:", &mut form.country),
+            ] {
+                ui.label(label);
+                ui.text_edit_singleline(field);
+                ui.end_row();
+            }
+            ui.label("Arrangement:");
+            let selected = form
+                .arrangement
+                .map_or("Unknown".to_string(), |a| a.to_string());
+            egui::ComboBox::from_id_source("location_arrangement")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut form.arrangement, None, "Unknown");
+                    for arrangement in WorkArrangement::iter() {
+                        ui.selectable_value(
+                            &mut form.arrangement,
+                            Some(arrangement),
+                            arrangement.to_string(),
+                        );
+                    }
+                });
+            ui.end_row();
+        });
+        ui.horizontal(|ui| {
+            save = ui.button("Save location").clicked();
+            cancel = ui.button("Cancel").clicked();
+        });
+
+        if save {
+            let form = form.clone();
+            let field = |text: &str| Some(text.trim().to_string()).filter(|t| !t.is_empty());
+            let location = Location {
+                city: field(&form.city),
+                region: field(&form.region),
+                country: field(&form.country),
+                arrangement: form.arrangement,
+            };
+            let text_changed = form.text.trim() != job.role_location.as_deref().unwrap_or_default();
+            // Only record a correction if the fields say something other
+            // than what the text was read as
+            let result = if text_changed {
+                let correction = (location != Location::parse(&form.text)).then_some(location);
+                self.store.update_location(job.id, &form.text, correction)
+            } else if location != job.location {
+                self.store.set_location(job.id, location)
+            } else {
+                Ok(())
+            };
+            match result {
+                Ok(()) => self.location_form = None,
+                Err(e) => self.location_message = Some(format!("{e:#}")),
+            }
+        }
+        if cancel {
+            self.location_form = None;
+            self.location_message = None;
+        }
+        if let Some(message) = &self.location_message {
+            ui.colored_label(Color32::RED, message);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use chrono_tz::America::New_York;
+use eframe::egui::{self, Color32, TextEdit, Ui};
+
+use crate::JobApp;
+use jobtracker_core::Job;
+
+impl JobApp {
+    /// Notes section of the details panel: every note on `job`, each
+    /// editable, and a box to add another.
+    pub(super) fn add_job_notes(&mut self, ui: &mut Ui, job: &Job) {
+        ui.label("Notes:");
+        let mut save_edit = None;
+        let mut to_delete = None;
+        egui::ScrollArea::vertical()
+            .id_source("notes")
+            .max_height(ui.available_height() * 0.6)
+            .show(ui, |ui| {
+                for note in &job.notes {
+                    ui.horizontal(|ui| {
+                        let mut when = note
+                            .at
+                            .with_timezone(&New_York)
+                            .format("%Y-%m-%d %H:%M")
+                            .to_string();
+                        if note.edited_at.is_some() {
+                            when.push_str(" (edited)");
+                        }
+                        ui.weak(when);
+                        match &self.editing_note {
+                            Some((id, text)) if *id == note.id => {
+                                if ui.small_button("Save").clicked() {
+                                    save_edit = Some((note.id, text.clone()));
+                                }
+                                if ui.small_button("Cancel").clicked() {
+                                    self.editing_note = None;
+                                }
+                            }
+                            _ => {
+                                if ui.small_button("Edit").clicked() {
+                                    self.editing_note = Some((note.id, note.text.clone()));
+                                }
+                                if ui.small_button("Delete").clicked() {
+                                    to_delete = Some(note.id);
+                                }
+                            }
+                        }
+                    });
+                    match &mut self.editing_note {
+                        Some((id, text)) if *id == note.id => {
+                            ui.add(TextEdit::multiline(text).desired_rows(3));
+                        }
+                        _ => {
+                            ui.label(&note.text);
+                        }
+                    }
+                    ui.add_space(6.0);
+                }
+            });
+
+        if let Some((note_id, text)) = save_edit {
+            match self.store.edit_note(job.id, note_id, text) {
+                Ok(_) => {
+                    self.editing_note = None;
+                    self.note_message = None;
+                }
+                Err(e) => self.note_message = Some(format!("{e:#}")),
+            }
+        }
+        if let Some(note_id) = to_delete {
+            let result = self.store.delete_note(job.id, note_id);
+            self.note_message = result.err().map(|e| format!("{e:#}"));
+        }
+
+        ui.add(
+            TextEdit::multiline(&mut self.new_note)
+                .hint_text("New note (Markdown)")
+                .desired_rows(3),
+        );
+        if ui
+            .add_enabled(
+                !self.new_note.trim().is_empty(),
+                egui::Button::new("Add note"),
+            )
+            .clicked()
+        {
+            let text = self.new_note.trim().to_string();
+            match self.store.add_note(job.id, text) {
+                Ok(_) => {
+                    self.new_note.clear();
+                    self.note_message = None;
+                }
+                Err(e) => self.note_message = Some(format!("{e:#}")),
+            }
+        }
+        if let Some(message) = &self.note_message {
+            ui.colored_label(Color32::RED, message);
+        }
+    }
+}
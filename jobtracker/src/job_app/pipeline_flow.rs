@@ -0,0 +1,360 @@
+use eframe::egui::{self, Color32, Stroke, Ui};
+
+use crate::{JobApp, StatusColor};
+use jobtracker_core::{JobSource, JobStatus, PipelineFlow};
+
+impl JobApp {
+    /// Window with the pipeline funnel and the flow from each source
+    /// through the furthest stage reached to where applications ended up.
+    /// Clicking part of either narrows the job list to those applications.
+    pub(super) fn add_pipeline_flow(&mut self, ctx: &egui::Context) {
+        if !self.show_pipeline_flow {
+            return;
+        }
+        let flow = self.store.pipeline_flow();
+        let mut open = true;
+        let mut picked = None;
+        egui::Window::new("Pipeline flow")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(700.0)
+            .show(ctx, |ui| {
+                if flow.paths.is_empty() {
+                    ui.label("No applications yet");
+                    return;
+                }
+                ui.strong("Funnel");
+                if let Some(pick) = self.add_funnel_chart(ui, &flow) {
+                    picked = Some(pick);
+                }
+                ui.separator();
+                ui.strong("From source to outcome");
+                if let Some(pick) = self.add_flow_chart(ui, &flow) {
+                    picked = Some(pick);
+                }
+                ui.weak("Click a bar, box or band to list those applications");
+            });
+        self.show_pipeline_flow = open;
+        if picked.is_some() {
+            self.flow_filter = picked;
+        }
+    }
+
+    /// A bar per funnel stage, as wide as the share of applications that
+    /// got that far. Returns the stage clicked, if any, as a job filter.
+    fn add_funnel_chart(&self, ui: &mut Ui, flow: &PipelineFlow) -> Option<(String, Vec<u32>)> {
+        const ROW_HEIGHT: f32 = 28.0;
+        const LABEL_WIDTH: f32 = 180.0;
+        let total = flow.funnel.first().map_or(0, |stage| stage.job_ids.len());
+        let size = egui::vec2(
+            ui.available_width().max(400.0),
+            ROW_HEIGHT * flow.funnel.len() as f32,
+        );
+        let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let bar_area = rect.width() - LABEL_WIDTH;
+        let mut picked = None;
+        for (idx, stage) in flow.funnel.iter().enumerate() {
+            let count = stage.job_ids.len();
+            let share = count as f32 / total.max(1) as f32;
+            let top = rect.top() + ROW_HEIGHT * idx as f32;
+            let row = egui::Rect::from_min_size(
+                egui::pos2(rect.left(), top),
+                egui::vec2(rect.width(), ROW_HEIGHT),
+            );
+            let bar = egui::Rect::from_center_size(
+                egui::pos2(rect.left() + LABEL_WIDTH + bar_area / 2.0, row.center().y),
+                egui::vec2((bar_area * share).max(2.0), ROW_HEIGHT - 6.0),
+            );
+            let response = ui.interact(row, ui.id().with(("funnel", idx)), egui::Sense::click());
+            painter.rect_filled(
+                bar,
+                3.0,
+                stage.stage.get_status_color_mapping(&self.store.stages),
+            );
+            if response.hovered() {
+                painter.rect_stroke(bar, 3.0, Stroke::new(2.0, Color32::GOLD));
+            }
+            painter.text(
+                egui::pos2(rect.left(), row.center().y),
+                egui::Align2::LEFT_CENTER,
+                format!("{}: {count} ({:.0}%)", stage.stage, share * 100.0),
+                egui::FontId::proportional(14.0),
+                ui.visuals().strong_text_color(),
+            );
+            if response.clicked() {
+                picked = Some((format!("reached {}", stage.stage), stage.job_ids.clone()));
+            }
+        }
+        picked
+    }
+
+    /// Sankey-style diagram in three columns: sources, the furthest stage
+    /// reached and the outcome, with bands as thick as the number of
+    /// applications that went each way. Returns the box or band clicked,
+    /// if any, as a job filter.
+    fn add_flow_chart(&self, ui: &mut Ui, flow: &PipelineFlow) -> Option<(String, Vec<u32>)> {
+        const HEIGHT: f32 = 320.0;
+        const NODE_WIDTH: f32 = 14.0;
+        const GAP: f32 = 10.0;
+        const LABEL_WIDTH: f32 = 130.0;
+        const SEGMENTS: usize = 24;
+
+        let stages = &self.store.stages;
+        let source_name = |source: &Option<JobSource>| {
+            source
+                .as_ref()
+                .map_or_else(|| "Not provided".to_string(), |s| s.to_string())
+        };
+        let outcome_name = |outcome: &Option<JobStatus>| {
+            outcome
+                .as_ref()
+                .map_or_else(|| "Still open".to_string(), |s| s.to_string())
+        };
+        let node = |label: String, color: Color32| FlowNode {
+            label,
+            color,
+            job_ids: Vec::new(),
+        };
+
+        // Sources in the store's order, stages and outcomes in pipeline
+        // order, open applications last
+        let mut columns: [Vec<FlowNode>; 3] = Default::default();
+        for path in &flow.paths {
+            let label = source_name(&path.source);
+            if !columns[0].iter().any(|n| n.label == label) {
+                columns[0].push(node(label, Color32::GRAY));
+            }
+        }
+        for stage in stages {
+            if flow.paths.iter().any(|path| path.reached == stage.name) {
+                let color = stage.name.get_status_color_mapping(stages);
+                columns[1].push(node(stage.name.to_string(), color));
+            }
+        }
+        for stage in stages {
+            if flow
+                .paths
+                .iter()
+                .any(|path| path.outcome.as_ref() == Some(&stage.name))
+            {
+                let color = stage.name.get_status_color_mapping(stages);
+                columns[2].push(node(stage.name.to_string(), color));
+            }
+        }
+        if flow.paths.iter().any(|path| path.outcome.is_none()) {
+            columns[2].push(node(outcome_name(&None), Color32::GRAY));
+        }
+
+        let mut links: Vec<FlowLink> = Vec::new();
+        for path in &flow.paths {
+            let labels = [
+                source_name(&path.source),
+                path.reached.to_string(),
+                outcome_name(&path.outcome),
+            ];
+            let mut at = [0; 3];
+            for (column, label) in labels.iter().enumerate() {
+                at[column] = columns[column]
+                    .iter()
+                    .position(|n| &n.label == label)
+                    .unwrap_or_default();
+                columns[column][at[column]].job_ids.extend(&path.job_ids);
+            }
+            for column in 0..2 {
+                let existing = links.iter_mut().find(|link| {
+                    link.column == column && link.from == at[column] && link.to == at[column + 1]
+                });
+                match existing {
+                    Some(link) => link.job_ids.extend(&path.job_ids),
+                    None => links.push(FlowLink {
+                        column,
+                        from: at[column],
+                        to: at[column + 1],
+                        job_ids: path.job_ids.clone(),
+                    }),
+                }
+            }
+        }
+        links.sort_by_key(|link| (link.column, link.from, link.to));
+
+        // Lay the boxes out top to bottom, at the same scale in every column
+        let total: usize = columns[0].iter().map(|n| n.job_ids.len()).sum();
+        // Gaps take at most a third of a column, however many boxes it has
+        let gap = |nodes: &[FlowNode]| GAP.min(HEIGHT / 3.0 / nodes.len() as f32);
+        let scale = columns
+            .iter()
+            .map(|nodes| {
+                (HEIGHT - gap(nodes) * nodes.len().saturating_sub(1) as f32) / total as f32
+            })
+            .fold(f32::INFINITY, f32::min);
+        let (rect, response) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width().max(500.0), HEIGHT),
+            egui::Sense::click(),
+        );
+        let painter = ui.painter_at(rect);
+        let inner = rect.shrink2(egui::vec2(LABEL_WIDTH, 0.0));
+        let column_x =
+            |column: usize| inner.left() + (inner.width() - NODE_WIDTH) * column as f32 / 2.0;
+        let boxes: Vec<Vec<egui::Rect>> = columns
+            .iter()
+            .enumerate()
+            .map(|(column, nodes)| {
+                let mut y = rect.top();
+                nodes
+                    .iter()
+                    .map(|n| {
+                        let height = n.job_ids.len() as f32 * scale;
+                        let node_rect = egui::Rect::from_min_size(
+                            egui::pos2(column_x(column), y),
+                            egui::vec2(NODE_WIDTH, height),
+                        );
+                        y += height + gap(nodes);
+                        node_rect
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Each band leaves its source box and enters its target box below
+        // the bands drawn before it
+        let mut out_offset: Vec<Vec<f32>> = boxes.iter().map(|b| vec![0.0; b.len()]).collect();
+        let mut in_offset = out_offset.clone();
+        let bands: Vec<Vec<egui::Pos2>> = links
+            .iter()
+            .map(|link| {
+                let height = link.job_ids.len() as f32 * scale;
+                let from = boxes[link.column][link.from];
+                let to = boxes[link.column + 1][link.to];
+                let start = egui::pos2(
+                    from.right(),
+                    from.top() + out_offset[link.column][link.from],
+                );
+                let end = egui::pos2(to.left(), to.top() + in_offset[link.column + 1][link.to]);
+                out_offset[link.column][link.from] += height;
+                in_offset[link.column + 1][link.to] += height;
+                let middle = (start.x + end.x) / 2.0;
+                (0..=SEGMENTS)
+                    .map(|i| {
+                        let t = i as f32 / SEGMENTS as f32;
+                        let s = 1.0 - t;
+                        let x = s * s * s * start.x + 3.0 * s * t * middle + t * t * t * end.x;
+                        let y = (s * s * s + 3.0 * s * s * t) * start.y
+                            + (3.0 * s * t * t + t * t * t) * end.y;
+                        egui::pos2(x, y)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // What the pointer is over: a box first, then a band
+        let pointer = response.hover_pos();
+        let hovered_node = pointer.and_then(|pointer| {
+            boxes.iter().enumerate().find_map(|(column, rects)| {
+                rects
+                    .iter()
+                    .position(|r| r.expand2(egui::vec2(2.0, 0.0)).contains(pointer))
+                    .map(|idx| (column, idx))
+            })
+        });
+        let hovered_link = pointer
+            .filter(|_| hovered_node.is_none())
+            .and_then(|pointer| {
+                links.iter().zip(&bands).position(|(link, band)| {
+                    let height = link.job_ids.len() as f32 * scale;
+                    band.windows(2).any(|pair| {
+                        let (a, b) = (pair[0], pair[1]);
+                        if pointer.x < a.x || pointer.x > b.x || b.x <= a.x {
+                            return false;
+                        }
+                        let y = a.y + (b.y - a.y) * (pointer.x - a.x) / (b.x - a.x);
+                        pointer.y >= y && pointer.y <= y + height
+                    })
+                })
+            });
+
+        for (idx, (link, band)) in links.iter().zip(&bands).enumerate() {
+            let height = link.job_ids.len() as f32 * scale;
+            let color = columns[link.column + 1][link.to].color;
+            let color = color.gamma_multiply(if hovered_link == Some(idx) { 0.7 } else { 0.35 });
+            let mut mesh = egui::Mesh::default();
+            for point in band {
+                mesh.colored_vertex(*point, color);
+                mesh.colored_vertex(*point + egui::vec2(0.0, height), color);
+            }
+            for i in 0..SEGMENTS as u32 {
+                let k = 2 * i;
+                mesh.add_triangle(k, k + 1, k + 2);
+                mesh.add_triangle(k + 1, k + 3, k + 2);
+            }
+            painter.add(mesh);
+        }
+        let text_color = ui.visuals().strong_text_color();
+        for (column, nodes) in columns.iter().enumerate() {
+            for (idx, n) in nodes.iter().enumerate() {
+                let node_rect = boxes[column][idx];
+                painter.rect_filled(node_rect, 2.0, n.color);
+                if hovered_node == Some((column, idx)) {
+                    painter.rect_stroke(node_rect, 2.0, Stroke::new(2.0, Color32::GOLD));
+                }
+                let (pos, align) = match column {
+                    0 => (
+                        node_rect.left_center() - egui::vec2(6.0, 0.0),
+                        egui::Align2::RIGHT_CENTER,
+                    ),
+                    _ => (
+                        node_rect.right_center() + egui::vec2(6.0, 0.0),
+                        egui::Align2::LEFT_CENTER,
+                    ),
+                };
+                painter.text(
+                    pos,
+                    align,
+                    format!("{} ({})", n.label, n.job_ids.len()),
+                    egui::FontId::proportional(13.0),
+                    text_color,
+                );
+            }
+        }
+
+        let picked = if let Some((column, idx)) = hovered_node {
+            let n = &columns[column][idx];
+            let label = match column {
+                0 => format!("from {}", n.label),
+                1 => format!("reached {}", n.label),
+                _ => format!("ended in {}", n.label),
+            };
+            Some((label, n.job_ids.clone()))
+        } else {
+            hovered_link.map(|idx| {
+                let link = &links[idx];
+                let from = &columns[link.column][link.from].label;
+                let to = &columns[link.column + 1][link.to].label;
+                (format!("{from} to {to}"), link.job_ids.clone())
+            })
+        };
+        let response = match &picked {
+            Some((label, job_ids)) => {
+                response.on_hover_text_at_pointer(format!("{label}: {}", job_ids.len()))
+            }
+            None => response,
+        };
+        picked.filter(|_| response.clicked())
+    }
+}
+
+/// A box in the pipeline flow chart and the applications it stands for.
+struct FlowNode {
+    label: String,
+    color: Color32,
+    job_ids: Vec<u32>,
+}
+
+/// A band in the pipeline flow chart from box `from` in `column` to box
+/// `to` in the next column.
+struct FlowLink {
+    column: usize,
+    from: usize,
+    to: usize,
+    job_ids: Vec<u32>,
+}
@@ -0,0 +1,132 @@
+use eframe::egui::{self, Color32, TextEdit, Ui};
+
+use crate::JobApp;
+use jobtracker_core::{format_amount, Job};
+
+impl JobApp {
+    /// Fills the add form from the posting page at `posting_path`, keeping
+    /// the rest of what was found for when the user clicks Add.
+    pub(super) fn load_posting(&mut self) {
+        let result = std::fs::File::open(self.posting_path.trim())
+            .map_err(anyhow::Error::from)
+            .and_then(Job::from_posting_html);
+        match result {
+            Ok(draft) => {
+                self.new_company = draft.company.clone();
+                self.new_role = draft.role.clone();
+                self.new_role_location = draft.role_location.clone().unwrap_or_default();
+                self.new_source = draft
+                    .source
+                    .as_ref()
+                    .map(|source| source.to_string())
+                    .unwrap_or_default();
+                let mut found = Vec::new();
+                if let Some(range) = &draft.salary_range {
+                    found.push(format!(
+                        "salary {} {} - {} per {}",
+                        range.currency,
+                        format_amount(range.min),
+                        format_amount(range.max),
+                        range.period.to_string().to_lowercase()
+                    ));
+                }
+                if draft.description.is_some() {
+                    found.push("the description".to_string());
+                }
+                if draft.posting_url.is_some() {
+                    found.push("the link".to_string());
+                }
+                let extra = if found.is_empty() {
+                    String::new()
+                } else {
+                    format!(" Also saving {}.", found.join(", "))
+                };
+                self.posting_import_message =
+                    Some(format!("Check the fields above, then click Add.{extra}"));
+                self.posting_draft = Some(draft);
+            }
+            Err(e) => {
+                self.posting_import_message = Some(format!("{e:#}"));
+                self.posting_draft = None;
+            }
+        }
+    }
+
+    /// Posting section of the details panel: the link to the posting and
+    /// its archived description.
+    pub(super) fn add_job_posting(&mut self, ui: &mut Ui, job: &Job) {
+        ui.horizontal(|ui| {
+            ui.label("Posting:");
+            match &job.posting_url {
+                Some(url) => {
+                    ui.hyperlink(url);
+                }
+                None => {
+                    ui.weak("No link");
+                }
+            }
+        });
+
+        let mut save = false;
+        let mut cancel = false;
+        match &mut self.posting_edit {
+            Some((url, description)) => {
+                ui.add(TextEdit::singleline(url).hint_text("https://..."));
+                egui::ScrollArea::vertical()
+                    .id_source("description_edit")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            TextEdit::multiline(description)
+                                .hint_text("Paste the job description")
+                                .desired_rows(6),
+                        );
+                    });
+                ui.horizontal(|ui| {
+                    save = ui.button("Save posting").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            }
+            None => {
+                if let Some(description) = &job.description {
+                    egui::CollapsingHeader::new("Description")
+                        .id_source("description")
+                        .show(ui, |ui| {
+                            egui::ScrollArea::vertical()
+                                .id_source("description_text")
+                                .max_height(200.0)
+                                .show(ui, |ui| ui.label(description));
+                        });
+                }
+                if ui.button("Edit posting").clicked() {
+                    self.posting_edit = Some((
+                        job.posting_url.clone().unwrap_or_default(),
+                        job.description.clone().unwrap_or_default(),
+                    ));
+                }
+            }
+        }
+
+        if save {
+            let (url, description) = self.posting_edit.clone().unwrap_or_default();
+            let result = self
+                .store
+                .update_posting_url(job.id, url)
+                .and_then(|_| self.store.update_description(job.id, description));
+            match result {
+                Ok(_) => {
+                    self.posting_edit = None;
+                    self.posting_message = None;
+                }
+                Err(e) => self.posting_message = Some(format!("{e:#}")),
+            }
+        }
+        if cancel {
+            self.posting_edit = None;
+            self.posting_message = None;
+        }
+        if let Some(message) = &self.posting_message {
+            ui.colored_label(Color32::RED, message);
+        }
+    }
+}
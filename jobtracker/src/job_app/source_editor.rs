@@ -0,0 +1,113 @@
+use eframe::egui::{self, Color32, Ui};
+
+use crate::{JobApp, SourceEdit};
+use jobtracker_core::{JobSource, Source};
+
+impl JobApp {
+    pub(super) fn add_source_controls(&mut self, ui: &mut Ui) {
+        ui.collapsing("Sources", |ui| {
+            let Some(edits) = &mut self.source_edits else {
+                for source in &self.store.sources {
+                    ui.horizontal(|ui| {
+                        ui.label(source.name.to_string());
+                        if !source.aliases.is_empty() {
+                            ui.weak(format!("also {}", source.aliases.join(", ")));
+                        }
+                        if source.referral {
+                            ui.weak("(referral)");
+                        }
+                    });
+                }
+                if ui.button("Edit sources").clicked() {
+                    self.source_edits = Some(
+                        self.store
+                            .sources
+                            .iter()
+                            .map(|source| SourceEdit {
+                                original: Some(source.name.clone()),
+                                name: source.name.to_string(),
+                                aliases: source.aliases.join(", "),
+                                referral: source.referral,
+                            })
+                            .collect(),
+                    );
+                    self.source_message = None;
+                }
+                return;
+            };
+
+            let mut move_up = None;
+            let mut remove = None;
+            egui::Grid::new("source_edits").show(ui, |ui| {
+                ui.label("Name");
+                ui.label("Also called");
+                ui.label("Referral");
+                ui.end_row();
+                for (idx, source) in edits.iter_mut().enumerate() {
+                    ui.text_edit_singleline(&mut source.name);
+                    ui.text_edit_singleline(&mut source.aliases);
+                    ui.checkbox(&mut source.referral, "");
+                    if ui.add_enabled(idx > 0, egui::Button::new("Up")).clicked() {
+                        move_up = Some(idx);
+                    }
+                    if ui.button("Remove").clicked() {
+                        remove = Some(idx);
+                    }
+                    ui.end_row();
+                }
+            });
+            if let Some(idx) = move_up {
+                edits.swap(idx - 1, idx);
+            }
+            if let Some(idx) = remove {
+                edits.remove(idx);
+            }
+
+            let (mut save, mut cancel) = (false, false);
+            ui.horizontal(|ui| {
+                if ui.button("Add source").clicked() {
+                    edits.push(SourceEdit::default());
+                }
+                save = ui.button("Save").clicked();
+                cancel = ui.button("Cancel").clicked();
+            });
+            if save {
+                let renamed: Vec<(JobSource, JobSource)> = edits
+                    .iter()
+                    .filter_map(|edit| {
+                        let original = edit.original.clone()?;
+                        Some((original, JobSource::new(edit.name.trim())))
+                    })
+                    .collect();
+                let sources: Vec<Source> = edits
+                    .iter()
+                    .map(|edit| Source {
+                        name: JobSource::new(edit.name.trim()),
+                        aliases: edit
+                            .aliases
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|alias| !alias.is_empty())
+                            .map(str::to_string)
+                            .collect(),
+                        referral: edit.referral,
+                    })
+                    .collect();
+                match self.store.set_sources(sources, &renamed) {
+                    Ok(()) => {
+                        self.source_edits = None;
+                        self.source_message = None;
+                    }
+                    Err(e) => self.source_message = Some(format!("{e:#}")),
+                }
+            }
+            if cancel {
+                self.source_edits = None;
+                self.source_message = None;
+            }
+            if let Some(message) = &self.source_message {
+                ui.colored_label(Color32::RED, message);
+            }
+        });
+    }
+}
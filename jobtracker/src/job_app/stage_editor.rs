@@ -0,0 +1,199 @@
+use eframe::egui::{self, Color32, Ui};
+
+use crate::{JobApp, StageEdit, StatusColor};
+use jobtracker_core::{JobStatus, Stage, StageOutcome};
+use strum::IntoEnumIterator;
+
+impl StageEdit {
+    /// Identifies the stage while its name is being edited.
+    fn key(&self) -> JobStatus {
+        self.original
+            .clone()
+            .unwrap_or_else(|| JobStatus::new(self.name.trim()))
+    }
+}
+
+impl JobApp {
+    pub(super) fn add_stage_controls(&mut self, ui: &mut Ui) {
+        ui.collapsing("Pipeline stages", |ui| {
+            let Some(edits) = &mut self.stage_edits else {
+                for stage in &self.store.stages {
+                    ui.horizontal(|ui| {
+                        ui.painter().rect_filled(
+                            egui::Rect::from_min_size(ui.cursor().min, egui::vec2(12.0, 12.0)),
+                            2.0,
+                            stage.name.get_status_color_mapping(&self.store.stages),
+                        );
+                        ui.add_space(16.0);
+                        ui.label(stage.name.to_string());
+                        if stage.terminal {
+                            ui.weak("(final)");
+                        }
+                        if let Some(outcome) = stage.outcome {
+                            ui.weak(outcome.to_string());
+                        }
+                    });
+                }
+                if ui.button("Edit stages").clicked() {
+                    let stages = &self.store.stages;
+                    self.stage_edits = Some(
+                        stages
+                            .iter()
+                            .map(|stage| StageEdit {
+                                original: Some(stage.name.clone()),
+                                name: stage.name.to_string(),
+                                color: stage.name.get_status_color_mapping(stages),
+                                terminal: stage.terminal,
+                                outcome: stage.outcome,
+                                next: stage.next.clone(),
+                            })
+                            .collect(),
+                    );
+                    self.stage_message = None;
+                }
+                return;
+            };
+
+            let targets: Vec<(JobStatus, String)> = edits
+                .iter()
+                .map(|edit| (edit.key(), edit.name.clone()))
+                .collect();
+            let mut move_up = None;
+            let mut remove = None;
+            egui::Grid::new("stage_edits").show(ui, |ui| {
+                ui.label("Name");
+                ui.label("Color");
+                ui.label("Final");
+                ui.label("Outcome");
+                ui.label("Moves to");
+                ui.end_row();
+                for (idx, stage) in edits.iter_mut().enumerate() {
+                    ui.text_edit_singleline(&mut stage.name);
+                    ui.color_edit_button_srgba(&mut stage.color);
+                    ui.checkbox(&mut stage.terminal, "");
+                    egui::ComboBox::from_id_source(format!("stage_outcome_{idx}"))
+                        .selected_text(stage.outcome.map_or("None".to_string(), |o| o.to_string()))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut stage.outcome, None, "None");
+                            for outcome in StageOutcome::iter() {
+                                ui.selectable_value(
+                                    &mut stage.outcome,
+                                    Some(outcome),
+                                    outcome.to_string(),
+                                );
+                            }
+                        });
+                    let moves_to = match &stage.next {
+                        None => "Any later".to_string(),
+                        Some(next) if next.is_empty() => "Nowhere".to_string(),
+                        Some(next) => format!("{} stage(s)", next.len()),
+                    };
+                    ui.menu_button(moves_to, |ui| {
+                        let mut default_rule = stage.next.is_none();
+                        if ui
+                            .checkbox(&mut default_rule, "Default: any later or final stage")
+                            .changed()
+                        {
+                            stage.next = if default_rule { None } else { Some(Vec::new()) };
+                        }
+                        if let Some(next) = &mut stage.next {
+                            ui.separator();
+                            let own_key = stage.original.clone();
+                            for (key, name) in &targets {
+                                if own_key.as_ref() == Some(key) {
+                                    continue;
+                                }
+                                let mut allowed = next.contains(key);
+                                if ui.checkbox(&mut allowed, name).changed() {
+                                    if allowed {
+                                        next.push(key.clone());
+                                    } else {
+                                        next.retain(|n| n != key);
+                                    }
+                                }
+                            }
+                        }
+                    });
+                    if ui.add_enabled(idx > 0, egui::Button::new("Up")).clicked() {
+                        move_up = Some(idx);
+                    }
+                    if ui.button("Remove").clicked() {
+                        remove = Some(idx);
+                    }
+                    ui.end_row();
+                }
+            });
+            if let Some(idx) = move_up {
+                edits.swap(idx - 1, idx);
+            }
+            if let Some(idx) = remove {
+                edits.remove(idx);
+            }
+
+            let (mut save, mut cancel) = (false, false);
+            ui.horizontal(|ui| {
+                if ui.button("Add stage").clicked() {
+                    edits.push(StageEdit {
+                        original: None,
+                        name: String::new(),
+                        color: Color32::GRAY,
+                        terminal: false,
+                        outcome: None,
+                        next: None,
+                    });
+                }
+                save = ui.button("Save").clicked();
+                cancel = ui.button("Cancel").clicked();
+            });
+            if save {
+                let renamed: Vec<(JobStatus, JobStatus)> = edits
+                    .iter()
+                    .filter_map(|edit| {
+                        let original = edit.original.clone()?;
+                        Some((original, JobStatus::new(edit.name.trim())))
+                    })
+                    .collect();
+                let rename = |status: &JobStatus| {
+                    renamed
+                        .iter()
+                        .find(|(old, _)| old == status)
+                        .map_or_else(|| status.clone(), |(_, new)| new.clone())
+                };
+                let stages: Vec<Stage> = edits
+                    .iter()
+                    .enumerate()
+                    .map(|(order, edit)| Stage {
+                        name: JobStatus::new(edit.name.trim()),
+                        order: order as u32,
+                        color: format!(
+                            "#{:02x}{:02x}{:02x}",
+                            edit.color.r(),
+                            edit.color.g(),
+                            edit.color.b()
+                        ),
+                        terminal: edit.terminal,
+                        outcome: edit.outcome,
+                        next: edit
+                            .next
+                            .as_ref()
+                            .map(|next| next.iter().map(rename).collect()),
+                    })
+                    .collect();
+                match self.store.set_stages(stages, &renamed) {
+                    Ok(()) => {
+                        self.stage_edits = None;
+                        self.stage_message = None;
+                    }
+                    Err(e) => self.stage_message = Some(format!("{e:#}")),
+                }
+            }
+            if cancel {
+                self.stage_edits = None;
+                self.stage_message = None;
+            }
+            if let Some(message) = &self.stage_message {
+                ui.colored_label(Color32::RED, message);
+            }
+        });
+    }
+}
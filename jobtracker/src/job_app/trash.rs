@@ -0,0 +1,91 @@
+use eframe::egui::{self, Color32, Ui};
+
+use crate::{JobApp, PendingConfirmation};
+use jobtracker_core::Job;
+
+impl JobApp {
+    pub(super) fn add_trash_controls(&mut self, ui: &mut Ui) {
+        let trashed: Vec<Job> = self.store.trashed_jobs().into_iter().cloned().collect();
+        ui.collapsing(format!("Trash ({})", trashed.len()), |ui| {
+            if trashed.is_empty() {
+                ui.label("Trash is empty");
+            }
+            for job in trashed {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} - {}", job.company, job.role));
+                    if ui.button("Restore").clicked() {
+                        let result = self.store.restore_job(job.id);
+                        self.trash_message = result.err().map(|e| format!("{e:#}"));
+                    }
+                    if ui.button("Delete forever").clicked() {
+                        self.pending_confirmation = Some(PendingConfirmation::Purge(job.id));
+                    }
+                });
+            }
+            if let Some(message) = &self.trash_message {
+                ui.colored_label(Color32::RED, message);
+            }
+        });
+    }
+
+    /// Modal-style window asking the user to confirm a destructive action.
+    pub(super) fn add_confirmation_dialog(&mut self, ctx: &egui::Context) {
+        let Some(pending) = self.pending_confirmation.clone() else {
+            return;
+        };
+        let (PendingConfirmation::Trash(id) | PendingConfirmation::Purge(id)) = pending;
+        let Some(job) = self.store.jobs.iter().find(|job| job.id == id) else {
+            self.pending_confirmation = None;
+            return;
+        };
+        let (title, message, confirm) = match pending {
+            PendingConfirmation::Trash(_) => (
+                "Delete application?",
+                format!(
+                    "Move {} - {} to the trash? It can be restored from there.",
+                    job.company, job.role
+                ),
+                "Move to trash",
+            ),
+            PendingConfirmation::Purge(_) => (
+                "Delete forever?",
+                format!(
+                    "Permanently delete {} - {}? It can't be restored from the trash, only brought back with Undo.",
+                    job.company, job.role
+                ),
+                "Delete forever",
+            ),
+        };
+
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(message);
+                ui.horizontal(|ui| {
+                    if ui.button(confirm).clicked() {
+                        match pending {
+                            PendingConfirmation::Trash(id) => {
+                                let result = self.store.delete_job(id);
+                                self.list_message = result.err().map(|e| format!("{e:#}"));
+                            }
+                            PendingConfirmation::Purge(id) => {
+                                let result = self.store.purge_job(id);
+                                self.trash_message = result.err().map(|e| format!("{e:#}"));
+                            }
+                        }
+                        if self.detail_job == Some(id)
+                            && !self.store.active_jobs().any(|j| j.id == id)
+                        {
+                            self.detail_job = None;
+                        }
+                        self.pending_confirmation = None;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.pending_confirmation = None;
+                    }
+                });
+            });
+    }
+}
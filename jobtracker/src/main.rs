@@ -1,8 +1,252 @@
-use chrono::Utc;
-use eframe::egui::ViewportBuilder;
-use jobtracker_core::{JobApp, APP_NAME, WINDOW_HEIGHT, WINDOW_WIDTH};
+mod job_app;
+mod status_color;
+use chrono::{DateTime, Utc};
+use clap::Parser;
+use eframe::egui::{Color32, ViewportBuilder};
+use jobtracker_core::{
+    Config, Contact, CsvColumns, ImportReport, Job, JobSource, JobStatus, JobStore, PayPeriod,
+    Stage, StageOutcome, WorkArrangement, APP_NAME,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub const WINDOW_WIDTH: f32 = 1200.0;
+pub const WINDOW_HEIGHT: f32 = 800.0;
+
+const GRAY: Color32 = Color32::from_rgb(128, 128, 128);
+
+/// Representation of the application itself.
+#[derive(Default)]
+pub struct JobApp {
+    /// Internal datastore of all job applications so far.
+    pub store: JobStore,
+    /// Input element in form.
+    pub new_company: String,
+    /// Input element in form.
+    pub new_role: String,
+    /// Input element in form.
+    pub new_role_location: String,
+    /// Input element in form
+    pub new_source: String,
+    /// Input element in form
+    pub search_text: String,
+    /// Input element in form: saved job posting page to fill the form from.
+    pub posting_path: String,
+    /// Application read from `posting_path`, waiting for the user to check
+    /// the form and add it. Holds what the form has no fields for, like the
+    /// salary range and description.
+    pub posting_draft: Option<Job>,
+    /// Outcome of the last posting import.
+    pub posting_import_message: Option<String>,
+    /// The set of timestamps the user has edited in the form.
+    pub edit_timestamps: HashMap<u32, String>,
+    /// The set of company names the user has edited in the form.
+    pub edit_companies: HashMap<u32, String>,
+    /// Tags the user is editing in the form, comma-separated, by job ID.
+    pub edit_tags: HashMap<u32, String>,
+    /// Only list applications with all of these tags.
+    pub tag_filter: Vec<String>,
+    /// Only list applications worked this way.
+    pub arrangement_filter: Option<WorkArrangement>,
+    /// Only list applications in this area, see `Location::area`.
+    pub area_filter: Option<String>,
+    /// Last time the stored applications were successfully read.
+    pub last_refresh: DateTime<Utc>,
+    /// Why the last refresh couldn't read the data file.
+    pub refresh_message: Option<String>,
+    /// Something to point out about the data file that was picked, e.g. a
+    /// data file from an older version left behind.
+    pub data_file_warning: Option<String>,
+    /// Outcome of the last backup restore.
+    pub backup_message: Option<String>,
+    /// Tracks which chart entry the user's currently selected. This is used for
+    /// highlighting and filtering for a specific job application through the stacked
+    /// bar chart.
+    pub selected_company: Option<String>,
+    /// Why the data file couldn't be read at startup, if it couldn't. The
+    /// tracker refuses to edit anything while this is set so it never
+    /// overwrites a file it doesn't understand.
+    pub load_error: Option<String>,
+    /// ID of the job application shown in the details panel, if any.
+    pub detail_job: Option<u32>,
+    /// Input element in the details panel: the status to move to next.
+    pub detail_status: Option<JobStatus>,
+    /// Input element in the details panel: note for the next status change.
+    pub detail_note: String,
+    /// Input element in the details panel: skip the pipeline's transition
+    /// rules to correct a mistake.
+    pub detail_correction: bool,
+    /// Why the last status change or undone automatic change in the
+    /// details panel couldn't be saved.
+    pub detail_message: Option<String>,
+    /// Input element in the details panel: text of the next note.
+    pub new_note: String,
+    /// Note being edited in the details panel, by ID, with its new text.
+    pub editing_note: Option<(u32, String)>,
+    /// Why the last note edit couldn't be saved.
+    pub note_message: Option<String>,
+    /// Input element: file to import CSV from or export it to.
+    pub csv_path: String,
+    /// Input element: CSV column names for each job field.
+    pub csv_columns: CsvColumns,
+    /// Result of the last CSV import or import preview.
+    pub csv_report: Option<ImportReport>,
+    /// Outcome of the last CSV action, shown under its buttons.
+    pub csv_message: Option<String>,
+    /// Destructive action waiting on the user to confirm it.
+    pub pending_confirmation: Option<PendingConfirmation>,
+    /// Why the last edit made in the job list couldn't be saved.
+    pub list_message: Option<String>,
+    /// Why the last restore or permanent delete from the trash couldn't be
+    /// saved.
+    pub trash_message: Option<String>,
+    /// Why the last ghosting run or undone run couldn't be saved.
+    pub ghosting_message: Option<String>,
+    /// Outcome of the last undo or redo, shown next to their buttons.
+    pub undo_message: Option<String>,
+    /// Contact being added (ID 0) or edited while the contact form is open.
+    pub contact_form: Option<Contact>,
+    /// Input element: vCard file to import contacts from or export them to.
+    pub vcf_path: String,
+    /// Outcome of the last contact action, shown under the contact list.
+    pub contact_message: Option<String>,
+    /// Input elements in the details panel for the next interview.
+    pub interview_form: InterviewForm,
+    /// Input element: iCalendar file to import interviews from or export
+    /// them to.
+    pub ics_path: String,
+    /// Outcome of the last interview action.
+    pub interview_message: Option<String>,
+    /// Location being edited in the details panel.
+    pub location_form: Option<LocationForm>,
+    /// Why the last location edit couldn't be saved.
+    pub location_message: Option<String>,
+    /// Posting URL and description being edited in the details panel.
+    pub posting_edit: Option<(String, String)>,
+    /// Why the last posting edit couldn't be saved.
+    pub posting_message: Option<String>,
+    /// Input elements in the details panel for the salary range and offer.
+    pub compensation_form: CompensationForm,
+    /// Why the last compensation edit couldn't be saved.
+    pub compensation_message: Option<String>,
+    /// Whether the offer comparison window is open.
+    pub show_offer_comparison: bool,
+    /// Stages in the pipeline editor, in order, while it's open.
+    pub stage_edits: Option<Vec<StageEdit>>,
+    /// Why the last pipeline edit couldn't be saved.
+    pub stage_message: Option<String>,
+    /// Source typed in the add form that isn't on the list yet, waiting for
+    /// the user to add it as a new source or change the form.
+    pub unknown_source: Option<String>,
+    /// Sources in the source editor, in order, while it's open.
+    pub source_edits: Option<Vec<SourceEdit>>,
+    /// Why the last source edit couldn't be saved.
+    pub source_message: Option<String>,
+    /// Whether the pipeline flow window is open.
+    pub show_pipeline_flow: bool,
+    /// Only list these applications, picked in the pipeline flow window,
+    /// with a description of what was picked.
+    pub flow_filter: Option<(String, Vec<u32>)>,
+}
+
+/// A stage as edited in the pipeline editor.
+#[derive(Debug, Clone)]
+pub struct StageEdit {
+    /// Name of the stage when editing began, `None` for a new stage.
+    pub original: Option<JobStatus>,
+    pub name: String,
+    pub color: Color32,
+    pub terminal: bool,
+    pub outcome: Option<StageOutcome>,
+    /// Stages this one may move to, by their names when editing began.
+    /// `None` keeps the default rule.
+    pub next: Option<Vec<JobStatus>>,
+}
+
+/// A source as edited in the source editor.
+#[derive(Debug, Clone, Default)]
+pub struct SourceEdit {
+    /// Name of the source when editing began, `None` for a new source.
+    pub original: Option<JobSource>,
+    pub name: String,
+    /// Comma-separated.
+    pub aliases: String,
+    pub referral: bool,
+}
+
+/// Interview being scheduled in the details panel, as typed.
+#[derive(Debug, Clone, Default)]
+pub struct InterviewForm {
+    pub round: String,
+    /// Local time in `timezone`, `YYYY-MM-DD HH:MM`.
+    pub start: String,
+    pub minutes: String,
+    /// IANA name such as `Europe/Berlin`. Empty means this computer's time
+    /// zone.
+    pub timezone: String,
+    /// Comma-separated names.
+    pub interviewers: String,
+    pub location: String,
+}
+
+/// Location of the job in the details panel, as typed: the free text and
+/// how it was read, which can be corrected by hand.
+#[derive(Debug, Clone, Default)]
+pub struct LocationForm {
+    pub text: String,
+    pub city: String,
+    pub region: String,
+    pub country: String,
+    pub arrangement: Option<WorkArrangement>,
+}
+
+/// Salary range and offer of the job in the details panel, as typed.
+/// Blank amounts leave the salary range or offer unset.
+#[derive(Debug, Clone, Default)]
+pub struct CompensationForm {
+    pub range_min: String,
+    pub range_max: String,
+    pub range_currency: String,
+    pub range_period: PayPeriod,
+    pub base: String,
+    pub period: PayPeriod,
+    pub bonus: String,
+    pub equity: String,
+    pub vesting_years: String,
+    pub currency: String,
+    pub benefits: String,
+    /// `YYYY-MM-DD`.
+    pub deadline: String,
+}
+
+/// Actions that ask for confirmation before they run, with the ID of the
+/// job they apply to.
+#[derive(Debug, Clone)]
+pub enum PendingConfirmation {
+    /// Move the job to the trash.
+    Trash(u32),
+    /// Permanently delete the job.
+    Purge(u32),
+}
+
+/// Chart and legend colors for job application statuses, as set by the
+/// stage definitions.
+pub trait StatusColor {
+    fn get_status_color_mapping(&self, stages: &[Stage]) -> Color32;
+}
+
+/// Desktop tracker for job applications.
+#[derive(Parser)]
+struct Args {
+    /// Data file to open. Overrides the JOBTRACKER_DATA environment variable
+    /// and the config file. Files ending in .db, .sqlite or .sqlite3 are
+    /// opened as SQLite databases, anything else as JSON.
+    #[arg(long)]
+    data_file: Option<PathBuf>,
+}
 
 fn main() -> eframe::Result<()> {
+    let args = Args::parse();
     let options = eframe::NativeOptions {
         viewport: ViewportBuilder::default()
             .with_inner_size([WINDOW_WIDTH, WINDOW_HEIGHT])
@@ -14,6 +258,15 @@ fn main() -> eframe::Result<()> {
         last_refresh: Utc::now(),
         ..Default::default()
     };
-    job_app.store.load_from_file().unwrap();
+    match Config::load().and_then(|config| config.open_store(args.data_file)) {
+        Ok((store, warning)) => {
+            job_app.store = store;
+            job_app.data_file_warning = warning;
+            if let Err(e) = job_app.store.load() {
+                job_app.load_error = Some(format!("{e:#}"));
+            }
+        }
+        Err(e) => job_app.load_error = Some(format!("{e:#}")),
+    }
     eframe::run_native(APP_NAME, options, Box::new(|_cc| Ok(Box::new(job_app))))
 }
@@ -0,0 +1,21 @@
+use crate::{StatusColor, GRAY};
+use eframe::egui::Color32;
+use jobtracker_core::{Job, JobStatus, Stage};
+
+/// The color of the stage `self` names, or gray if that stage is no longer
+/// defined.
+impl StatusColor for JobStatus {
+    fn get_status_color_mapping(&self, stages: &[Stage]) -> Color32 {
+        stages
+            .iter()
+            .find(|stage| &stage.name == self)
+            .and_then(Stage::rgb)
+            .map_or(GRAY, |[r, g, b]| Color32::from_rgb(r, g, b))
+    }
+}
+
+impl StatusColor for Job {
+    fn get_status_color_mapping(&self, stages: &[Stage]) -> Color32 {
+        self.status.get_status_color_mapping(stages)
+    }
+}